@@ -70,6 +70,7 @@ pub fn args_main(args: &[String]) -> i32 {
 
     // Write deduped array back so help text reflects the final state
     shell::write_array("args", &args_arr);
+    field::capture_defaults(&args_arr);
 
     // Validate args array is pairs (REVIEW finding 4: use % for Rust <1.87 compat)
     #[allow(clippy::manual_is_multiple_of)]
@@ -81,87 +82,105 @@ pub fn args_main(args: &[String]) -> i32 {
     if cli_args.first().map(|s| s.as_str()) == Some("-h")
         || cli_args.first().map(|s| s.as_str()) == Some("--help")
     {
-        args_help_text(title, &args_arr);
+        args_help_text(title, &args_arr, 0);
         return shared::HELP_EXIT;
     }
 
-    // Parse CLI arguments
+    // Parse CLI arguments; positionals are collected and assigned afterwards
+    // so a variadic positional can leave room for the ones after it
     let mut cli: Vec<String> = cli_args.to_vec();
-    let mut positional_index: usize = 1;
+    let mut positionals: Vec<String> = Vec::new();
     let mut matched: Vec<String> = Vec::new();
-    let mut first_array = false;
+
+    // Opt-in GNU-style abbreviation of long flags (`--verb` for `--verbose`),
+    // enabled by a `local ARGSH_FLAG_PREFIX=1` in the script
+    let prefix = matches!(shell::get_local_scalar("ARGSH_FLAG_PREFIX").as_deref(), Some("1" | "true"));
+    let mode = match parse_mode() {
+        Ok(mode) => mode,
+        Err(msg) => return shared::error_usage("ARGSH_PARSE_MODE", &msg),
+    };
+    let mut rest: Option<Vec<String>> = None;
 
     // idx stays 0: we always process the front element; cli.remove(0) shifts the rest down
     let idx = 0;
     while idx < cli.len() {
-        // Positional argument
-        if !cli[idx].starts_with('-') {
-            let pos_idx = match field::field_positional(positional_index, &args_arr) {
-                Some(i) => i,
-                None => {
-                    return shared::error_usage("", &format!("too many arguments: {}", cli[idx]));
-                }
-            };
-
-            let field_str = &args_arr[pos_idx];
-            let name = field::field_name(field_str, true);
-            let def = match field::parse_field(field_str) {
-                Ok(d) => d,
-                Err(msg) => return shared::error_usage(field_str, &msg),
-            };
-
-            // Type convert
-            let value = match field::convert_type(&def.type_name, &cli[idx], &name) {
-                Ok(v) => v,
-                Err(msg) => {
-                    return shared::error_usage(field_str, &msg);
-                }
-            };
+        // `--` ends flag parsing; what follows is kept verbatim
+        if cli[idx] == "--" {
+            cli.remove(idx);
+            rest = Some(std::mem::take(&mut cli));
+            break;
+        }
 
-            if shell::is_array(&name) {
-                if !first_array {
-                    // Clear array on first assignment
-                    shell::write_array(&name, &[]);
-                    first_array = true;
-                }
-                shell::array_append(&name, &value);
-            } else {
-                shell::set_scalar(&name, &value);
+        // Positional argument; in POSIX mode it also ends flag parsing
+        if !cli[idx].starts_with('-') {
+            positionals.push(cli.remove(idx));
+            if mode == ParseMode::Posix {
+                positionals.append(&mut cli);
             }
-
-            cli.remove(idx);
-            positional_index += 1;
             continue;
         }
 
         // Handle -h/--help at any position (but only as a standalone flag,
         // not when consumed as a value by a preceding flag)
         if cli[idx] == "-h" || cli[idx] == "--help" {
-            args_help_text(title, &args_arr);
+            args_help_text(title, &args_arr, positionals.len());
             return shared::HELP_EXIT;
         }
 
         // Flag argument -- set_bool for :args just sets scalar "1"
-        match shared::parse_flag_at(&mut cli, idx, &args_arr, &mut matched, |name| {
-            shell::set_scalar(name, "1");
-        }) {
+        match shared::parse_flag_at(
+            &mut cli,
+            idx,
+            &args_arr,
+            &mut matched,
+            |name| shell::set_scalar(name, "1"),
+            prefix,
+        ) {
             Ok(true) => {
                 // idx stays same since parse_flag_at modifies cli
             }
             Ok(false) => {
-                return shared::error_usage("", &format!("unknown flag: {}", cli[idx]));
+                let mut msg = format!("unknown flag: {}", cli[idx]);
+                let suggestion = shared::suggest_flag(&cli[idx], &args_arr);
+                if let Some(ref suggestion) = suggestion {
+                    msg.push_str(&format!(". Did you mean '{}'?", suggestion));
+                }
+                return shared::error_suggest(
+                    shared::ErrorKind::UnknownFlag,
+                    "",
+                    &cli[idx],
+                    &msg,
+                    suggestion.as_deref(),
+                );
             }
             Err(code) => return code,
         }
     }
 
-    // Check if next expected positional is required
-    if let Some(pos_idx) = field::field_positional(positional_index, &args_arr) {
-        let field_str = &args_arr[pos_idx];
-        let name = field::field_name(field_str, true);
-        if shell::is_uninitialized(&name) && !shell::is_array(&name) {
-            return shared::error_usage(&name, &format!("missing required argument: {}", name));
+    // Flags absent from the command line take their :$VAR environment variable
+    let ret = shared::apply_env_flags(&args_arr, &mut matched);
+    if ret != 0 {
+        return ret;
+    }
+
+    // Values after `--` go to the `rest` field when args declares one,
+    // otherwise they are positionals like any other
+    let ret = match (rest, rest_field(&args_arr)) {
+        (Some(values), Some(i)) => {
+            let mut fields = args_arr.clone();
+            let rest_def: Vec<String> = fields.drain(i..i + 2).collect();
+            match assign_positionals(&fields, &positionals) {
+                0 => assign_positionals(&rest_def, &values),
+                code => code,
+            }
         }
+        (values, _) => {
+            positionals.extend(values.unwrap_or_default());
+            assign_positionals(&args_arr, &positionals)
+        }
+    };
+    if ret != 0 {
+        return ret;
     }
 
     // Check required flags and set boolean defaults
@@ -175,14 +194,225 @@ pub fn args_main(args: &[String]) -> i32 {
         return shared::error_usage("", &format!("too many arguments: {}", cli.join(" "))); // coverage:off
     } // coverage:off
 
+    // User validation hooks, now that every field is set
+    run_validators(&args_arr)
+}
+
+/// Call the caller's validation hooks: `<func>::validate::<name>` for each
+/// field that is set, with its value(s) as arguments, then the cross-field
+/// `<func>::validate`. A hook rejects by returning non-zero; what it wrote
+/// to stderr becomes the error message.
+fn run_validators(args_arr: &[String]) -> i32 {
+    let Some(caller) = shell::get_funcname(0) else {
+        return 0; // coverage:off - :args is always called from a function
+    };
+
+    for i in (0..args_arr.len()).step_by(2) {
+        let spec = &args_arr[i];
+        if spec == "-" {
+            continue;
+        }
+        let name = field::field_name(spec, true);
+        let hook = format!("{}::validate::{}", caller, name);
+        if !shell::function_exists(&hook) {
+            continue;
+        }
+        let Some(values) = field_values(&name) else {
+            continue;
+        };
+        let (status, msg) = shell::call_capture_stderr(&hook, &values);
+        if status != 0 {
+            let value = values.join(" ");
+            let msg = if msg.is_empty() {
+                format!("invalid value for {}: {}", field::field_name(spec, false), value)
+            } else {
+                msg
+            };
+            return shared::error_kind(shared::ErrorKind::InvalidValue, spec, &value, &msg);
+        }
+    }
+
+    let hook = format!("{}::validate", caller);
+    if shell::function_exists(&hook) {
+        let (status, msg) = shell::call_capture_stderr(&hook, &[]);
+        if status != 0 {
+            return shared::error_usage("", if msg.is_empty() { "validation failed" } else { &msg });
+        }
+    }
     0 // EXECUTION_SUCCESS
 }
 
-/// Print :args help text.
-fn args_help_text(title: &str, args_arr: &[String]) {
+/// Current value(s) of a field's variable: the elements of an array, the
+/// `key=value` pairs of a map, or the scalar. None when it is unset.
+fn field_values(name: &str) -> Option<Vec<String>> {
+    if shell::is_array(name) {
+        return Some(shell::read_array(name));
+    }
+    if shell::is_assoc(name) {
+        let pairs = shell::get_assoc_keys(name)
+            .into_iter()
+            .map(|k| {
+                let v = shell::assoc_get(name, &k).unwrap_or_default();
+                format!("{}={}", k, v)
+            })
+            .collect();
+        return Some(pairs);
+    }
+    if shell::is_uninitialized(name) {
+        return None;
+    }
+    shell::get_scalar(name).map(|v| vec![v])
+}
+
+/// How flags and positionals may be mixed on the command line.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ParseMode {
+    /// Flags may appear anywhere, before or after positionals.
+    Gnu,
+    /// The first positional ends flag parsing.
+    Posix,
+}
+
+/// Read the parse mode from a script-local `ARGSH_PARSE_MODE` (`gnu` or
+/// `posix`); GNU when it is unset.
+fn parse_mode() -> Result<ParseMode, String> {
+    match shell::get_local_scalar("ARGSH_PARSE_MODE").as_deref() {
+        None | Some("" | "gnu") => Ok(ParseMode::Gnu),
+        Some("posix") => Ok(ParseMode::Posix),
+        Some(other) => Err(format!("invalid ARGSH_PARSE_MODE: {} (expected gnu or posix)", other)),
+    }
+}
+
+/// Index of the positional `rest` field, if args declares one; it receives
+/// the values after `--`.
+fn rest_field(args_arr: &[String]) -> Option<usize> {
+    field::positional_fields(args_arr)
+        .into_iter()
+        .find(|&i| field::field_name(&args_arr[i], true) == "rest")
+}
+
+/// Distribute positional values over the positional fields.
+///
+/// Fixed positionals take one value each, in order. An array-backed
+/// (variadic) positional takes everything except the values needed by the
+/// fixed positionals after it. Without a variadic the historical messages
+/// are kept: "too many arguments" and "missing required argument" for the
+/// next unfilled positional.
+fn assign_positionals(args_arr: &[String], values: &[String]) -> i32 {
+    let mut fields: Vec<(&String, field::FieldDef)> = Vec::new();
+    for i in field::positional_fields(args_arr) {
+        match field::parse_field(&args_arr[i]) {
+            Ok(def) => fields.push((&args_arr[i], def)),
+            Err(msg) => return shared::error_usage(&args_arr[i], &msg),
+        }
+    }
+
+    let variadic: Vec<usize> = (0..fields.len()).filter(|&k| fields[k].1.is_array).collect();
+    if let [_, second, ..] = variadic[..] {
+        return shared::error_usage(fields[second].0, "only one variadic positional is allowed");
+    }
+    let variadic = variadic.first().copied();
+    let is_required = |def: &field::FieldDef| {
+        if def.is_array {
+            def.required
+        } else {
+            !def.is_optional && !def.has_default
+        }
+    };
+    // Values held back for the fixed positionals after the variadic one
+    // (and one for the variadic itself when it is `:!`)
+    let reserved = variadic.map_or(0, |v| fields.len() - v - 1);
+    let variadic_limit = values.len().saturating_sub(reserved);
+    let before_limit = variadic_limit.saturating_sub(variadic.map_or(0, |v| usize::from(fields[v].1.required)));
+
+    let mut next = 0;
+    let mut unfilled: Option<usize> = None;
+    let mut missing: Option<usize> = None;
+    for (k, (field_str, def)) in fields.iter().enumerate() {
+        let take = match variadic {
+            Some(v) if k == v => variadic_limit.saturating_sub(next),
+            Some(v) if k < v => usize::from(next < before_limit),
+            _ => usize::from(next < values.len()),
+        };
+        if take == 0 {
+            if unfilled.is_none() && !def.is_array {
+                unfilled = Some(k);
+            }
+            if missing.is_none() && is_required(def) {
+                missing = Some(k);
+            }
+            continue;
+        }
+
+        let mut converted = Vec::with_capacity(take);
+        for value in &values[next..next + take] {
+            match field::convert_value(def, value) {
+                Ok(v) => converted.push(v),
+                Err(msg) => {
+                    let suggestion = field::suggest_choice(def, value);
+                    return shared::error_suggest(
+                        shared::ErrorKind::InvalidValue,
+                        field_str,
+                        value,
+                        &msg,
+                        suggestion.as_deref(),
+                    );
+                }
+            }
+        }
+        next += take;
+
+        if def.is_array {
+            shell::write_array(&def.name, &converted);
+        } else {
+            shell::set_scalar(&def.name, &converted[0]);
+        }
+    }
+
+    if next < values.len() {
+        return shared::error_usage("", &format!("too many arguments: {}", values[next]));
+    }
+
+    if variadic.is_some() {
+        let minimum = fields.iter().filter(|(_, def)| is_required(def)).count();
+        if values.len() < minimum {
+            return shared::error_kind(
+                shared::ErrorKind::MissingRequired,
+                "",
+                "",
+                &format!(
+                    "expected at least {} argument{}, got {}",
+                    minimum,
+                    if minimum == 1 { "" } else { "s" },
+                    values.len()
+                ),
+            );
+        }
+    } else {
+        // Only the next expected positional is checked, as before
+        missing = unfilled.filter(|&k| is_required(&fields[k].1));
+    }
+    if let Some(k) = missing {
+        let name = &fields[k].1.name;
+        return shared::error_kind(
+            shared::ErrorKind::MissingRequired,
+            fields[k].0,
+            "",
+            &format!("missing required argument: {}", name),
+        );
+    }
+
+    0
+}
+
+/// Print :args help text. The first `given` positionals were already on the
+/// command line and show as set (`[name]`), as in the pure-bash `:args`,
+/// which assigns positionals as it reads them.
+fn args_help_text(title: &str, args_arr: &[String], mut given: usize) {
     let out = std::io::stdout();
     let mut out = out.lock();
     let fw = shell::get_field_width();
+    let style = crate::usage::style::Style::detect();
     let commandname = shell::get_commandname();
     let cmdname_str = commandname.join(" ");
 
@@ -197,15 +427,23 @@ fn args_help_text(title: &str, args_arr: &[String]) {
 
     for i in (0..args_arr.len()).step_by(2) {
         let entry = &args_arr[i];
-        if entry.contains('|') || entry == "-" {
+        if field::is_flag_spec(entry) || entry == "-" {
             continue;
         }
         let name = field::field_name(entry, true);
         positional_indices.push(i);
+        let (optional, required) = field::parse_field(entry)
+            .map_or((false, false), |def| (def.is_optional, def.required));
+        let is_set = given > 0 && !shell::is_array(&name);
+        if is_set {
+            given -= 1;
+        }
 
-        if shell::is_array(&name) {
+        if shell::is_array(&name) && required {
+            params.push(format!("<{}>...", name));
+        } else if shell::is_array(&name) {
             params.push(format!("...{}", name));
-        } else if !shell::is_uninitialized(&name) {
+        } else if optional || is_set || !shell::is_uninitialized(&name) {
             params.push(format!("[{}]", name));
         } else {
             params.push(format!("<{}>", name));
@@ -214,13 +452,13 @@ fn args_help_text(title: &str, args_arr: &[String]) {
 
     // Usage line
     let _ = writeln!(out);
-    let _ = writeln!(out, "Usage:");
+    let _ = writeln!(out, "{}", style.heading("Usage:"));
     let _ = writeln!(out, "  {} {}", cmdname_str, params.join(" "));
 
     // Arguments section
     if !positional_indices.is_empty() {
         let _ = writeln!(out);
-        let _ = writeln!(out, "Arguments:");
+        let _ = writeln!(out, "{}", style.heading("Arguments:"));
 
         for &i in &positional_indices {
             let entry = &args_arr[i];
@@ -237,12 +475,14 @@ fn args_help_text(title: &str, args_arr: &[String]) {
             };
             let field_fmt = field::format_field(&def);
 
-            let _ = writeln!(out, "   {:width$}{}", field_fmt, desc, width = fw);
+            let _ = write!(out, "   {}", style.name_padded(&field_fmt, fw));
+            style.write_desc(&mut out, desc, 3 + fw.max(field_fmt.len()));
         }
     }
 
     // Flags section
-    crate::usage::print_flags_section(&mut out, args_arr, fw);
+    crate::usage::print_flags_section(&mut out, args_arr, &style);
+    crate::usage::style::print_examples(&mut out, &style, &cmdname_str);
 
     let _ = writeln!(out);
 }
@@ -0,0 +1,170 @@
+//! Config-file defaults for :args and :usage.
+//!
+//! A flag typed `:~config` names a file whose entries pre-populate the other
+//! flags. Two formats are understood:
+//!   - JSON: a top-level object (`{"retries": 3, "tag": ["a", "b"]}`)
+//!   - key=value / INI / simple TOML (`retries = 3`, `tag = ["a", "b"]`)
+//!
+//! Precedence is command line, then `:$VAR` environment variable, then config
+//! file, then the variable's default.
+
+use crate::field::FieldDef;
+
+/// Entries read from a config file, in file order. A key may carry several
+/// values (a JSON/TOML array or a repeated key); map flags get `key=value` pairs.
+pub struct Config {
+    pub path: String,
+    entries: Vec<(String, Vec<String>)>,
+}
+
+impl Config {
+    /// Read and parse a config file. JSON is detected by a leading `{`.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("cannot read config file {}: {}", path, e))?;
+        let entries = if content.trim_start().starts_with('{') {
+            parse_json(&content)
+        } else {
+            parse_ini(&content)
+        }
+        .map_err(|e| format!("invalid config file {}: {}", path, e))?;
+        Ok(Config { path: path.to_string(), entries })
+    }
+
+    /// Values for a flag, looked up by its display name (`dry-run`) or its
+    /// variable name (`dry_run`). Returns the key as written in the file.
+    pub fn get(&self, def: &FieldDef) -> Option<(&str, &[String])> {
+        self.entries
+            .iter()
+            .find(|(key, _)| *key == def.display_name || *key == def.name)
+            .map(|(key, values)| (key.as_str(), values.as_slice()))
+    }
+}
+
+/// Append a value to `key`, keeping the first-seen key order.
+fn push_entry(entries: &mut Vec<(String, Vec<String>)>, key: &str, values: Vec<String>) {
+    match entries.iter_mut().find(|(k, _)| k == key) {
+        Some((_, existing)) => existing.extend(values),
+        None => entries.push((key.to_string(), values)),
+    }
+}
+
+/// Parse `key = value` lines. `#`/`;` comments and blank lines are skipped,
+/// `[section]` headers are ignored, values may be quoted, and `[a, b]` lists
+/// give several values.
+fn parse_ini(content: &str) -> Result<Vec<(String, Vec<String>)>, String> {
+    let mut entries = Vec::new();
+    for (n, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if line.starts_with('[') && line.ends_with(']') {
+            continue;
+        }
+        let (key, value) = match line.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => (unquote(key.trim()), value.trim()),
+            _ => return Err(format!("line {}: expected key = value: {}", n + 1, line)),
+        };
+        let values = match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            Some(list) => list
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|item| unquote(item).to_string())
+                .collect(),
+            None => vec![unquote(value).to_string()],
+        };
+        push_entry(&mut entries, key, values);
+    }
+    Ok(entries)
+}
+
+/// Strip matching single or double quotes; an unquoted value loses a
+/// trailing ` # comment`.
+fn unquote(value: &str) -> &str {
+    for q in ['"', '\''] {
+        if let Some(rest) = value.strip_prefix(q) {
+            if let Some(end) = rest.find(q) {
+                return &rest[..end];
+            }
+        }
+    }
+    value.split(" #").next().unwrap_or(value).trim_end()
+}
+
+/// Parse a top-level JSON object. Scalars become strings, arrays give
+/// several values, objects give `key=value` pairs and `null` is skipped.
+fn parse_json(content: &str) -> Result<Vec<(String, Vec<String>)>, String> {
+    let value: serde_json::Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+    let serde_json::Value::Object(obj) = value else {
+        return Err("expected a JSON object".to_string()); // coverage:off - load only takes the JSON path for a leading '{'
+    };
+    let mut entries = Vec::new();
+    for (key, value) in obj {
+        let values = match value {
+            serde_json::Value::Null => continue,
+            serde_json::Value::Array(items) => items.iter().map(json_scalar).collect(),
+            serde_json::Value::Object(pairs) => {
+                pairs.iter().map(|(k, v)| format!("{}={}", k, json_scalar(v))).collect()
+            }
+            other => vec![json_scalar(&other)],
+        };
+        push_entry(&mut entries, &key, values);
+    }
+    Ok(entries)
+}
+
+/// A JSON value as a flag value: strings unquoted, everything else as JSON.
+fn json_scalar(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values<'a>(entries: &'a [(String, Vec<String>)], key: &str) -> Option<&'a [String]> {
+        entries.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_slice())
+    }
+
+    #[test]
+    fn test_parse_ini() {
+        let entries = parse_ini(
+            "# presets\n[deploy]\nregion = eu-west-1\nretries=3\nname = \"my app\" # quoted\n\
+             tag = [\"a\", 'b']\ntag = c\n; done\n",
+        )
+        .unwrap();
+        assert_eq!(values(&entries, "region"), Some(&["eu-west-1".to_string()][..]));
+        assert_eq!(values(&entries, "retries"), Some(&["3".to_string()][..]));
+        assert_eq!(values(&entries, "name"), Some(&["my app".to_string()][..]));
+        assert_eq!(
+            values(&entries, "tag"),
+            Some(&["a".to_string(), "b".to_string(), "c".to_string()][..])
+        );
+        assert_eq!(values(&entries, "deploy"), None);
+    }
+
+    #[test]
+    fn test_parse_ini_invalid_line() {
+        let err = parse_ini("retries = 3\nverbose\n").unwrap_err();
+        assert_eq!(err, "line 2: expected key = value: verbose");
+    }
+
+    #[test]
+    fn test_parse_json() {
+        let entries = parse_json(
+            r#"{"retries": 3, "dry-run": true, "tag": ["a", "b"], "label": {"env": "prod"}, "skip": null}"#,
+        )
+        .unwrap();
+        assert_eq!(values(&entries, "retries"), Some(&["3".to_string()][..]));
+        assert_eq!(values(&entries, "dry-run"), Some(&["true".to_string()][..]));
+        assert_eq!(values(&entries, "tag"), Some(&["a".to_string(), "b".to_string()][..]));
+        assert_eq!(values(&entries, "label"), Some(&["env=prod".to_string()][..]));
+        assert_eq!(values(&entries, "skip"), None);
+        assert!(parse_json("{\"a\": }").is_err());
+    }
+}
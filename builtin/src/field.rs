@@ -3,7 +3,9 @@
 //! Mirrors: libraries/args.sh (args::field_name function, field parsing internals)
 
 use crate::{word_list_to_vec, BashBuiltin, SyncPtr, WordList, BUILTIN_ENABLED};
+use crate::shared;
 use crate::shell;
+use crate::to;
 use std::ffi::{c_char, c_int};
 
 // ── args::field_name builtin registration ────────────────────────
@@ -46,142 +48,89 @@ extern "C" fn field_name_builtin_fn(word_list: *const WordList) -> c_int {
 
 // ── Field parsing ────────────────────────────────────────────────
 
-/// Parsed field definition from the args array.
+/// Parsed field definition from the args array: the text-only definition
+/// shared with `argsh-syntax`, plus the state of its shell variable.
 #[derive(Debug, Clone)]
-#[allow(dead_code)]
 pub struct FieldDef {
-    pub name: String,         // variable name (dashes → underscores)
-    pub display_name: String, // original name (with dashes)
-    pub short: Option<String>,
-    pub is_boolean: bool,     // :+ modifier
-    pub type_name: String,    // "string", "int", "float", "file", etc.
-    pub required: bool,       // :! modifier
-    pub is_positional: bool,  // no | in definition
-    pub is_hidden: bool,      // # prefix
-    pub is_inherited: bool,   // :^ modifier — yields to non-:^ duplicates
+    pub spec: argsh_syntax::FieldDef, // name, type and modifiers
     pub is_array: bool,       // variable declared as array in shell
     pub has_default: bool,    // variable already initialized
     pub is_multiple: bool,    // array variable (collects multiple values)
-    pub raw: String,          // raw field definition string
+    pub is_map: bool,         // associative array variable (collects key=value pairs)
 }
 
-/// Extract the variable name from a field definition.
-/// 'flag|f:~int!' → "flag"
-/// '#hidden|h' → "hidden"
-/// 'my-flag|m' → "my_flag" (with asref=true, dashes → underscores)
-pub fn field_name(field: &str, asref: bool) -> String {
-    let mut name = field;
-    // Remove everything after first | or :
-    if let Some(pos) = name.find(['|', ':']) {
-        name = &name[..pos];
+impl FieldDef {
+    /// A scalar boolean whose variable starts out on; only such flags offer
+    /// their `--no-<name>` form in help, docs and completions. The value
+    /// recorded by `capture_defaults` is used when there is one, so a flag
+    /// given before `--help` does not change it.
+    pub fn is_default_on(&self) -> bool {
+        if !self.is_boolean || self.is_multiple || !self.has_default {
+            return false;
+        }
+        DEFAULTS_ON
+            .with(|defaults| defaults.borrow().get(&self.name).copied())
+            .unwrap_or_else(|| is_on(&self.name))
     }
-    // Remove leading #
-    let name = name.strip_prefix('#').unwrap_or(name);
-    if asref {
-        name.replace('-', "_")
-    } else {
-        name.to_string()
+}
+
+thread_local! {
+    /// Whether each boolean flag of the last `:args` or `:usage` call was on
+    /// before its command line was parsed, by variable name.
+    static DEFAULTS_ON: std::cell::RefCell<std::collections::HashMap<String, bool>> =
+        std::cell::RefCell::default();
+}
+
+/// Whether the variable `name` holds an on value.
+fn is_on(name: &str) -> bool {
+    shell::get_scalar(name).is_some_and(|v| !matches!(v.as_str(), "" | "0"))
+}
+
+/// Record the declared state of the boolean flags in `args_arr`. Called
+/// before any flag is applied; help rendered later in the same call, or by
+/// the deferred `:usage::help`, reads it through `is_default_on`.
+pub fn capture_defaults(args_arr: &[String]) {
+    let defaults = args_arr
+        .iter()
+        .step_by(2)
+        .filter_map(|spec| parse_field(spec).ok())
+        .filter(|def| def.is_boolean && !def.is_positional)
+        .map(|def| {
+            let on = def.has_default && is_on(&def.name);
+            (def.spec.name, on)
+        })
+        .collect();
+    DEFAULTS_ON.with(|d| *d.borrow_mut() = defaults);
+}
+
+impl std::ops::Deref for FieldDef {
+    type Target = argsh_syntax::FieldDef;
+
+    fn deref(&self) -> &Self::Target {
+        &self.spec
     }
 }
 
+pub use argsh_syntax::field::{field_name, is_flag_spec};
+
 /// Parse a field definition string into a FieldDef.
 /// Returns Err(msg) for invalid modifier combinations (matching bash :args::field_attrs behavior).
 pub fn parse_field(field: &str) -> Result<FieldDef, String> {
-    let raw = field.to_string();
-    let name = field_name(field, true);
-    let display_name = field_name(field, false);
-    let mut is_hidden = field.starts_with('#');
-    let is_positional = !field.contains('|') && field != "-";
-
-    // Parse short name
-    let short = if !is_positional {
-        let without_mods = field.split(':').next().unwrap_or(field);
-        let parts: Vec<&str> = without_mods.split('|').collect();
-        if parts.len() > 1 && !parts[1].is_empty() {
-            Some(parts[1].to_string())
-        } else {
-            None
-        }
-    } else {
-        None
-    };
-
-    // Parse modifiers after ':'
-    let mut is_boolean = false;
-    let mut type_name = String::new();
-    let mut required = false;
-    let mut is_inherited = false;
-
-    if let Some(colon_pos) = field.find(':') {
-        let mods = &field[colon_pos + 1..];
-        let mut chars = mods.chars().peekable();
-        while let Some(&c) = chars.peek() {
-            match c {
-                '+' => {
-                    if !type_name.is_empty() {
-                        return Err(format!(
-                            "cannot have multiple types: {} and boolean",
-                            type_name
-                        ));
-                    }
-                    is_boolean = true;
-                    chars.next();
-                }
-                '~' => {
-                    if is_boolean {
-                        return Err("already flagged as boolean".to_string());
-                    }
-                    chars.next();
-                    // Collect type name until next modifier
-                    let mut tname = String::new();
-                    while let Some(&tc) = chars.peek() {
-                        if tc == '+' || tc == '~' || tc == '!' || tc == '^' || tc == '#' || tc == ':' {
-                            break;
-                        }
-                        tname.push(tc);
-                        chars.next();
-                    }
-                    type_name = tname;
-                }
-                '!' => {
-                    if required {
-                        return Err("field already flagged as required".to_string());
-                    }
-                    required = true;
-                    chars.next();
-                }
-                '^' => {
-                    is_inherited = true;
-                    chars.next();
-                }
-                '#' => {
-                    // hidden modifier (also handled by # prefix)
-                    is_hidden = true;
-                    chars.next();
-                }
-                ':' => {
-                    // separator between chained modifiers
-                    chars.next();
-                }
-                _ => {
-                    return Err(format!("unknown modifier: {}", c));
-                }
-            }
-        }
-    }
-
-    // Default type
-    if type_name.is_empty() && !is_boolean {
-        type_name = "string".to_string();
-    }
+    let spec = parse_spec(field)?;
+    let name = &spec.name;
 
     // Check shell variable state
-    let is_arr = shell::is_array(&name);
-    let is_uninit = shell::is_uninitialized(&name);
-    let is_multiple = is_arr;
-    let has_default = if is_arr {
+    let is_arr = shell::is_array(name);
+    let is_map = !is_arr && shell::is_assoc(name);
+    if is_map && (spec.is_positional || spec.is_boolean) {
+        return Err("associative arrays are only supported for value flags".to_string());
+    }
+    let is_uninit = shell::is_uninitialized(name);
+    let has_default = if is_map {
+        !shell::get_assoc_keys(name).is_empty()
+    } else if is_arr {
         !is_uninit && {
-            let arr = shell::read_array(&name);
+            let arr = shell::read_array(name);
             !arr.is_empty()
         }
     } else {
@@ -189,22 +138,19 @@ pub fn parse_field(field: &str) -> Result<FieldDef, String> {
     };
 
     Ok(FieldDef {
-        name,
-        display_name,
-        short,
-        is_boolean,
-        type_name,
-        required,
-        is_positional,
-        is_hidden,
-        is_inherited,
+        spec,
         is_array: is_arr,
         has_default,
-        is_multiple,
-        raw,
+        is_multiple: is_arr,
+        is_map,
     })
 }
 
+/// The text-only part of `parse_field`.
+fn parse_spec(field: &str) -> Result<argsh_syntax::FieldDef, String> {
+    argsh_syntax::parse_field(field).map_err(|e| e.message)
+}
+
 /// Deduplicate args array entries based on `:^` (inherited) modifier.
 ///
 /// The args array is structured as pairs: [spec, description, spec, description, ...].
@@ -238,7 +184,7 @@ pub fn dedup_inherited(args: &[String]) -> Vec<String> {
         if name.is_empty() {
             continue;
         }
-        let inherited = parse_field(&args[i]).map(|f| f.is_inherited).unwrap_or(false);
+        let inherited = argsh_syntax::parse_field(&args[i]).is_ok_and(|f| f.is_inherited);
         field_map.entry(name).or_default().push((i, inherited));
     }
 
@@ -281,6 +227,48 @@ pub fn dedup_inherited(args: &[String]) -> Vec<String> {
     result
 }
 
+/// Convert a value for a parsed field: checks enum choices, otherwise
+/// delegates to `convert_type` and then checks range and pattern constraints
+/// with `argsh_syntax::FieldDef::check_value`, as the language server does.
+pub fn convert_value(def: &FieldDef, value: &str) -> Result<String, String> {
+    if def.choices.is_empty() {
+        let converted = convert_type(&def.type_name, value, &def.name)?;
+        def.check_value(&converted).map_err(|e| e.message)?;
+        if def.must_not_exist && std::path::Path::new(&converted).exists() {
+            return Err(format!("path already exists: {}", converted));
+        }
+        return Ok(converted);
+    }
+    if def.choices.iter().any(|c| c == value) {
+        return Ok(value.to_string());
+    }
+    let mut msg = format!("invalid choice ({}): {}", def.choices.join("|"), value);
+    if let Some(suggestion) = suggest_choice(def, value) {
+        msg.push_str(&format!(". Did you mean '{}'?", suggestion));
+    }
+    Err(msg)
+}
+
+/// The choice closest to an invalid `value` of an enum field, if one is
+/// close enough to suggest.
+pub fn suggest_choice(def: &FieldDef, value: &str) -> Option<String> {
+    if def.choices.iter().any(|c| c == value) {
+        return None;
+    }
+    shared::closest_match(value, def.choices.iter().map(String::as_str))
+}
+
+/// Convert the value of a boolean `--flag=value`. `convert_type("boolean")`
+/// reads every other word as true; here only its known spellings are
+/// accepted, so a typo is an error instead of switching the flag.
+pub fn convert_switch(value: &str) -> Result<bool, String> {
+    match value {
+        "true" | "1" | "yes" | "on" => Ok(true),
+        "false" | "0" | "no" | "off" => Ok(false),
+        _ => Err(format!("invalid type (boolean): {}", value)),
+    }
+}
+
 /// Convert a value to the expected type. Returns the converted value or an error message.
 pub fn convert_type(
     type_name: &str,
@@ -329,6 +317,30 @@ pub fn convert_type(
                 Err(format!("file not found: {}", value))
             }
         }
+        "config" => {
+            if std::path::Path::new(value).is_file() {
+                Ok(value.to_string())
+            } else {
+                Err(format!("config file not found: {}", value))
+            }
+        }
+        "dir" => {
+            if std::path::Path::new(value).is_dir() {
+                Ok(value.to_string())
+            } else {
+                Err(format!("directory not found: {}", value))
+            }
+        }
+        "path" if !value.is_empty() => Ok(to::normalize_path(value)),
+        "duration" => to::duration(value)
+            .map(|secs| secs.to_string())
+            .ok_or_else(|| format!("invalid type (duration): {}", value)),
+        "size" => to::size(value)
+            .map(|bytes| bytes.to_string())
+            .ok_or_else(|| format!("invalid type (size): {}", value)),
+        "url" if to::is_url(value) => Ok(value.to_string()),
+        "json" if to::is_json(value) => Ok(value.to_string()),
+        "path" | "url" | "json" => Err(format!("invalid type ({}): {}", type_name, value)),
         "stdin" => {
             if value == "-" { // coverage:off - stdin pipe reading cannot be tested in BATS subshell context
                 // Read from stdin
@@ -369,12 +381,23 @@ pub fn convert_type(
     }
 }
 
+/// Short and long name of a flag as shown in help: `-s, --name`, or the long
+/// name indented to line up with it. A boolean that defaults to on shows its
+/// `--no-` form as `--[no-]name`.
+pub fn flag_names(short: Option<&str>, name: &str, default_on: bool) -> String {
+    let long = if default_on { format!("[no-]{}", name) } else { name.to_string() };
+    match short {
+        Some(short) => format!("-{}, --{}", short, long),
+        None => format!("    --{}", long),
+    }
+}
+
 /// Format a field for help text display.
 /// For positionals: "name type"
 /// For flags: "   -s, --name type (default: val)"
 pub fn format_field(def: &FieldDef) -> String {
     if def.is_positional {
-        return format!("{} {}", def.display_name, def.type_name);
+        return format!("{} {}", def.display_name, def.type_label());
     }
 
     let mut out = String::new();
@@ -386,25 +409,29 @@ pub fn format_field(def: &FieldDef) -> String {
         out.push_str("   ");
     }
 
-    // Short + long
-    if let Some(ref short) = def.short {
-        out.push_str(&format!("-{}, --{}", short, def.display_name));
-    } else {
-        out.push_str(&format!("    --{}", def.display_name));
-    }
-
+    out.push_str(&flag_names(def.short.as_deref(), &def.display_name, def.is_default_on()));
     out.push(' ');
 
-    // Multiple
+    // Multiple; a map takes repeated `key=value` pairs
     if def.is_multiple {
         out.push_str("...");
+    } else if def.is_map {
+        out.push_str("...key=");
     }
 
     // Type
-    out.push_str(&def.type_name);
+    out.push_str(&def.type_label());
+    if def.is_counter {
+        out.push_str("(repeatable)");
+    }
+
+    // Environment variable fallback
+    if let Some(ref var) = def.env_var {
+        out.push_str(&format!(" (env: {})", var));
+    }
 
     // Default value (only for non-boolean with existing value)
-    if def.has_default && !def.is_boolean {
+    if def.has_default && !def.is_boolean && !def.is_map {
         if let Some(display) = shell::get_var_display(&def.name) {
             out.push_str(&format!(" (default: {})", display));
         }
@@ -432,22 +459,114 @@ pub fn field_lookup(flag: &str, args: &[String]) -> Option<usize> {
     None
 }
 
-/// Find the nth positional field. If a field is an array, it always matches
-/// (catch-all behavior for remaining positionals).
-pub fn field_positional(position: usize, args: &[String]) -> Option<usize> {
-    let mut pos = position;
-    for i in (0..args.len()).step_by(2) {
-        let def = &args[i];
-        // Positional = no | and not '-'
-        if !def.contains('|') && def != "-" {
-            let name = field_name(def, true);
-            if shell::is_array(&name) || {
-                pos -= 1;
-                pos == 0
-            } {
-                return Some(i);
-            }
+/// Lookup a flag by a prefix of its long name (`verb` for `verbose`).
+/// `no-<prefix>` also matches the negated form of a boolean flag.
+/// Returns Ok(Some((index, negated))) for a unique match, Ok(None) for no
+/// match and Err(names) with the matching long names when the prefix is
+/// ambiguous.
+pub fn field_prefix_lookup(prefix: &str, args: &[String]) -> Result<Option<(usize, bool)>, Vec<String>> {
+    let negated = prefix.strip_prefix("no-");
+    let mut matches: Vec<(usize, bool, String)> = Vec::new();
+    for i in (0..args.len()).step_by(2).filter(|&i| is_flag_spec(&args[i])) {
+        let name = field_name(&args[i], false);
+        if name.starts_with(prefix) {
+            matches.push((i, false, name));
+        } else if negated.is_some_and(|rest| name.starts_with(rest))
+            && parse_spec(&args[i]).is_ok_and(|def| def.is_boolean)
+        {
+            matches.push((i, true, format!("no-{}", name)));
+        }
+    }
+    match matches.as_slice() {
+        [] => Ok(None),
+        [(i, negated, _)] => Ok(Some((*i, *negated))),
+        _ => Err(matches.into_iter().map(|(_, _, name)| name).collect()),
+    }
+}
+
+/// Indices of the positional field definitions in an args array, in order.
+pub fn positional_fields(args: &[String]) -> Vec<usize> {
+    (0..args.len())
+        .step_by(2)
+        .filter(|&i| !is_flag_spec(&args[i]) && args[i] != "-")
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_prefix_lookup() {
+        let args: Vec<String> = ["verbose|v:+", "", "version|:+", "", "output|o", "", "verb", ""]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(field_prefix_lookup("out", &args), Ok(Some((4, false))));
+        assert_eq!(field_prefix_lookup("verb", &args), Ok(Some((0, false))));
+        assert_eq!(field_prefix_lookup("x", &args), Ok(None));
+        assert_eq!(
+            field_prefix_lookup("ver", &args),
+            Err(vec!["verbose".to_string(), "version".to_string()])
+        );
+        // Negated booleans match by prefix too; value flags have no negation
+        assert_eq!(field_prefix_lookup("no-verb", &args), Ok(Some((0, true))));
+        assert_eq!(field_prefix_lookup("no-out", &args), Ok(None));
+        assert_eq!(
+            field_prefix_lookup("no-ver", &args),
+            Err(vec!["no-verbose".to_string(), "no-version".to_string()])
+        );
+    }
+
+    /// What the pure-bash `:args::field_attrs` (libraries/args.sh) gives for
+    /// the specs both parsers understand: name, display name, short, boolean,
+    /// type (empty for a boolean), required -- or the error message.
+    /// The pure-bash parser does not mark `#name` as hidden, so hidden is
+    /// left out.
+    #[allow(clippy::type_complexity)]
+    const BASH_FIELD_ATTRS: &[(&str, Result<(&str, &str, &str, bool, &str, bool), &str>)] = &[
+        ("name", Ok(("name", "name", "", false, "string", false))),
+        ("#name", Ok(("name", "name", "", false, "string", false))),
+        ("my-name", Ok(("my_name", "my-name", "", false, "string", false))),
+        ("count:~int", Ok(("count", "count", "", false, "int", false))),
+        ("file:~file:!", Ok(("file", "file", "", false, "file", true))),
+        ("verbose|v:+", Ok(("verbose", "verbose", "v", true, "", false))),
+        ("quiet|q:+:!", Ok(("quiet", "quiet", "q", true, "", true))),
+        ("debug|d:+:#", Ok(("debug", "debug", "d", true, "", false))),
+        ("output|o", Ok(("output", "output", "o", false, "string", false))),
+        ("output|", Ok(("output", "output", "", false, "string", false))),
+        ("my-flag|m:~string:!", Ok(("my_flag", "my-flag", "m", false, "string", true))),
+        ("dry-run|:+", Ok(("dry_run", "dry-run", "", true, "", false))),
+        ("config|c:~config", Ok(("config", "config", "c", false, "config", false))),
+        ("global|g:^", Ok(("global", "global", "g", false, "string", false))),
+        ("global|g:~int:^:!", Ok(("global", "global", "g", false, "int", true))),
+        ("name:~custom", Ok(("name", "name", "", false, "custom", false))),
+        ("ratio|r:!:~float", Ok(("ratio", "ratio", "r", false, "float", true))),
+        ("flag|f:+:~int", Err("already flagged as boolean")),
+        ("flag|f:~int:+", Err("cannot have multiple types: int and boolean")),
+        ("flag|f:!:!", Err("field already flagged as required")),
+        ("flag|f:x", Err("unknown modifier: x")),
+        ("flag|f:~int!", Ok(("flag", "flag", "f", false, "int", true))),
+    ];
+
+    #[test]
+    fn test_parse_spec_matches_bash_field_attrs() {
+        for (spec, expected) in BASH_FIELD_ATTRS {
+            let actual = parse_spec(spec).map(|def| {
+                let type_name = if def.is_boolean { "" } else { def.type_name.as_str() };
+                (
+                    def.name.clone(),
+                    def.display_name.clone(),
+                    def.short.clone().unwrap_or_default(),
+                    def.is_boolean,
+                    type_name.to_string(),
+                    def.required,
+                )
+            });
+            let expected = expected.map(|(name, display, short, boolean, type_name, required)| {
+                (name.into(), display.into(), short.into(), boolean, type_name.into(), required)
+            });
+            assert_eq!(actual, expected.map_err(str::to_string), "spec: {}", spec);
         }
     }
-    None
 }
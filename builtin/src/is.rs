@@ -154,7 +154,12 @@ pub extern "C" fn is_tty_builtin_unload(_name: *const c_char) {} // coverage:off
 
 extern "C" fn is_tty_builtin_fn(_word_list: *const WordList) -> c_int {
     std::panic::catch_unwind(|| {
-        if unsafe { libc::isatty(1) } == 1 { 0 } else { 1 }
+        if stdout_is_tty() { 0 } else { 1 }
     })
     .unwrap_or(1)
 }
+
+/// Whether stdout is a terminal, as checked by `is::tty`.
+pub(crate) fn stdout_is_tty() -> bool {
+    unsafe { libc::isatty(1) == 1 }
+}
@@ -2,19 +2,25 @@
 //!
 //! Module layout mirrors libraries/*.sh:
 //!   args.rs  ← args.sh (:args)
-//!   usage/   ← args.sh (:usage, :usage::help, :usage::completion, :usage::docgen, :usage::mcp)
+//!   usage/   ← args.sh (:usage, :usage::help, :usage::complete, :usage::completion, :usage::docgen,
+//!                :usage::mcp, :usage::version)
 //!   field.rs ← args.sh (args::field_name, field parsing)
 //!   is.rs    ← is.sh   (is::array, is::uninitialized, is::set, is::tty)
-//!   to.rs    ← to.sh   (to::int, to::float, to::boolean, to::file, to::string)
+//!   to.rs    ← to.sh   (to::int, to::float, to::boolean, to::file, to::string;
+//!                       native only: to::duration, to::size, to::url, to::dir,
+//!                       to::path, to::json)
+//!   config.rs — :~config file defaults for :args/:usage (no .sh counterpart)
 //!   shell.rs — bash FFI bridge (no .sh counterpart)
 //!
 //! Build: cargo build --release
-//! Load:  enable -f ./target/release/libargsh.so :usage :usage::help \
-//!            :usage::completion :usage::docgen :usage::mcp :args \
+//! Load:  enable -f ./target/release/libargsh.so :usage :usage::help :usage::complete \
+//!            :usage::completion :usage::docgen :usage::mcp :usage::version :args \
 //!            is::array is::uninitialized is::set is::tty args::field_name \
-//!            to::int to::float to::boolean to::file to::string
+//!            to::int to::float to::boolean to::file to::string \
+//!            to::duration to::size to::url to::dir to::path to::json
 
 mod args;
+mod config;
 mod field;
 mod import;
 mod is;
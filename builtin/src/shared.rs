@@ -3,6 +3,7 @@
 //! Extracted from args.rs and usage.rs to eliminate code duplication (REVIEW finding 3).
 //! All error functions return exit codes instead of calling std::process::exit() (REVIEW finding 2).
 
+use crate::config;
 use crate::field;
 use crate::shell;
 
@@ -13,25 +14,119 @@ pub const EXIT_USAGE: i32 = 2;
 /// Distinct from 0 (success, continue script).
 pub const HELP_EXIT: i32 = -1;
 
+/// Kind of a parse error. In JSON error mode each kind exits with its own code.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ErrorKind {
+    Usage,
+    UnknownFlag,
+    MissingValue,
+    InvalidValue,
+    MissingRequired,
+    UnknownCommand,
+}
+
+impl ErrorKind {
+    /// Name reported in the `error` field of a JSON error.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorKind::Usage => "usage",
+            ErrorKind::UnknownFlag => "unknown_flag",
+            ErrorKind::MissingValue => "missing_value",
+            ErrorKind::InvalidValue => "invalid_value",
+            ErrorKind::MissingRequired => "missing_required",
+            ErrorKind::UnknownCommand => "unknown_command",
+        }
+    }
+
+    /// Exit code in JSON error mode; human-readable errors always exit 2.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ErrorKind::Usage => EXIT_USAGE,
+            ErrorKind::UnknownFlag => 3,
+            ErrorKind::MissingValue => 4,
+            ErrorKind::InvalidValue => 5,
+            ErrorKind::MissingRequired => 6,
+            ErrorKind::UnknownCommand => 7,
+        }
+    }
+}
+
 /// Print a usage error and return exit code 2.
 /// Does NOT call std::process::exit() -- returns the code for the caller to propagate.
-pub fn error_usage(_field: &str, msg: &str) -> i32 {
-    let script = shell::get_script_name();
-    eprintln!("Error: {}\n", msg);
-    eprintln!("  Run \"{} -h\" for more information.", script);
-    EXIT_USAGE
+pub fn error_usage(field: &str, msg: &str) -> i32 {
+    error_kind(ErrorKind::Usage, field, "", msg)
 }
 
-/// Print an argument error and return exit code 2.
+/// Print a missing-value error and return exit code 2.
 /// Does NOT call std::process::exit() -- returns the code for the caller to propagate.
-pub fn error_args(_field: &str, msg: &str) -> i32 {
+pub fn error_args(field: &str, msg: &str) -> i32 {
+    error_kind(ErrorKind::MissingValue, field, "", msg)
+}
+
+/// Print a classified parse error and return its exit code.
+/// With `ARGSH_ERROR_FORMAT=json` the error is a single JSON object on stderr
+/// (see `error_json`) and the exit code depends on the kind; otherwise it is
+/// the usual human-readable message and exit code 2.
+pub fn error_kind(kind: ErrorKind, field: &str, value: &str, msg: &str) -> i32 {
+    error_suggest(kind, field, value, msg, None)
+}
+
+/// `error_kind` for an error whose message offers a suggestion ("Did you
+/// mean 'x'?"); JSON errors report it in the `suggestion` field.
+pub fn error_suggest(kind: ErrorKind, field: &str, value: &str, msg: &str, suggestion: Option<&str>) -> i32 {
+    if shell::get_scalar("ARGSH_ERROR_FORMAT").as_deref() == Some("json") {
+        eprintln!("{}", error_json(kind, field, value, msg, suggestion, &shell::get_commandname()));
+        return kind.exit_code();
+    }
     let script = shell::get_script_name();
     eprintln!("Error: {}\n", msg);
     eprintln!("  Run \"{} -h\" for more information.", script);
     EXIT_USAGE
 }
 
-/// Parse a flag at position `idx` in the cli args.
+/// JSON error object. Empty `field`/`value` and a missing suggestion become
+/// `null`.
+fn error_json(
+    kind: ErrorKind,
+    field: &str,
+    value: &str,
+    msg: &str,
+    suggestion: Option<&str>,
+    command: &[String],
+) -> String {
+    let non_empty = |s: &str| (!s.is_empty()).then(|| s.to_string());
+    serde_json::json!({
+        "error": kind.as_str(),
+        "message": msg,
+        "field": non_empty(field),
+        "value": non_empty(value),
+        "suggestion": suggestion,
+        "command": command,
+        "exit_code": kind.exit_code(),
+    })
+    .to_string()
+}
+
+/// Print a one-line warning that `name` is deprecated, naming its
+/// replacement when there is one. Parsing continues as usual.
+pub fn warn_deprecated(name: &str, replacement: &str) {
+    if replacement.is_empty() {
+        eprintln!("Warning: {} is deprecated", name);
+    } else {
+        eprintln!("Warning: {} is deprecated, use {} instead", name, replacement);
+    }
+}
+
+/// Index of the boolean field negated by a long `--no-<name>` flag.
+fn negated_field(is_long: bool, lookup_name: &str, args_arr: &[String]) -> Option<usize> {
+    let name = lookup_name.strip_prefix("no-").filter(|_| is_long)?;
+    let idx = field::field_lookup(name, args_arr)?;
+    let def = field::parse_field(&args_arr[idx]).ok()?;
+    (def.is_boolean && def.display_name == name).then_some(idx)
+}
+
+/// Parse a flag at position `idx` in the cli args. With `prefix`, a long flag
+/// may be abbreviated to any unambiguous prefix of its name.
 /// Returns Ok(true) if parsed, Ok(false) if not a known flag, Err(code) on error.
 pub fn parse_flag_at(
     cli: &mut Vec<String>,
@@ -39,6 +134,7 @@ pub fn parse_flag_at(
     args_arr: &[String],
     matched: &mut Vec<String>,
     set_bool: fn(&str),
+    prefix: bool,
 ) -> Result<bool, i32> {
     if idx >= cli.len() { // coverage:off - defensive_check: callers verify idx < cli.len() before calling
         return Ok(false); // coverage:off
@@ -55,22 +151,77 @@ pub fn parse_flag_at(
         return Ok(false); // coverage:off - defensive_check: callers only pass flag args (starts_with '-')
     };
 
-    // Find field in args array
-    let field_idx = match field::field_lookup(&lookup_name, args_arr) {
-        Some(i) => i,
-        None => return Ok(false),
+    // Find field in args array; `--no-<name>` negates a boolean flag unless
+    // a field of that literal name exists. Prefixes are tried last.
+    let (field_idx, negated) = match field::field_lookup(&lookup_name, args_arr) {
+        Some(i) => (i, false),
+        None => match negated_field(is_long, &lookup_name, args_arr) {
+            Some(i) => (i, true),
+            None if !(prefix && is_long) || lookup_name.is_empty() => return Ok(false),
+            None => match field::field_prefix_lookup(&lookup_name, args_arr) {
+                Ok(Some(found)) => found,
+                Ok(None) => return Ok(false),
+                Err(names) => {
+                    let names: Vec<String> = names.iter().map(|n| format!("--{}", n)).collect();
+                    return Err(error_kind(
+                        ErrorKind::UnknownFlag,
+                        "",
+                        &arg,
+                        &format!("ambiguous flag: --{} (could be {})", lookup_name, names.join(", ")),
+                    ));
+                }
+            },
+        },
     };
 
     let field_str = &args_arr[field_idx];
-    matched.push(field_str.clone());
     let def = match field::parse_field(field_str) {
         Ok(d) => d,
         Err(msg) => return Err(error_usage(field_str, &msg)),
     };
+    if let Some(ref replacement) = def.deprecated {
+        let replacement = if replacement.is_empty() { String::new() } else { format!("--{}", replacement) };
+        warn_deprecated(&format!("--{}", def.display_name), &replacement);
+    }
 
-    // Boolean flag (no value)
+    // Boolean flag (no value); `--flag=false` / `--flag=0` switch it off.
+    // A counter (:++) adds one per occurrence and takes no value at all.
+    // A flag switched off does not count as given.
     if def.is_boolean {
-        if def.is_multiple || shell::is_array(&def.name) {
+        let inline = arg.split_once('=').filter(|_| is_long).map(|(_, v)| v);
+        if inline.is_some() && (negated || def.is_counter) {
+            let name = if negated { format!("no-{}", def.display_name) } else { def.display_name.clone() };
+            let kind = if negated { "negated" } else { "counter" };
+            return Err(error_kind(
+                ErrorKind::InvalidValue,
+                field_str,
+                &arg,
+                &format!("{} flag does not take a value: --{}", kind, name),
+            ));
+        }
+        let off = match inline {
+            Some(value) => match field::convert_switch(value) {
+                Ok(on) => !on,
+                Err(msg) => return Err(error_kind(ErrorKind::InvalidValue, field_str, value, &msg)),
+            },
+            None => negated,
+        };
+        if !off {
+            matched.push(field_str.clone());
+        }
+
+        if off {
+            if def.is_multiple || shell::is_array(&def.name) {
+                shell::write_array(&def.name, &[]);
+            } else {
+                shell::set_scalar(&def.name, "0");
+            }
+        } else if def.is_counter {
+            let count = shell::get_scalar(&def.name)
+                .and_then(|v| v.parse::<i64>().ok())
+                .unwrap_or(0);
+            shell::set_scalar(&def.name, &(count + 1).to_string());
+        } else if def.is_multiple || shell::is_array(&def.name) {
             shell::array_append(&def.name, "1");
         } else {
             set_bool(&def.name);
@@ -131,11 +282,25 @@ pub fn parse_flag_at(
         }
     };
 
+    matched.push(field_str.clone());
+
+    // A map flag (`local -A`) takes `key=value`; only the value is typed
+    if def.is_map {
+        return match set_map_entry(&def, &value) {
+            Ok(()) => Ok(true),
+            Err(msg) => {
+                let suggestion = value.split_once('=').and_then(|(_, v)| field::suggest_choice(&def, v));
+                Err(error_suggest(ErrorKind::InvalidValue, field_str, &value, &msg, suggestion.as_deref()))
+            }
+        };
+    }
+
     // Type convert
-    let converted = match field::convert_type(&def.type_name, &value, &def.name) {
+    let converted = match field::convert_value(&def, &value) {
         Ok(v) => v,
         Err(msg) => {
-            return Err(error_usage(field_str, &msg));
+            let suggestion = field::suggest_choice(&def, &value);
+            return Err(error_suggest(ErrorKind::InvalidValue, field_str, &value, &msg, suggestion.as_deref()));
         }
     };
 
@@ -149,9 +314,44 @@ pub fn parse_flag_at(
     Ok(true)
 }
 
-/// Check required flags and set boolean defaults.
+/// Set flags absent from the command line from their `:$VAR` environment
+/// variable. Runs once, right after the command line is parsed; flags set
+/// this way are added to `matched` so the later checks treat them as given,
+/// except booleans switched off.
+/// Returns 0 on success, or an error exit code.
+pub fn apply_env_flags(args_arr: &[String], matched: &mut Vec<String>) -> i32 {
+    for i in (0..args_arr.len()).step_by(2) {
+        let field_str = &args_arr[i];
+        if field_str == "-" || matched.contains(field_str) {
+            continue;
+        }
+        let Ok(def) = field::parse_field(field_str) else {
+            continue; // coverage:off - defensive_check: parse_flag_at already rejected invalid specs
+        };
+        if def.is_positional {
+            continue;
+        }
+        match apply_env_fallback(&def) {
+            Ok(true) => matched.push(field_str.clone()),
+            Ok(false) => {}
+            Err(msg) => return error_kind(ErrorKind::InvalidValue, field_str, "", &msg),
+        }
+    }
+    0
+}
+
+/// Check required flags and flag-group constraints, and set boolean defaults.
+/// Flags absent from the command line (and their environment variable, see
+/// `apply_env_flags`) fall back to the `:~config` file.
 /// Returns 0 on success, or an error exit code.
 pub fn check_required_flags(args_arr: &[String], matched: &[String]) -> i32 {
+    let config = match load_config(args_arr, matched) {
+        Ok(c) => c,
+        Err(code) => return code,
+    };
+
+    // (definition, given on the command line or via its environment variable)
+    let mut flags: Vec<(field::FieldDef, bool)> = Vec::new();
     for i in (0..args_arr.len()).step_by(2) {
         let field_str = &args_arr[i];
         if field_str == "-" {
@@ -167,19 +367,203 @@ pub fn check_required_flags(args_arr: &[String], matched: &[String]) -> i32 {
             continue;
         }
 
+        let mut satisfied = matched.contains(field_str);
+        let from_env = env_value(&def).is_some();
+        if let Some(config) = config.as_ref().filter(|_| !satisfied && !from_env && def.type_name != "config") {
+            match apply_config(&def, config) {
+                Ok(applied) => satisfied = applied,
+                Err(msg) => return error_kind(ErrorKind::InvalidValue, field_str, "", &msg),
+            }
+        }
+
         // Set boolean to false if not matched and no default
-        if def.is_boolean && !def.has_default && !matched.contains(field_str) {
+        if def.is_boolean && !def.has_default && !satisfied {
             // For arrays: sets arr[0]=0. For scalars: sets var=0.
             shell::set_scalar(&def.name, "0");
         }
+        flags.push((def, satisfied));
+    }
 
-        // Check required
-        if def.required && !matched.contains(field_str) {
-            let display = field_str.split('|').next().unwrap_or(field_str);
-            return error_usage(field_str, &format!("missing required flag: {}", display));
+    for (def, satisfied) in &flags {
+        if !def.required || *satisfied {
+            continue;
+        }
+        // A required member of a :%group is satisfied by any other member
+        let Some(ref group) = def.group else {
+            let display = def.raw.split('|').next().unwrap_or(&def.raw);
+            return error_kind(
+                ErrorKind::MissingRequired,
+                &def.raw,
+                "",
+                &format!("missing required flag: {}", display),
+            );
+        };
+        let members: Vec<&(field::FieldDef, bool)> =
+            flags.iter().filter(|(d, _)| d.group.as_ref() == Some(group)).collect();
+        if !members.iter().any(|(_, given)| *given) {
+            let names: Vec<String> = members.iter().map(|(d, _)| format!("--{}", d.display_name)).collect();
+            return error_kind(
+                ErrorKind::MissingRequired,
+                &def.raw,
+                "",
+                &format!("missing required flag: one of {}", names.join(", ")),
+            );
         }
     }
-    0
+
+    match check_flag_groups(&flags) {
+        Ok(()) => 0,
+        Err((field, msg)) => error_usage(field, &msg),
+    }
+}
+
+/// Enforce `:%group` (at most one member given) and `:&flag` (requires another
+/// flag) constraints. Only flags given on the command line, via their
+/// environment variable or in the config file count; defaults and booleans
+/// switched off do not. Errors carry the raw spec of
+/// the offending flag.
+fn check_flag_groups(flags: &[(field::FieldDef, bool)]) -> Result<(), (&str, String)> {
+    let mut seen_groups: Vec<&str> = Vec::new();
+    for (def, _) in flags {
+        let Some(ref group) = def.group else { continue };
+        if seen_groups.contains(&group.as_str()) {
+            continue;
+        }
+        seen_groups.push(group);
+        let given: Vec<&field::FieldDef> = flags
+            .iter()
+            .filter(|(d, given)| *given && d.group.as_ref() == Some(group))
+            .map(|(d, _)| d)
+            .collect();
+        if let [_, second, ..] = given[..] {
+            let names: Vec<String> = given.iter().map(|d| format!("--{}", d.display_name)).collect();
+            return Err((&second.raw, format!("flags cannot be used together: {}", names.join(", "))));
+        }
+    }
+
+    for (def, given) in flags {
+        if !given {
+            continue;
+        }
+        for other in &def.requires {
+            let other_name = field::field_name(other, true);
+            match flags.iter().find(|(d, _)| d.name == other_name) {
+                Some((_, true)) => {}
+                Some((d, false)) => {
+                    return Err((&def.raw, format!("--{} requires --{}", def.display_name, d.display_name)));
+                }
+                None => {
+                    return Err((&def.raw, format!("--{} requires unknown flag: {}", def.display_name, other)));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The `:$VAR` environment variable of a flag and its value, if declared
+/// and non-empty.
+fn env_value(def: &field::FieldDef) -> Option<(&str, String)> {
+    let var = def.env_var.as_deref()?;
+    shell::get_scalar(var).filter(|v| !v.is_empty()).map(|v| (var, v))
+}
+
+/// Set a flag from its `:$VAR` environment variable, if declared and non-empty.
+/// The value is converted by `convert_default`.
+/// Returns Ok(true) if the variable supplied a value, unless it switched a
+/// boolean off.
+fn apply_env_fallback(def: &field::FieldDef) -> Result<bool, String> {
+    let Some((var, raw)) = env_value(def) else {
+        return Ok(false);
+    };
+    if def.is_map {
+        set_map_entry(def, &raw).map_err(|msg| format!("{} (from ${})", msg, var))?;
+        return Ok(true);
+    }
+    let converted = convert_default(def, &raw).map_err(|msg| format!("{} (from ${})", msg, var))?;
+
+    let given = !def.is_boolean || converted != "0";
+    if def.is_multiple {
+        shell::write_array(&def.name, &[converted]);
+    } else {
+        shell::set_scalar(&def.name, &converted);
+    }
+    Ok(given)
+}
+
+/// Load the file named by the `:~config` flag. A path given on the command
+/// line or via `:$VAR` must exist; a default path is only read if present.
+/// Errors name the `:~config` flag. Returns Err(code) on error.
+fn load_config(args_arr: &[String], matched: &[String]) -> Result<Option<config::Config>, i32> {
+    let found = (0..args_arr.len()).step_by(2).find_map(|i| {
+        let def = field::parse_field(&args_arr[i]).ok()?;
+        (!def.is_positional && def.type_name == "config").then(|| (&args_arr[i], def))
+    });
+    let Some((field_str, def)) = found else {
+        return Ok(None);
+    };
+    let explicit = matched.contains(field_str);
+    let path = match shell::get_scalar(&def.name) {
+        Some(p) if !p.is_empty() => p,
+        _ => return Ok(None),
+    };
+    if !explicit && !std::path::Path::new(&path).is_file() {
+        return Ok(None);
+    }
+    config::Config::load(&path).map(Some).map_err(|msg| error_usage(field_str, &msg))
+}
+
+/// Set a flag from its config-file entry, if there is one. Values are
+/// converted by `convert_default`; errors name the file and key. Returns Ok(true) if the file supplied a value, unless it switched
+/// a boolean off.
+fn apply_config(def: &field::FieldDef, config: &config::Config) -> Result<bool, String> {
+    let Some((key, values)) = config.get(def).filter(|(_, values)| !values.is_empty()) else {
+        return Ok(false);
+    };
+    let from = |msg: String| format!("{} (from {}: {})", msg, config.path, key);
+    if def.is_map {
+        for pair in values {
+            set_map_entry(def, pair).map_err(from)?;
+        }
+        return Ok(true);
+    }
+    let mut converted = Vec::with_capacity(values.len());
+    for value in values {
+        converted.push(convert_default(def, value).map_err(from)?);
+    }
+
+    let given = !def.is_boolean || converted.last().is_some_and(|v| v != "0");
+    if def.is_multiple {
+        shell::write_array(&def.name, &converted);
+    } else if let Some(last) = converted.last() {
+        shell::set_scalar(&def.name, last);
+    }
+    Ok(given)
+}
+
+/// Convert a value from the environment or a config file: a counter takes
+/// a count, a boolean the spellings of `--flag=value`, anything else goes
+/// through the same type conversion as a command-line value.
+fn convert_default(def: &field::FieldDef, value: &str) -> Result<String, String> {
+    if def.is_counter {
+        value.parse::<u64>().map(|n| n.to_string()).map_err(|_| format!("invalid count: {}", value))
+    } else if def.is_boolean {
+        field::convert_switch(value).map(|on| if on { "1" } else { "0" }.to_string())
+    } else {
+        field::convert_value(def, value)
+    }
+}
+
+/// Split a `key=value` pair, type-convert the value and store it in the
+/// flag's associative array.
+fn set_map_entry(def: &field::FieldDef, pair: &str) -> Result<(), String> {
+    let (key, value) = match pair.split_once('=') {
+        Some((key, value)) if !key.is_empty() => (key, value),
+        _ => return Err(format!("invalid key=value pair: {}", pair)),
+    };
+    let converted = field::convert_value(def, value)?;
+    shell::assoc_set(&def.name, key, &converted);
+    Ok(())
 }
 
 /// Compute Levenshtein edit distance between two strings.
@@ -218,41 +602,119 @@ pub fn levenshtein(a: &str, b: &str) -> usize {
     prev[b_len]
 }
 
+/// Find the candidate closest to `input`, ranked by edit distance with ties
+/// going to a candidate that starts with `input`.
+/// Returns Some(candidate) only for a plausible typo (distance ≤ 2 and ≤ 40% of
+/// longer string) or a truncated name of at least 3 characters, and None if
+/// `input` is itself a candidate.
+pub fn closest_match<'a>(input: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    let mut best: Option<(&str, usize, bool)> = None;
+    for candidate in candidates {
+        let dist = levenshtein(input, candidate);
+        if dist == 0 {
+            return None;
+        }
+        let prefix = input.len() >= 3 && candidate.starts_with(input);
+        let max_len = input.len().max(candidate.len());
+        // Threshold: distance ≤ 2 AND ≤ 40% of the longer string length
+        if !prefix && (dist > 2 || (dist * 100) > (max_len * 40)) {
+            continue;
+        }
+        let better = match best {
+            Some((_, best_dist, best_prefix)) => (dist, !prefix) < (best_dist, !best_prefix),
+            None => true,
+        };
+        if better {
+            best = Some((candidate, dist, prefix));
+        }
+    }
+    best.map(|(name, _, _)| name.to_string())
+}
+
 /// Find the closest matching command from usage array pairs.
-/// Returns Some(name) if a suggestion is close enough (distance ≤ 2 and ≤ 40% of longer string).
+/// Returns Some(name) if a suggestion is close enough (see `closest_match`).
 pub fn suggest_command(input: &str, usage_arr: &[String]) -> Option<String> {
-    let mut best: Option<(String, usize)> = None;
-
-    for i in (0..usage_arr.len()).step_by(2) {
+    let aliases = (0..usage_arr.len()).step_by(2).flat_map(|i| {
         let entry = &usage_arr[i];
         // Strip hidden prefix and @ annotations
         let entry_cmd_part = entry.split(':').next().unwrap_or(entry);
         let entry_cmd_part = entry_cmd_part.split('@').next().unwrap_or(entry_cmd_part);
         let entry_clean = entry_cmd_part.strip_prefix('#').unwrap_or(entry_cmd_part);
+        entry_clean.split('|')
+    });
+    closest_match(input, aliases)
+}
 
-        for alias in entry_clean.split('|') {
-            let dist = levenshtein(input, alias);
-            if let Some((_, best_dist)) = &best {
-                if dist < *best_dist {
-                    best = Some((alias.to_string(), dist));
-                }
-            } else {
-                best = Some((alias.to_string(), dist));
-            }
-        }
+/// Find the closest flag name (long names and short aliases) for an unknown
+/// flag like `--verbos`, formatted with its dashes (`--verbose`, `-v`).
+/// Booleans also offer their `--no-<name>` form.
+pub fn suggest_flag(input: &str, args_arr: &[String]) -> Option<String> {
+    let name = input.split('=').next().unwrap_or(input);
+    let bare = name.trim_start_matches('-');
+    if bare.is_empty() {
+        return None;
     }
-
-    if let Some((name, dist)) = best {
-        let max_len = input.len().max(name.len());
-        // Threshold: distance ≤ 2 AND ≤ 40% of the longer string length
-        if dist > 0 && dist <= 2 && (dist * 100) <= (max_len * 40) {
-            return Some(name);
+    let mut candidates: Vec<String> = Vec::new();
+    for i in (0..args_arr.len()).step_by(2) {
+        if args_arr[i] == "-" {
+            continue;
+        }
+        let Ok(def) = field::parse_field(&args_arr[i]) else { continue };
+        if def.is_positional {
+            continue;
+        }
+        candidates.push(format!("--{}", def.display_name));
+        if let Some(ref short) = def.short {
+            candidates.push(format!("-{}", short));
+        }
+        if def.is_boolean {
+            candidates.push(format!("--no-{}", def.display_name));
         }
     }
-    None
+    // Compare without dashes so they do not loosen the typo threshold
+    let found = closest_match(bare, candidates.iter().map(|c| c.trim_start_matches('-')))?;
+    candidates.into_iter().find(|c| c.trim_start_matches('-') == found)
 }
 
-// NOTE: Unit tests cannot run via `cargo test` because this crate is a cdylib
-// that links against bash symbols (dollar_vars, find_variable, etc.) which are
-// only available inside the bash process. All testing is done via BATS:
-//   ARGSH_SOURCE=argsh bats libraries/args.bats
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_json() {
+        let out = error_json(
+            ErrorKind::InvalidValue,
+            "env|e:~enum(dev,prod)",
+            "prd",
+            "invalid choice (dev|prod): prd. Did you mean 'prod'?",
+            Some("prod"),
+            &["app".to_string(), "deploy".to_string()],
+        );
+        let v: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(v["error"], "invalid_value");
+        assert_eq!(v["field"], "env|e:~enum(dev,prod)");
+        assert_eq!(v["value"], "prd");
+        assert_eq!(v["suggestion"], "prod");
+        assert_eq!(v["command"], serde_json::json!(["app", "deploy"]));
+        assert_eq!(v["exit_code"], 5);
+
+        let out = error_json(ErrorKind::MissingRequired, "", "", "missing required flag: --token", None, &[]);
+        let v: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert!(v["field"].is_null() && v["value"].is_null() && v["suggestion"].is_null());
+    }
+
+    #[test]
+    fn test_closest_match() {
+        let names = ["verbose", "version", "output"];
+        assert_eq!(closest_match("verbos", names).as_deref(), Some("verbose"));
+        assert_eq!(closest_match("outptu", names).as_deref(), Some("output"));
+        // Truncated names match by prefix even beyond the typo threshold
+        assert_eq!(closest_match("out", names).as_deref(), Some("output"));
+        assert_eq!(closest_match("verb", names).as_deref(), Some("verbose"));
+        // Equal distance: the prefix match wins
+        assert_eq!(closest_match("tag", ["tap", "tags"]).as_deref(), Some("tags"));
+        assert_eq!(closest_match("xyzabc", names), None);
+        assert_eq!(closest_match("output", names), None);
+        assert_eq!(closest_match("ou", names), None);
+    }
+}
@@ -17,6 +17,7 @@ pub struct ShellVar {
 }
 
 const ATT_ARRAY: c_int = 0x0000004;
+const ATT_ASSOC: c_int = 0x0000040;
 const ATT_INVISIBLE: c_int = 0x0001000;
 
 extern "C" {
@@ -33,6 +34,8 @@ extern "C" {
     ) -> c_int;
     // $0 is stored in dollar_vars[0], not as a regular variable
     static dollar_vars: [*const c_char; 10];
+    // Function nesting depth of the running builtin's caller
+    static variable_context: c_int;
 }
 
 // ── Public helpers ────────────────────────────────────────────────
@@ -56,6 +59,18 @@ pub fn is_array(name: &str) -> bool {
     }
 }
 
+/// Whether `name` is an associative array (`local -A`).
+pub fn is_assoc(name: &str) -> bool {
+    if let Ok(cname) = CString::new(name) {
+        unsafe {
+            let var = find_variable(cname.as_ptr());
+            !var.is_null() && ((*var).attributes & ATT_ASSOC) != 0
+        }
+    } else {
+        false // coverage:off - CString null byte impossible for shell variable names
+    }
+}
+
 pub fn is_uninitialized(name: &str) -> bool {
     if let Ok(cname) = CString::new(name) {
         unsafe {
@@ -125,6 +140,34 @@ pub fn get_scalar(name: &str) -> Option<String> {
         .map(|s| s.to_string_lossy().into_owned())
 }
 
+/// Value of `name` only when it is a function-local variable (`local`) in
+/// the current call stack. Globals and variables inherited from the
+/// environment give None, so a setting cannot leak in from a caller.
+pub fn get_local_scalar(name: &str) -> Option<String> {
+    let cname = CString::new(name).ok()?;
+    let local = unsafe {
+        let var = find_variable(cname.as_ptr());
+        !var.is_null() && (*var).context > 0
+    };
+    if local {
+        get_scalar(name)
+    } else {
+        None
+    }
+}
+
+/// Whether `name` is declared `local` in the function that called the
+/// builtin itself, not inherited from a caller through dynamic scope.
+pub fn is_caller_local(name: &str) -> bool {
+    let Ok(cname) = CString::new(name) else {
+        return false; // coverage:off - CString null byte impossible for shell variable names
+    };
+    unsafe {
+        let var = find_variable(cname.as_ptr());
+        !var.is_null() && (*var).context == variable_context
+    }
+}
+
 pub fn set_scalar(name: &str, value: &str) {
     let _ = bash_builtins::variables::set(name, value);
 }
@@ -227,6 +270,26 @@ pub fn exec_capture(cmd: &str, result_var: &str) -> Option<String> {
     }
 }
 
+/// Call a shell function with `args` in a subshell, its stdout passed
+/// through. Returns the exit status and what it wrote to stderr.
+pub fn call_capture_stderr(func: &str, args: &[String]) -> (i32, String) {
+    set_scalar("__argsh_fn", func);
+    write_array("__argsh_a", args);
+    run_bash(
+        "__argsh_s=0; { __argsh_r=\"$(\"${__argsh_fn}\" ${__argsh_a[@]+\"${__argsh_a[@]}\"} 2>&1 >&3 3>&-)\"; } 3>&1 || __argsh_s=$?",
+    );
+    let status = get_scalar("__argsh_s").and_then(|s| s.parse().ok()).unwrap_or(1);
+    (status, get_scalar("__argsh_r").unwrap_or_default())
+}
+
+/// Call a shell function with `args` in a subshell and return its stdout.
+pub fn call_capture_stdout(func: &str, args: &[String]) -> String {
+    set_scalar("__argsh_fn", func);
+    write_array("__argsh_a", args);
+    run_bash("__argsh_r=\"$(\"${__argsh_fn}\" ${__argsh_a[@]+\"${__argsh_a[@]}\"})\"");
+    get_scalar("__argsh_r").unwrap_or_default()
+}
+
 /// Write an error message to stderr.
 pub fn write_stderr(msg: &str) { // coverage:off - exit(2) prevents coverage flush in forked subshell
     use std::io::Write; // coverage:off - exit(2) prevents coverage flush in forked subshell
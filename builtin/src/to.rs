@@ -198,3 +198,393 @@ extern "C" fn to_string_builtin_fn(word_list: *const WordList) -> c_int {
     })
     .unwrap_or(1)
 }
+
+/// Shared body of the `to::*` builtins backed by `field::convert_type`:
+/// print the converted value, or return 1 when it is invalid.
+fn convert_builtin(word_list: *const WordList, type_name: &str) -> c_int {
+    std::panic::catch_unwind(|| {
+        let args = word_list_to_vec(word_list);
+        let value = args.first().map(|s| s.as_str()).unwrap_or("");
+        match crate::field::convert_type(type_name, value, "") {
+            Ok(converted) => {
+                println!("{}", converted);
+                0
+            }
+            Err(_) => 1,
+        }
+    })
+    .unwrap_or(1)
+}
+
+// ── to::duration ─────────────────────────────────────────────────
+
+static TO_DURATION_LONG_DOC: [SyncPtr; 2] = [
+    SyncPtr(c"Convert a duration (90s, 5m, 1h30m) to seconds.".as_ptr()),
+    SyncPtr(std::ptr::null()),
+];
+
+#[export_name = "to::duration_struct"]
+pub static mut TO_DURATION_STRUCT: BashBuiltin = BashBuiltin {
+    name: c"to::duration".as_ptr(),
+    function: to_duration_builtin_fn,
+    flags: BUILTIN_ENABLED,
+    short_doc: c"to::duration <value>".as_ptr(),
+    long_doc: TO_DURATION_LONG_DOC.as_ptr().cast(),
+    handle: std::ptr::null(),
+};
+
+#[export_name = "to::duration_builtin_load"]
+pub extern "C" fn to_duration_builtin_load(_name: *const c_char) -> c_int { 1 }
+
+#[export_name = "to::duration_builtin_unload"]
+pub extern "C" fn to_duration_builtin_unload(_name: *const c_char) {} // coverage:off - bash internal callback, never called during tests
+
+extern "C" fn to_duration_builtin_fn(word_list: *const WordList) -> c_int {
+    convert_builtin(word_list, "duration")
+}
+
+// ── to::size ─────────────────────────────────────────────────────
+
+static TO_SIZE_LONG_DOC: [SyncPtr; 2] = [
+    SyncPtr(c"Convert a size (10MiB, 1.5GB) to bytes.".as_ptr()),
+    SyncPtr(std::ptr::null()),
+];
+
+#[export_name = "to::size_struct"]
+pub static mut TO_SIZE_STRUCT: BashBuiltin = BashBuiltin {
+    name: c"to::size".as_ptr(),
+    function: to_size_builtin_fn,
+    flags: BUILTIN_ENABLED,
+    short_doc: c"to::size <value>".as_ptr(),
+    long_doc: TO_SIZE_LONG_DOC.as_ptr().cast(),
+    handle: std::ptr::null(),
+};
+
+#[export_name = "to::size_builtin_load"]
+pub extern "C" fn to_size_builtin_load(_name: *const c_char) -> c_int { 1 }
+
+#[export_name = "to::size_builtin_unload"]
+pub extern "C" fn to_size_builtin_unload(_name: *const c_char) {} // coverage:off - bash internal callback, never called during tests
+
+extern "C" fn to_size_builtin_fn(word_list: *const WordList) -> c_int {
+    convert_builtin(word_list, "size")
+}
+
+// ── to::url ──────────────────────────────────────────────────────
+
+static TO_URL_LONG_DOC: [SyncPtr; 2] = [
+    SyncPtr(c"Validate a URL (scheme://host[:port]...).".as_ptr()),
+    SyncPtr(std::ptr::null()),
+];
+
+#[export_name = "to::url_struct"]
+pub static mut TO_URL_STRUCT: BashBuiltin = BashBuiltin {
+    name: c"to::url".as_ptr(),
+    function: to_url_builtin_fn,
+    flags: BUILTIN_ENABLED,
+    short_doc: c"to::url <url>".as_ptr(),
+    long_doc: TO_URL_LONG_DOC.as_ptr().cast(),
+    handle: std::ptr::null(),
+};
+
+#[export_name = "to::url_builtin_load"]
+pub extern "C" fn to_url_builtin_load(_name: *const c_char) -> c_int { 1 }
+
+#[export_name = "to::url_builtin_unload"]
+pub extern "C" fn to_url_builtin_unload(_name: *const c_char) {} // coverage:off - bash internal callback, never called during tests
+
+extern "C" fn to_url_builtin_fn(word_list: *const WordList) -> c_int {
+    convert_builtin(word_list, "url")
+}
+
+// ── to::dir ──────────────────────────────────────────────────────
+
+static TO_DIR_LONG_DOC: [SyncPtr; 2] = [
+    SyncPtr(c"Validate that the value is an existing directory.".as_ptr()),
+    SyncPtr(std::ptr::null()),
+];
+
+#[export_name = "to::dir_struct"]
+pub static mut TO_DIR_STRUCT: BashBuiltin = BashBuiltin {
+    name: c"to::dir".as_ptr(),
+    function: to_dir_builtin_fn,
+    flags: BUILTIN_ENABLED,
+    short_doc: c"to::dir <path>".as_ptr(),
+    long_doc: TO_DIR_LONG_DOC.as_ptr().cast(),
+    handle: std::ptr::null(),
+};
+
+#[export_name = "to::dir_builtin_load"]
+pub extern "C" fn to_dir_builtin_load(_name: *const c_char) -> c_int { 1 }
+
+#[export_name = "to::dir_builtin_unload"]
+pub extern "C" fn to_dir_builtin_unload(_name: *const c_char) {} // coverage:off - bash internal callback, never called during tests
+
+extern "C" fn to_dir_builtin_fn(word_list: *const WordList) -> c_int {
+    convert_builtin(word_list, "dir")
+}
+
+// ── to::path ─────────────────────────────────────────────────────
+
+static TO_PATH_LONG_DOC: [SyncPtr; 2] = [
+    SyncPtr(c"Normalize a path (resolve . and .. segments).".as_ptr()),
+    SyncPtr(std::ptr::null()),
+];
+
+#[export_name = "to::path_struct"]
+pub static mut TO_PATH_STRUCT: BashBuiltin = BashBuiltin {
+    name: c"to::path".as_ptr(),
+    function: to_path_builtin_fn,
+    flags: BUILTIN_ENABLED,
+    short_doc: c"to::path <path>".as_ptr(),
+    long_doc: TO_PATH_LONG_DOC.as_ptr().cast(),
+    handle: std::ptr::null(),
+};
+
+#[export_name = "to::path_builtin_load"]
+pub extern "C" fn to_path_builtin_load(_name: *const c_char) -> c_int { 1 }
+
+#[export_name = "to::path_builtin_unload"]
+pub extern "C" fn to_path_builtin_unload(_name: *const c_char) {} // coverage:off - bash internal callback, never called during tests
+
+extern "C" fn to_path_builtin_fn(word_list: *const WordList) -> c_int {
+    convert_builtin(word_list, "path")
+}
+
+// ── to::json ─────────────────────────────────────────────────────
+
+static TO_JSON_LONG_DOC: [SyncPtr; 2] = [
+    SyncPtr(c"Validate that the value is well-formed JSON.".as_ptr()),
+    SyncPtr(std::ptr::null()),
+];
+
+#[export_name = "to::json_struct"]
+pub static mut TO_JSON_STRUCT: BashBuiltin = BashBuiltin {
+    name: c"to::json".as_ptr(),
+    function: to_json_builtin_fn,
+    flags: BUILTIN_ENABLED,
+    short_doc: c"to::json <value>".as_ptr(),
+    long_doc: TO_JSON_LONG_DOC.as_ptr().cast(),
+    handle: std::ptr::null(),
+};
+
+#[export_name = "to::json_builtin_load"]
+pub extern "C" fn to_json_builtin_load(_name: *const c_char) -> c_int { 1 }
+
+#[export_name = "to::json_builtin_unload"]
+pub extern "C" fn to_json_builtin_unload(_name: *const c_char) {} // coverage:off - bash internal callback, never called during tests
+
+extern "C" fn to_json_builtin_fn(word_list: *const WordList) -> c_int {
+    convert_builtin(word_list, "json")
+}
+
+// ── Native conversions shared with field::convert_type ───────────
+
+/// Parse a duration (`90`, `90s`, `5m`, `1h30m`, `2d`) into whole seconds.
+/// Units: `s`, `m`, `h`, `d`, `w`; a bare number is seconds.
+pub fn duration(value: &str) -> Option<u64> {
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(secs);
+    }
+    let mut total: u64 = 0;
+    let mut digits = String::new();
+    for c in value.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let unit: u64 = match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 3_600,
+            'd' => 86_400,
+            'w' => 604_800,
+            _ => return None,
+        };
+        let n = digits.parse::<u64>().ok()?;
+        total = total.checked_add(n.checked_mul(unit)?)?;
+        digits.clear();
+    }
+    (!value.is_empty() && digits.is_empty()).then_some(total)
+}
+
+/// Parse a size (`512`, `10KB`, `10MiB`, `1.5G`) into bytes. Decimal units
+/// (`KB`, `MB`, `GB`, `TB`) are powers of 1000; binary units (`KiB` … `TiB`)
+/// and the bare letters (`K` … `T`) are powers of 1024. Case-insensitive.
+pub fn size(value: &str) -> Option<u64> {
+    let split = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let multiplier: u64 = match unit.to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "kb" => 1_000,
+        "mb" => 1_000_000,
+        "gb" => 1_000_000_000,
+        "tb" => 1_000_000_000_000,
+        "k" | "kib" => 1 << 10,
+        "m" | "mib" => 1 << 20,
+        "g" | "gib" => 1 << 30,
+        "t" | "tib" => 1 << 40,
+        _ => return None,
+    };
+    let (int, frac) = number.split_once('.').unwrap_or((number, ""));
+    let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if int.is_empty() || !is_digits(int) || !is_digits(frac) || (number.contains('.') && frac.is_empty()) {
+        return None;
+    }
+    let whole = int.parse::<u64>().ok()?.checked_mul(multiplier)?;
+    // Fractional bytes are truncated; 12 digits are plenty for TiB precision
+    let frac = &frac[..frac.len().min(12)];
+    let part = if frac.is_empty() {
+        0
+    } else {
+        let scaled = frac.parse::<u128>().ok()? * u128::from(multiplier) / 10u128.pow(frac.len() as u32);
+        u64::try_from(scaled).ok()?
+    };
+    whole.checked_add(part)
+}
+
+/// Validate `scheme://host[:port][/path][?query][#fragment]`. Credentials
+/// (`user@`) and bracketed IPv6 hosts are accepted; whitespace is not.
+pub fn is_url(value: &str) -> bool {
+    if value.chars().any(char::is_whitespace) {
+        return false;
+    }
+    let Some((scheme, rest)) = value.split_once("://") else {
+        return false;
+    };
+    let scheme_ok = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    if !scheme_ok {
+        return false;
+    }
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or("");
+    if authority.is_empty() && scheme.eq_ignore_ascii_case("file") {
+        return true;
+    }
+    let host_port = authority.rsplit_once('@').map_or(authority, |(_, h)| h);
+    let (host_ok, port) = if let Some(v6) = host_port.strip_prefix('[') {
+        let Some((addr, after)) = v6.split_once(']') else {
+            return false;
+        };
+        if !after.is_empty() && !after.starts_with(':') {
+            return false;
+        }
+        let addr_ok = !addr.is_empty()
+            && addr.chars().all(|c| c.is_ascii_hexdigit() || c == ':' || c == '.');
+        (addr_ok, after.strip_prefix(':'))
+    } else {
+        let (host, port) = match host_port.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (host_port, None),
+        };
+        let host_ok = !host.is_empty()
+            && host.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_'));
+        (host_ok, port)
+    };
+    let port_ok = match port {
+        Some(p) => p.parse::<u16>().is_ok(),
+        None => true,
+    };
+    host_ok && port_ok
+}
+
+/// Lexically normalize a path: drop empty and `.` segments and resolve `..`
+/// against the preceding segment. Symlinks are not followed.
+pub fn normalize_path(value: &str) -> String {
+    let absolute = value.starts_with('/');
+    let mut parts: Vec<&str> = Vec::new();
+    for segment in value.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                if parts.last().is_some_and(|p| *p != "..") {
+                    parts.pop();
+                } else if !absolute {
+                    parts.push("..");
+                }
+            }
+            s => parts.push(s),
+        }
+    }
+    let joined = parts.join("/");
+    if absolute {
+        format!("/{}", joined)
+    } else if joined.is_empty() {
+        ".".to_string()
+    } else {
+        joined
+    }
+}
+
+/// Whether `value` is well-formed JSON.
+pub fn is_json(value: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(value).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duration() {
+        assert_eq!(duration("90"), Some(90));
+        assert_eq!(duration("90s"), Some(90));
+        assert_eq!(duration("5m"), Some(300));
+        assert_eq!(duration("1h30m"), Some(5_400));
+        assert_eq!(duration("1d2h"), Some(93_600));
+        assert_eq!(duration(""), None);
+        assert_eq!(duration("5"), Some(5));
+        assert_eq!(duration("h"), None);
+        assert_eq!(duration("5x"), None);
+        assert_eq!(duration("1h30"), None);
+        assert_eq!(duration("-5s"), None);
+    }
+
+    #[test]
+    fn test_size() {
+        assert_eq!(size("512"), Some(512));
+        assert_eq!(size("10KB"), Some(10_000));
+        assert_eq!(size("10MiB"), Some(10 * 1024 * 1024));
+        assert_eq!(size("1.5G"), Some(1_610_612_736));
+        assert_eq!(size("2kib"), Some(2_048));
+        assert_eq!(size("10XB"), None);
+        assert_eq!(size("MiB"), None);
+        assert_eq!(size("1."), None);
+        assert_eq!(size("99999999999TiB"), None);
+    }
+
+    #[test]
+    fn test_is_url() {
+        assert!(is_url("https://example.com"));
+        assert!(is_url("http://localhost:8080/api?q=1#top"));
+        assert!(is_url("postgres://user:pw@db.internal:5432/app"));
+        assert!(is_url("http://[::1]:3000/"));
+        assert!(is_url("file:///etc/hosts"));
+        assert!(!is_url("example.com"));
+        assert!(!is_url("https://"));
+        assert!(!is_url("https://exa mple.com"));
+        assert!(!is_url("1http://example.com"));
+        assert!(!is_url("http://example.com:http"));
+        assert!(!is_url("http://example.com:70000"));
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(normalize_path("a/./b//c/"), "a/b/c");
+        assert_eq!(normalize_path("/a/b/../c"), "/a/c");
+        assert_eq!(normalize_path("/../a"), "/a");
+        assert_eq!(normalize_path("../a/../../b"), "../../b");
+        assert_eq!(normalize_path("a/.."), ".");
+        assert_eq!(normalize_path("/"), "/");
+    }
+
+    #[test]
+    fn test_is_json() {
+        assert!(is_json("{\"a\": [1, true, null]}"));
+        assert!(is_json("\"text\""));
+        assert!(!is_json("{a: 1}"));
+        assert!(!is_json(""));
+    }
+}
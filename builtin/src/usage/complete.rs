@@ -0,0 +1,415 @@
+//! :usage::complete builtin -- the hidden `__complete` command behind
+//! dynamic shell completion.
+//!
+//! The generated completion scripts run `<cmd> __complete <words...>` with
+//! the words after the command name, the last one being the word under the
+//! cursor. Candidates are printed one per line, followed by a directive:
+//! `:file` or `:dir` to let the shell complete paths, `:none` otherwise.
+//! A `--flag=<partial>` word completes the value after `=`, printed with
+//! the flag in front.
+//!
+//! Values are completed by the first of:
+//!   1. `<func>::complete::<field>` -- a hook on the command function
+//!   2. the choices of an enum field
+//!   3. `complete::<type>` -- a hook for every field of that type
+//!   4. paths, for `file`, `path`, `dir` and untyped fields
+//!
+//! Hooks receive the word under the cursor and print one candidate per line.
+
+use crate::{word_list_to_vec, BashBuiltin, SyncPtr, WordList, BUILTIN_ENABLED};
+use crate::shared;
+use crate::shell;
+use std::ffi::{c_char, c_int};
+use std::io::Write;
+use super::{build_command_tree, extract_flags, CommandNode, FlagInfo, PositionalInfo};
+
+// -- :usage::complete builtin registration ------------------------------------
+
+static USAGE_COMPLETE_LONG_DOC: [SyncPtr; 2] = [
+    SyncPtr(c"Print completion candidates for a partial command line.".as_ptr()),
+    SyncPtr(std::ptr::null()),
+];
+
+/// Print completion candidates for the generated completion scripts.
+#[export_name = ":usage::complete_struct"]
+pub static mut USAGE_COMPLETE_STRUCT: BashBuiltin = BashBuiltin {
+    name: c":usage::complete".as_ptr(),
+    function: usage_complete_builtin_fn,
+    flags: BUILTIN_ENABLED,
+    short_doc: c":usage::complete [word...] [-- title usage_pairs...]".as_ptr(),
+    long_doc: USAGE_COMPLETE_LONG_DOC.as_ptr().cast(),
+    handle: std::ptr::null(),
+};
+
+#[export_name = ":usage::complete_builtin_load"]
+pub extern "C" fn usage_complete_builtin_load(_name: *const c_char) -> c_int {
+    1 // success
+}
+
+#[export_name = ":usage::complete_builtin_unload"]
+pub extern "C" fn usage_complete_builtin_unload(_name: *const c_char) {} // coverage:off - bash internal callback
+
+extern "C" fn usage_complete_builtin_fn(word_list: *const WordList) -> c_int {
+    let code = std::panic::catch_unwind(|| {
+        let args = word_list_to_vec(word_list);
+        usage_complete_main(&args)
+    })
+    .unwrap_or(1); // coverage:off - catch_unwind: panics don't occur in practice
+
+    std::process::exit(if code == shared::HELP_EXIT || code == 0 { 0 } else { code }) // coverage:off
+}
+
+// -- :usage::complete implementation ------------------------------------------
+
+/// Main entry point for :usage::complete builtin.
+/// Called via "${usage[@]}" for `__complete`.
+/// Args: [word...] -- title usage_pairs...
+pub fn usage_complete_main(args: &[String]) -> i32 {
+    // The words may contain a `--` of their own; the usage pairs never do
+    let sep = args.iter().rposition(|s| s == "--").unwrap_or(args.len());
+    let words = &args[..sep];
+    let usage_pairs = args.get(sep + 2..).unwrap_or_default();
+    let args_arr = shell::read_array("args");
+
+    let caller = shell::get_funcname(0);
+    let tree = build_command_tree(usage_pairs, &args_arr, caller.as_deref());
+    let root_flags = extract_flags(&args_arr);
+    let root = Scope { func: caller.as_deref(), flags: &root_flags, children: &tree, positionals: &[] };
+
+    let (cur, before) = match words.split_last() {
+        Some((cur, before)) => (cur.as_str(), before),
+        None => ("", words),
+    };
+    let (scope, target) = resolve(root, before, cur);
+
+    let (candidates, directive) = match target {
+        Target::Flags => (scope.flag_words(), Directive::None),
+        Target::Commands => (visible(scope.children).map(|n| n.name.clone()).collect(), Directive::None),
+        Target::Value(field) => complete_value(scope.func, &field, cur),
+        Target::InlineValue(field) => {
+            let (flag, value) = cur.split_once('=').unwrap_or((cur, ""));
+            let (values, directive) = complete_value(scope.func, &field, value);
+            (values.into_iter().map(|v| format!("{}={}", flag, v)).collect(), directive)
+        }
+        Target::Nothing => (Vec::new(), Directive::None),
+    };
+
+    let out = std::io::stdout();
+    let mut out = out.lock();
+    for candidate in candidates.iter().filter(|c| c.starts_with(cur)) {
+        let _ = writeln!(out, "{}", candidate);
+    }
+    let _ = writeln!(out, ":{}", directive.as_str());
+    0
+}
+
+/// A command level while walking the words: its function, flags,
+/// subcommands and positionals.
+#[derive(Clone, Copy)]
+struct Scope<'a> {
+    func: Option<&'a str>,
+    flags: &'a [FlagInfo],
+    children: &'a [CommandNode],
+    positionals: &'a [PositionalInfo],
+}
+
+impl<'a> Scope<'a> {
+    fn enter(node: &'a CommandNode) -> Self {
+        Scope {
+            func: node.func.as_deref(),
+            flags: &node.flags,
+            children: &node.children,
+            positionals: &node.positionals,
+        }
+    }
+
+    /// The flag spelled by `word` (`--name` or `-s`), if any.
+    fn flag(&self, word: &str) -> Option<&'a FlagInfo> {
+        match word.strip_prefix("--") {
+            Some(name) => self.flags.iter().find(|f| f.name == name),
+            None => {
+                let short = word.strip_prefix('-').filter(|s| s.chars().count() == 1)?;
+                self.flags.iter().find(|f| f.short.as_deref() == Some(short))
+            }
+        }
+    }
+
+    /// Long, short and negated forms of the flags that are not deprecated,
+    /// with `--help`.
+    fn flag_words(&self) -> Vec<String> {
+        let mut words = Vec::new();
+        for flag in self.flags.iter().filter(|f| f.deprecated.is_none()) {
+            words.push(format!("--{}", flag.name));
+            if let Some(ref short) = flag.short {
+                words.push(format!("-{}", short));
+            }
+            if let Some(negated) = flag.negated_name() {
+                words.push(format!("--{}", negated));
+            }
+        }
+        if !self.flags.iter().any(|f| f.name == "help") {
+            words.extend(["--help".to_string(), "-h".to_string()]);
+        }
+        words
+    }
+}
+
+/// What the word under the cursor is.
+enum Target<'a> {
+    Flags,
+    Commands,
+    Value(Field<'a>),
+    /// The value after `=` in a `--flag=<partial>` word
+    InlineValue(Field<'a>),
+    Nothing,
+}
+
+/// A flag or positional whose value is being completed.
+struct Field<'a> {
+    name: &'a str,
+    type_name: &'a str,
+    choices: &'a [String],
+}
+
+/// How the shell should go on after the candidates.
+#[derive(Debug, PartialEq)]
+enum Directive {
+    None,
+    File,
+    Dir,
+}
+
+impl Directive {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Directive::None => "none",
+            Directive::File => "file",
+            Directive::Dir => "dir",
+        }
+    }
+}
+
+/// Walk the words before the cursor down the command tree, the way `:usage`
+/// dispatches them, and tell what `cur` completes in the scope reached.
+/// A flag of a parent command is still known below it, as `:usage` parses
+/// its own flags after the command name too; its value is completed in the
+/// scope that declares it.
+fn resolve<'a>(root: Scope<'a>, before: &[String], cur: &str) -> (Scope<'a>, Target<'a>) {
+    let mut scope = root;
+    let mut parents: Vec<Scope<'a>> = Vec::new();
+    let mut positional = 0;
+    let mut terminated = false;
+    let mut words = before.iter().peekable();
+    // The value flag spelled by `word`, and the scope that declares it
+    let value_flag = |scope: &Scope<'a>, parents: &[Scope<'a>], word: &str| {
+        std::iter::once(scope)
+            .chain(parents.iter().rev())
+            .find_map(|s| s.flag(word).map(|flag| (*s, flag)))
+            .filter(|(_, flag)| !flag.is_boolean)
+    };
+
+    while let Some(word) = words.next() {
+        if !terminated && word == "--" {
+            terminated = true;
+        } else if !terminated && word.starts_with('-') && word.len() > 1 {
+            if let Some((owner, flag)) = value_flag(&scope, &parents, word) {
+                // bash splits `--flag=value` into three words
+                words.next_if(|w| *w == "=");
+                // The flag's value is the word under the cursor
+                if words.next().is_none() {
+                    return (owner, Target::Value(Field::flag(flag)));
+                }
+            }
+        } else if let Some(node) = scope.children.iter().find(|n| !terminated && positional == 0 && n.name == *word) {
+            parents.push(scope);
+            scope = Scope::enter(node);
+        } else {
+            positional += 1;
+        }
+    }
+
+    if let Some((flag, _)) = cur.split_once('=').filter(|_| !terminated && cur.starts_with("--")) {
+        if let Some((owner, flag)) = value_flag(&scope, &parents, flag) {
+            return (owner, Target::InlineValue(Field::flag(flag)));
+        }
+    }
+    if !terminated && cur.starts_with('-') {
+        return (scope, Target::Flags);
+    }
+    if positional == 0 && visible(scope.children).next().is_some() {
+        return (scope, Target::Commands);
+    }
+    let pos = scope.positionals.get(positional).or_else(|| scope.positionals.last().filter(|p| p.is_variadic));
+    match pos {
+        Some(pos) => (scope, Target::Value(Field::positional(pos))),
+        None => (scope, Target::Nothing),
+    }
+}
+
+impl<'a> Field<'a> {
+    fn flag(flag: &'a FlagInfo) -> Self {
+        Field { name: &flag.name, type_name: &flag.type_name, choices: &flag.choices }
+    }
+
+    fn positional(pos: &'a PositionalInfo) -> Self {
+        Field { name: &pos.name, type_name: &pos.type_name, choices: &pos.choices }
+    }
+}
+
+/// Candidates for a field value: from a hook, the enum choices, or paths.
+fn complete_value(func: Option<&str>, field: &Field, cur: &str) -> (Vec<String>, Directive) {
+    let cur = [cur.to_string()];
+    let field_hook = func.map(|f| format!("{}::complete::{}", f, field.name.replace('-', "_")));
+    if let Some(hook) = field_hook.filter(|h| shell::function_exists(h)) {
+        return (hook_lines(&hook, &cur), Directive::None);
+    }
+    if !field.choices.is_empty() {
+        return (field.choices.to_vec(), Directive::None);
+    }
+    let type_hook = format!("complete::{}", field.type_name);
+    if shell::function_exists(&type_hook) {
+        return (hook_lines(&type_hook, &cur), Directive::None);
+    }
+    (Vec::new(), path_directive(field.type_name))
+}
+
+fn hook_lines(hook: &str, args: &[String]) -> Vec<String> {
+    shell::call_capture_stdout(hook, args).lines().map(str::to_string).collect()
+}
+
+/// Paths for path-like and untyped fields, nothing for the others.
+fn path_directive(type_name: &str) -> Directive {
+    match type_name {
+        "file" | "path" | "string" | "" => Directive::File,
+        "dir" => Directive::Dir,
+        _ => Directive::None,
+    }
+}
+
+/// Hidden and deprecated commands still resolve but are not offered.
+fn visible(nodes: &[CommandNode]) -> impl Iterator<Item = &CommandNode> {
+    nodes.iter().filter(|n| !n.hidden && n.deprecated().is_none())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flag(name: &str, short: Option<&str>, is_boolean: bool) -> FlagInfo {
+        FlagInfo {
+            name: name.to_string(),
+            short: short.map(str::to_string),
+            is_boolean,
+            default_on: is_boolean, // offers the `--no-` form
+            type_name: if is_boolean { String::new() } else { "string".to_string() },
+            ..FlagInfo::help()
+        }
+    }
+
+    fn node(path: &[&str], flags: Vec<FlagInfo>, children: Vec<CommandNode>) -> CommandNode {
+        CommandNode {
+            name: path.last().unwrap().to_string(),
+            desc: String::new(),
+            full_path: path.iter().map(|s| s.to_string()).collect(),
+            flags,
+            positionals: Vec::new(),
+            children,
+            hidden: false,
+            annotations: Vec::new(),
+            func: Some(format!("tool::{}", path.join("::"))),
+        }
+    }
+
+    fn words(s: &str) -> Vec<String> {
+        s.split_whitespace().map(str::to_string).collect()
+    }
+
+    /// tool [-c config] remote add [--mode mode] <name> [urls...]
+    fn tree() -> Vec<CommandNode> {
+        let mut add = node(&["remote", "add"], vec![flag("mode", None, false), flag("fetch", Some("f"), true)], Vec::new());
+        add.positionals = vec![
+            PositionalInfo {
+                name: "name".to_string(),
+                desc: String::new(),
+                type_name: "string".to_string(),
+                choices: Vec::new(),
+                required: true,
+                is_variadic: false,
+            },
+            PositionalInfo {
+                name: "urls".to_string(),
+                desc: String::new(),
+                type_name: "url".to_string(),
+                choices: Vec::new(),
+                required: false,
+                is_variadic: true,
+            },
+        ];
+        let mut gc = node(&["gc"], Vec::new(), Vec::new());
+        gc.hidden = true;
+        vec![node(&["remote"], Vec::new(), vec![add]), gc]
+    }
+
+    fn target_name(target: &Target) -> String {
+        match target {
+            Target::Flags => "flags".to_string(),
+            Target::Commands => "commands".to_string(),
+            Target::Value(field) => format!("value:{}", field.name),
+            Target::InlineValue(field) => format!("inline:{}", field.name),
+            Target::Nothing => "nothing".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_resolve_walks_command_path() {
+        let tree = tree();
+        let root_flags = vec![flag("config", Some("c"), false)];
+        let root = Scope { func: Some("tool"), flags: &root_flags, children: &tree, positionals: &[] };
+        let check = |before: &str, cur: &str| {
+            let (scope, target) = resolve(root, &words(before), cur);
+            (scope.func.unwrap_or_default().to_string(), target_name(&target))
+        };
+
+        assert_eq!(check("", ""), ("tool".to_string(), "commands".to_string()));
+        assert_eq!(check("-c", ""), ("tool".to_string(), "value:config".to_string()));
+        // A flag value is never taken for a command
+        assert_eq!(check("-c remote", ""), ("tool".to_string(), "commands".to_string()));
+        assert_eq!(check("remote", "--"), ("tool::remote".to_string(), "flags".to_string()));
+        assert_eq!(check("remote add", ""), ("tool::remote::add".to_string(), "value:name".to_string()));
+        assert_eq!(check("remote add --mode", ""), ("tool::remote::add".to_string(), "value:mode".to_string()));
+        assert_eq!(check("remote add -f origin", ""), ("tool::remote::add".to_string(), "value:urls".to_string()));
+        assert_eq!(check("remote add origin a b", ""), ("tool::remote::add".to_string(), "value:urls".to_string()));
+        // After `--` every word is a positional
+        assert_eq!(check("remote add --", "-x"), ("tool::remote::add".to_string(), "value:name".to_string()));
+        // Hidden commands are walked into but not offered
+        assert_eq!(check("gc", ""), ("tool::gc".to_string(), "nothing".to_string()));
+        // A parent's value flag is known below it and completed where declared
+        assert_eq!(check("remote add -c", ""), ("tool".to_string(), "value:config".to_string()));
+        assert_eq!(check("remote add -c prod", ""), ("tool::remote::add".to_string(), "value:name".to_string()));
+        assert_eq!(check("remote -c prod", ""), ("tool::remote".to_string(), "commands".to_string()));
+        // `--flag=<partial>`, whole or split by bash at the `=`
+        assert_eq!(check("remote add", "--mode=pu"), ("tool::remote::add".to_string(), "inline:mode".to_string()));
+        assert_eq!(check("remote add", "--config="), ("tool".to_string(), "inline:config".to_string()));
+        assert_eq!(check("remote add", "--fetch=t"), ("tool::remote::add".to_string(), "flags".to_string()));
+        assert_eq!(check("remote add --mode =", ""), ("tool::remote::add".to_string(), "value:mode".to_string()));
+        assert_eq!(check("remote add --mode = push", ""), ("tool::remote::add".to_string(), "value:name".to_string()));
+    }
+
+    #[test]
+    fn test_scope_flag_words() {
+        let flags = vec![flag("config", Some("c"), false), flag("fetch", None, true)];
+        let scope = Scope { func: None, flags: &flags, children: &[], positionals: &[] };
+        assert_eq!(scope.flag_words(), vec!["--config", "-c", "--fetch", "--no-fetch", "--help", "-h"]);
+        assert_eq!(scope.flag("-c").map(|f| f.name.as_str()), Some("config"));
+        assert_eq!(scope.flag("--fetch").map(|f| f.name.as_str()), Some("fetch"));
+        assert!(scope.flag("-cx").is_none());
+    }
+
+    #[test]
+    fn test_path_directive() {
+        assert_eq!(path_directive("file"), Directive::File);
+        assert_eq!(path_directive("string"), Directive::File);
+        assert_eq!(path_directive("dir"), Directive::Dir);
+        assert_eq!(path_directive("int"), Directive::None);
+    }
+}
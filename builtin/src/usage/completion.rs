@@ -7,7 +7,8 @@ use crate::shared;
 use crate::shell;
 use std::ffi::{c_char, c_int};
 use std::io::Write;
-use super::{extract_subcommands, extract_flags};
+use std::path::{Path, PathBuf};
+use super::{build_command_tree, extract_flags, CommandNode, FlagInfo, PositionalInfo};
 
 /// Escape a string for safe inclusion in a bash single-quoted word list.
 fn bash_escape(s: &str) -> String {
@@ -21,20 +22,111 @@ fn fish_escape(s: &str) -> String {
     s.replace('\'', "'\"'\"'")
 }
 
+/// The subcommands and flags offered after one command path (empty for the
+/// top level). Hidden and deprecated entries still work but are not
+/// suggested.
+struct Level {
+    path: Vec<String>,
+    cmds: Vec<(String, String)>, // name, description
+    flags: Vec<FlagInfo>,
+    positionals: Vec<PositionalInfo>,
+}
+
+impl Level {
+    /// Path as matched by the generated scripts: words joined by spaces.
+    fn key(&self) -> String {
+        self.path.join(" ")
+    }
+
+    /// Long, short and negated forms of every flag.
+    fn flag_words(&self) -> Vec<String> {
+        self.flags.iter().flat_map(|f| {
+            let mut words = vec![format!("--{}", f.name)];
+            if let Some(ref s) = f.short {
+                words.push(format!("-{}", s));
+            }
+            if let Some(negated) = f.negated_name() {
+                words.push(format!("--{}", negated));
+            }
+            words
+        }).collect()
+    }
+
+    /// `--name` and `-s` of the flags that take a value.
+    fn value_flag_words(&self) -> Vec<String> {
+        self.flags.iter().filter(|f| !f.is_boolean).flat_map(|f| {
+            std::iter::once(format!("--{}", f.name)).chain(f.short.iter().map(|s| format!("-{}", s)))
+        }).collect()
+    }
+}
+
+/// Every command level of the tree, top level first, parents before children.
+fn completion_levels(usage_pairs: &[String], args_arr: &[String]) -> Vec<Level> {
+    let caller = shell::get_funcname(0);
+    let tree = build_command_tree(usage_pairs, args_arr, caller.as_deref());
+    let mut flags = extract_flags(args_arr);
+    flags.retain(|f| f.deprecated.is_none());
+    let mut levels = vec![Level { path: Vec::new(), cmds: offered_cmds(&tree), flags, positionals: Vec::new() }];
+    collect_levels(tree, &mut levels);
+    levels
+}
+
+fn collect_levels(nodes: Vec<CommandNode>, levels: &mut Vec<Level>) {
+    for node in nodes {
+        if node.hidden || node.deprecated().is_some() {
+            continue;
+        }
+        let mut flags: Vec<FlagInfo> = node.flags.into_iter().filter(|f| f.deprecated.is_none()).collect();
+        if !flags.iter().any(|f| f.name == "help") {
+            flags.push(FlagInfo::help());
+        }
+        levels.push(Level {
+            path: node.full_path,
+            cmds: offered_cmds(&node.children),
+            flags,
+            positionals: node.positionals,
+        });
+        collect_levels(node.children, levels);
+    }
+}
+
+fn offered_cmds(nodes: &[CommandNode]) -> Vec<(String, String)> {
+    nodes
+        .iter()
+        .filter(|n| !n.hidden && n.deprecated().is_none())
+        .map(|n| (n.name.clone(), n.desc.clone()))
+        .collect()
+}
+
+/// Shell function that asks `<cmd> __complete` for flag values and
+/// arguments: `__tool_complete`.
+fn complete_func(cmd_name: &str) -> String {
+    format!("_{}_complete", level_func(cmd_name, &[]))
+}
+
+/// Shell function name for a level: `_tool`, `_tool__remote__add`. Command
+/// words are joined with `__`, so `remote-add` (`_tool__remote_add`) and
+/// `remote add` get functions of their own.
+fn level_func(cmd_name: &str, path: &[String]) -> String {
+    path.iter().fold(format!("_{}", cmd_name.replace('-', "_")), |acc, w| {
+        format!("{}__{}", acc, w.replace('-', "_"))
+    })
+}
+
 // -- :usage::completion builtin registration ----------------------------------
 
 static USAGE_COMPLETION_LONG_DOC: [SyncPtr; 2] = [
-    SyncPtr(c"Generate shell completion scripts (bash, zsh, fish).".as_ptr()),
+    SyncPtr(c"Generate shell completion scripts (bash, zsh, fish, nu, powershell).".as_ptr()),
     SyncPtr(std::ptr::null()),
 ];
 
-/// Generate shell completion scripts (bash, zsh, fish) from the usage array.
+/// Generate shell completion scripts (bash, zsh, fish, nu, powershell) from the usage array.
 #[export_name = ":usage::completion_struct"]
 pub static mut USAGE_COMPLETION_STRUCT: BashBuiltin = BashBuiltin {
     name: c":usage::completion".as_ptr(),
     function: usage_completion_builtin_fn,
     flags: BUILTIN_ENABLED,
-    short_doc: c":usage::completion <shell> [-- title usage_pairs...]".as_ptr(),
+    short_doc: c":usage::completion <shell>|install [shell] [-- title usage_pairs...]".as_ptr(),
     long_doc: USAGE_COMPLETION_LONG_DOC.as_ptr().cast(),
     handle: std::ptr::null(),
 };
@@ -61,7 +153,7 @@ extern "C" fn usage_completion_builtin_fn(word_list: *const WordList) -> c_int {
 
 /// Main entry point for :usage::completion builtin.
 /// Called via "${usage[@]}" — generates shell completion scripts.
-/// Args: [shell_type] [-- title original_usage_pairs...]
+/// Args: [shell_type | install [shell] [--dir <dir>] [--dry-run]] [-- title original_usage_pairs...]
 pub fn usage_completion_main(args: &[String]) -> i32 {
     let sep = args.iter().position(|s| s == "--");
     let (user_args, meta) = match sep {
@@ -77,11 +169,16 @@ pub fn usage_completion_main(args: &[String]) -> i32 {
             shell::get_script_name() // coverage:off - defensive_check: always called via :usage dispatch which sets COMMANDNAME
         };
         println!("Generate shell completion scripts.\n");
-        println!("Usage: {} completion <shell>\n", cmd_str);
+        println!("Usage: {} completion <shell>", cmd_str);
+        println!("       {} completion install [bash|zsh|fish] [--dir <dir>] [--dry-run]\n", cmd_str);
         println!("Available shells:");
-        println!("  bash    Bash completion script");
-        println!("  zsh     Zsh completion script");
-        println!("  fish    Fish completion script");
+        println!("  bash          Bash completion script");
+        println!("  zsh           Zsh completion script");
+        println!("  fish          Fish completion script");
+        println!("  nu            Nushell completion script");
+        println!("  powershell    PowerShell completion script");
+        println!("\nInstall writes the script where the shell loads it from; the shell");
+        println!("defaults to $SHELL.");
         return shared::HELP_EXIT;
     }
 
@@ -100,25 +197,199 @@ pub fn usage_completion_main(args: &[String]) -> i32 {
         shell::get_script_name() // coverage:off - defensive_check: always called via :usage dispatch which sets COMMANDNAME
     };
 
+    if shell_type == "install" {
+        return install_completion(&user_args[1..], &cmd_name, title, usage_pairs, &args_arr);
+    }
+
     let out = std::io::stdout();
     let mut out = out.lock();
 
-    match shell_type.as_str() {
-        "bash" => generate_bash_completion(&mut out, &cmd_name, title, usage_pairs, &args_arr),
-        "zsh" => generate_zsh_completion(&mut out, &cmd_name, title, usage_pairs, &args_arr),
-        "fish" => generate_fish_completion(&mut out, &cmd_name, title, usage_pairs, &args_arr),
-        _ => {
-            return shared::error_usage("", &format!(
-                "unknown shell: {}. Use bash, zsh, or fish", shell_type
-            ));
+    if !generate_completion(&mut out, shell_type, &cmd_name, title, usage_pairs, &args_arr) {
+        return shared::error_usage("", &format!(
+            "unknown shell: {}. Use bash, zsh, fish, nu, or powershell", shell_type
+        ));
+    }
+    shared::HELP_EXIT
+}
+
+/// Write the completion script for `shell`; false if the shell is unknown.
+fn generate_completion<W: Write>(
+    out: &mut W,
+    shell: &str,
+    cmd_name: &str,
+    title: &str,
+    usage_pairs: &[String],
+    args_arr: &[String],
+) -> bool {
+    match shell {
+        "bash" => generate_bash_completion(out, cmd_name, title, usage_pairs, args_arr),
+        "zsh" => generate_zsh_completion(out, cmd_name, title, usage_pairs, args_arr),
+        "fish" => generate_fish_completion(out, cmd_name, title, usage_pairs, args_arr),
+        "nu" => generate_nu_completion(out, cmd_name, title, usage_pairs, args_arr),
+        "powershell" => generate_powershell_completion(out, cmd_name, title, usage_pairs, args_arr),
+        _ => return false,
+    }
+    true
+}
+
+// -- completion install -------------------------------------------------------
+
+/// Shells whose completion directory `completion install` knows.
+const INSTALL_SHELLS: [&str; 3] = ["bash", "zsh", "fish"];
+
+/// Options of `completion install [shell] [--dir <dir>] [--dry-run]`.
+#[derive(Debug, Default, PartialEq)]
+struct InstallOpts {
+    shell: Option<String>,
+    dir: Option<String>,
+    dry_run: bool,
+}
+
+fn parse_install_args(args: &[String]) -> Result<InstallOpts, String> {
+    let mut opts = InstallOpts::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if let Some(dir) = arg.strip_prefix("--dir=") {
+            opts.dir = Some(dir.to_string());
+            continue;
+        }
+        match arg.as_str() {
+            "--dry-run" => opts.dry_run = true,
+            "--dir" => match args.next() {
+                Some(dir) => opts.dir = Some(dir.clone()),
+                None => return Err("missing value for flag: --dir".to_string()),
+            },
+            a if a.starts_with('-') => return Err(format!("unknown flag: {}", a)),
+            a if opts.shell.is_none() => opts.shell = Some(a.to_string()),
+            a => return Err(format!("too many arguments: {}", a)),
+        }
+    }
+    Ok(opts)
+}
+
+/// What installing did, or would do with `--dry-run`.
+#[derive(Debug, PartialEq)]
+enum Installed {
+    Created,
+    Updated,
+    UpToDate,
+}
+
+/// Write the completion script for the user's shell to the directory that
+/// shell loads completions from, unless it is already there.
+fn install_completion(
+    args: &[String],
+    cmd_name: &str,
+    title: &str,
+    usage_pairs: &[String],
+    args_arr: &[String],
+) -> i32 {
+    let opts = match parse_install_args(args) {
+        Ok(opts) => opts,
+        Err(msg) => return shared::error_usage("", &msg),
+    };
+    let Some(shell) = opts.shell.or_else(|| shell_name(shell::get_scalar("SHELL").as_deref())) else {
+        return shared::error_usage("", "cannot detect the shell from $SHELL. Name it: completion install bash|zsh|fish");
+    };
+    if !INSTALL_SHELLS.contains(&shell.as_str()) {
+        return shared::error_usage("", &format!("cannot install completion for {}. Use bash, zsh, or fish", shell));
+    }
+
+    let var = |name: &str| shell::get_scalar(name).filter(|v| !v.is_empty());
+    let Some(path) = install_path(&shell, cmd_name, opts.dir.as_deref(), var) else {
+        return shared::error_usage("", "cannot find the completion directory: $HOME is not set. Use --dir <dir>");
+    };
+
+    let mut script = Vec::new();
+    generate_completion(&mut script, &shell, cmd_name, title, usage_pairs, args_arr);
+    let installed = match install_script(&path, &script, opts.dry_run) {
+        Ok(installed) => installed,
+        Err(e) => {
+            eprintln!("Error: cannot write {}: {}", path.display(), e);
+            return 1;
         }
+    };
+
+    let action = match (&installed, opts.dry_run) {
+        (Installed::UpToDate, _) => "Already installed",
+        (Installed::Created, false) => "Installed",
+        (Installed::Updated, false) => "Updated",
+        (Installed::Created, true) => "Would install",
+        (Installed::Updated, true) => "Would update",
+    };
+    println!("{} {} completion for {}: {}", action, shell, cmd_name, path.display());
+
+    // zsh only autoloads from $fpath, which is rarely exported
+    let dir = path.parent().unwrap_or(&path);
+    let on_fpath = var("FPATH").is_some_and(|fpath| fpath.split(':').any(|d| Path::new(d) == dir));
+    if shell == "zsh" && !on_fpath {
+        println!("Add the directory to $fpath in ~/.zshrc, before compinit:");
+        println!("  fpath=({} $fpath)", dir.display());
     }
     shared::HELP_EXIT
 }
 
+/// Shell named by a `$SHELL` value: `/usr/bin/zsh` is `zsh`.
+fn shell_name(shell_var: Option<&str>) -> Option<String> {
+    let name = Path::new(shell_var?).file_name()?.to_str()?;
+    Some(name.to_string())
+}
+
+/// File the completion script of `cmd_name` goes to: in `dir` when given,
+/// else where `shell` loads completions from, found through `var`.
+///
+/// - bash: `$BASH_COMPLETION_USER_DIR/completions`, else
+///   `$XDG_DATA_HOME/bash-completion/completions`
+/// - zsh: `$XDG_DATA_HOME/zsh/site-functions`
+/// - fish: `$XDG_CONFIG_HOME/fish/completions`
+fn install_path(shell: &str, cmd_name: &str, dir: Option<&str>, var: impl Fn(&str) -> Option<String>) -> Option<PathBuf> {
+    let file = match shell {
+        "zsh" => format!("_{}", cmd_name),
+        "fish" => format!("{}.fish", cmd_name),
+        _ => cmd_name.to_string(),
+    };
+    if let Some(dir) = dir {
+        return Some(Path::new(dir).join(file));
+    }
+
+    let home = var("HOME");
+    // XDG base directories must be absolute; relative ones are ignored
+    let xdg = |name: &str, default: &str| match var(name).filter(|d| d.starts_with('/')) {
+        Some(d) => Some(PathBuf::from(d)),
+        None => home.as_ref().map(|h| Path::new(h).join(default)),
+    };
+    let dir = match shell {
+        "bash" => match var("BASH_COMPLETION_USER_DIR") {
+            Some(d) => PathBuf::from(d).join("completions"),
+            None => xdg("XDG_DATA_HOME", ".local/share")?.join("bash-completion/completions"),
+        },
+        "zsh" => xdg("XDG_DATA_HOME", ".local/share")?.join("zsh/site-functions"),
+        _ => xdg("XDG_CONFIG_HOME", ".config")?.join("fish/completions"),
+    };
+    Some(dir.join(file))
+}
+
+/// Write `script` to `path`, creating its directory; nothing is written
+/// when the file already holds it or on a dry run.
+fn install_script(path: &Path, script: &[u8], dry_run: bool) -> std::io::Result<Installed> {
+    let installed = match std::fs::read(path) {
+        Ok(current) if current == script => return Ok(Installed::UpToDate),
+        Ok(_) => Installed::Updated,
+        Err(_) => Installed::Created,
+    };
+    if !dry_run {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, script)?;
+    }
+    Ok(installed)
+}
+
 // -- Completion generators ----------------------------------------------------
 
-/// Generate bash completion script.
+/// Generate bash completion script. The words before the cursor are walked
+/// to find the command path; subcommands and flags are offered for it.
 fn generate_bash_completion<W: Write>(
     out: &mut W,
     cmd_name: &str,
@@ -126,37 +397,99 @@ fn generate_bash_completion<W: Write>(
     usage_pairs: &[String],
     args_arr: &[String],
 ) {
-    let cmds = extract_subcommands(usage_pairs);
-    let flags = extract_flags(args_arr);
-    let func_name = format!("_{}", cmd_name.replace('-', "_"));
+    let levels = completion_levels(usage_pairs, args_arr);
+    write_bash_completion(out, cmd_name, &levels);
+}
+
+fn write_bash_completion<W: Write>(out: &mut W, cmd_name: &str, levels: &[Level]) {
+    let func_name = level_func(cmd_name, &[]);
+    let complete = complete_func(cmd_name);
 
     let _ = writeln!(out, "# bash completion for {}", cmd_name);
+
+    // Candidates from `__complete`, the last line being the directive
+    let _ = writeln!(out, "{}() {{", complete);
+    let _ = writeln!(out, "    local -a lines");
+    let _ = writeln!(out, "    mapfile -t lines < <(\"${{COMP_WORDS[0]}}\" __complete \"${{COMP_WORDS[@]:1:COMP_CWORD}}\" 2>/dev/null)");
+    let _ = writeln!(out, "    (( ${{#lines[@]}} )) || return 0");
+    let _ = writeln!(out, "    case \"${{lines[-1]}}\" in");
+    let _ = writeln!(out, "        :dir) COMPREPLY=($(compgen -d -- \"${{cur}}\")) ;;");
+    let _ = writeln!(out, "        :none) compopt +o default 2>/dev/null ;;");
+    let _ = writeln!(out, "    esac");
+    let _ = writeln!(out, "    COMPREPLY+=(\"${{lines[@]:0:${{#lines[@]}}-1}}\")");
+    let _ = writeln!(out, "}}");
+    let _ = writeln!(out);
+
     let _ = writeln!(out, "{}() {{", func_name);
-    let _ = writeln!(out, "    local cur=\"${{COMP_WORDS[COMP_CWORD]}}\"");
+    let _ = writeln!(out, "    local cur=\"${{COMP_WORDS[COMP_CWORD]}}\" prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"");
+    let _ = writeln!(out, "    local path=\"\" word i");
     let _ = writeln!(out);
 
-    // Flags
-    let flag_words: Vec<String> = flags.iter().flat_map(|f| {
-        let mut words = vec![format!("--{}", f.name)];
-        if let Some(ref s) = f.short {
-            words.push(format!("-{}", s));
+    // Walk the command path, skipping the values of flags
+    let _ = writeln!(out, "    for (( i=1; i < COMP_CWORD; i++ )); do");
+    let _ = writeln!(out, "        word=\"${{COMP_WORDS[i]}}\"");
+    let _ = writeln!(out, "        case \"${{path}}:${{word}}\" in");
+    for level in levels {
+        let key = level.key();
+        for (name, _) in &level.cmds {
+            let next = if key.is_empty() { name.clone() } else { format!("{} {}", key, name) };
+            let _ = writeln!(out, "            \"{}:{}\") path=\"{}\" ;;", key, name, next);
+        }
+        let values = level.value_flag_words();
+        if !values.is_empty() {
+            let pattern: Vec<String> = values.iter().map(|w| format!("\"{}:{}\"", key, w)).collect();
+            let _ = writeln!(out, "            {}) (( i++ )) ;;", pattern.join("|"));
         }
-        words
-    }).collect();
+    }
+    let _ = writeln!(out, "        esac");
+    let _ = writeln!(out, "    done");
+    let _ = writeln!(out);
 
-    let _ = writeln!(out, "    if [[ \"${{cur}}\" == -* ]]; then");
-    let _ = writeln!(out, "        COMPREPLY=($(compgen -W '{}' -- \"${{cur}}\"))", bash_escape(&flag_words.join(" ")));
-    let _ = writeln!(out, "    else");
+    let _ = writeln!(out, "    case \"${{path}}\" in");
+    for level in levels {
+        let _ = writeln!(out, "        \"{}\")", level.key());
 
-    // Subcommands
-    let cmd_words: Vec<&str> = cmds.iter().map(|c| c.name.as_str()).collect();
-    let _ = writeln!(out, "        COMPREPLY=($(compgen -W '{}' -- \"${{cur}}\"))", bash_escape(&cmd_words.join(" ")));
-    let _ = writeln!(out, "    fi");
+        // Flag values: choices for enums, `__complete` otherwise
+        let value_flags: Vec<&FlagInfo> = level.flags.iter().filter(|f| !f.is_boolean).collect();
+        if !value_flags.is_empty() {
+            let _ = writeln!(out, "            case \"${{prev}}\" in");
+            for flag in value_flags {
+                let mut pattern = format!("--{}", flag.name);
+                if let Some(ref s) = flag.short {
+                    pattern.push_str(&format!("|-{}", s));
+                }
+                let _ = writeln!(out, "                {})", pattern);
+                if flag.choices.is_empty() {
+                    let _ = writeln!(out, "                    {}", complete);
+                } else {
+                    let _ = writeln!(out, "                    COMPREPLY=($(compgen -W '{}' -- \"${{cur}}\"))", bash_escape(&flag.choices.join(" ")));
+                }
+                let _ = writeln!(out, "                    return");
+                let _ = writeln!(out, "                    ;;");
+            }
+            let _ = writeln!(out, "            esac");
+        }
+
+        let _ = writeln!(out, "            if [[ \"${{cur}}\" == -* ]]; then");
+        let _ = writeln!(out, "                COMPREPLY=($(compgen -W '{}' -- \"${{cur}}\"))", bash_escape(&level.flag_words().join(" ")));
+        if !level.cmds.is_empty() {
+            let cmd_words: Vec<&str> = level.cmds.iter().map(|(name, _)| name.as_str()).collect();
+            let _ = writeln!(out, "            else");
+            let _ = writeln!(out, "                COMPREPLY=($(compgen -W '{}' -- \"${{cur}}\"))", bash_escape(&cmd_words.join(" ")));
+        } else if !level.positionals.is_empty() {
+            let _ = writeln!(out, "            else");
+            let _ = writeln!(out, "                {}", complete);
+        }
+        let _ = writeln!(out, "            fi");
+        let _ = writeln!(out, "            ;;");
+    }
+    let _ = writeln!(out, "    esac");
     let _ = writeln!(out, "}}");
     let _ = writeln!(out, "complete -o default -F {} {}", func_name, cmd_name);
 }
 
-/// Generate zsh completion script.
+/// Generate zsh completion script: one function per command level, each
+/// handing the words after a subcommand to the subcommand's function.
 fn generate_zsh_completion<W: Write>(
     out: &mut W,
     cmd_name: &str,
@@ -164,19 +497,46 @@ fn generate_zsh_completion<W: Write>(
     usage_pairs: &[String],
     args_arr: &[String],
 ) {
-    let cmds = extract_subcommands(usage_pairs);
-    let flags = extract_flags(args_arr);
-    let func_name = format!("_{}", cmd_name.replace('-', "_"));
+    let levels = completion_levels(usage_pairs, args_arr);
 
+    let complete = complete_func(cmd_name);
     let _ = writeln!(out, "#compdef {}", cmd_name);
     let _ = writeln!(out);
-    let _ = writeln!(out, "{}() {{", func_name);
 
-    if !cmds.is_empty() {
+    // Candidates from `__complete`, the last line being the directive. The
+    // level functions only see `words` from their subcommand on, so this
+    // reads the whole line as saved by the top-level function.
+    let _ = writeln!(out, "{}() {{", complete);
+    let _ = writeln!(out, "    local -a lines");
+    let _ = writeln!(out, "    lines=(\"${{(@f)$(\"${{{0}_words[1]}}\" __complete \"${{(@){0}_words[2,{0}_cword]}}\" 2>/dev/null)}}\")", complete);
+    let _ = writeln!(out, "    case \"${{lines[-1]}}\" in");
+    let _ = writeln!(out, "        :file) _files ;;");
+    let _ = writeln!(out, "        :dir) _files -/ ;;");
+    let _ = writeln!(out, "        *) compadd -- \"${{(@)lines[1,-2]}}\" ;;");
+    let _ = writeln!(out, "    esac");
+    let _ = writeln!(out, "}}");
+    for level in &levels {
+        let _ = writeln!(out);
+        write_zsh_level(out, cmd_name, level);
+    }
+    let _ = writeln!(out);
+    let _ = writeln!(out, "{} \"$@\"", level_func(cmd_name, &[]));
+}
+
+fn write_zsh_level<W: Write>(out: &mut W, cmd_name: &str, level: &Level) {
+    let _ = writeln!(out, "{}() {{", level_func(cmd_name, &level.path));
+    let complete = complete_func(cmd_name);
+
+    if level.path.is_empty() {
+        let _ = writeln!(out, "    local -a {}_words=(\"${{words[@]}}\")", complete);
+        let _ = writeln!(out, "    local {}_cword=${{CURRENT}}", complete);
+    }
+
+    if !level.cmds.is_empty() {
+        let _ = writeln!(out, "    local state");
         let _ = writeln!(out, "    local -a commands=(");
-        for cmd in &cmds {
-            let esc_desc = cmd.desc.replace('\'', "'\\''");
-            let _ = writeln!(out, "        '{}:{}'", cmd.name, esc_desc);
+        for (name, desc) in &level.cmds {
+            let _ = writeln!(out, "        '{}:{}'", name, desc.replace('\'', "'\\''"));
         }
         let _ = writeln!(out, "    )");
         let _ = writeln!(out);
@@ -184,42 +544,74 @@ fn generate_zsh_completion<W: Write>(
 
     let _ = write!(out, "    _arguments -s");
 
-    for flag in &flags {
+    for flag in &level.flags {
         let long = &flag.name;
         let esc_desc = flag.desc.replace('\'', "'\\''").replace('[', "\\[").replace(']', "\\]");
-        if let Some(ref short) = flag.short {
+        // Value action: choice list for enums, `__complete` otherwise
+        let action = if flag.choices.is_empty() {
+            format!("{{{}}}", complete)
+        } else {
+            let esc: Vec<String> = flag.choices.iter().map(|c| c.replace('\'', "'\\''")).collect();
+            format!("({})", esc.join(" "))
+        };
+        // Counters may repeat, so they are not excluded after first use
+        if flag.is_counter {
+            match flag.short {
+                Some(ref short) => {
+                    let _ = write!(out, " \\\n        '*'{{\"-{}\",\"--{}\"}}'[{}]'", short, long, esc_desc);
+                }
+                None => {
+                    let _ = write!(out, " \\\n        '*--{}[{}]'", long, esc_desc);
+                }
+            }
+        } else if let Some(ref short) = flag.short {
             if flag.is_boolean {
                 let _ = write!(out, " \\\n        '(-{} --{})'{{\"-{}\",\"--{}\"}}'[{}]'",
                     short, long, short, long, esc_desc);
             } else {
-                let _ = write!(out, " \\\n        '(-{} --{})'{{\"-{}\",\"--{}\"}}'[{}]:{}:'",
-                    short, long, short, long, esc_desc, flag.type_name);
+                let _ = write!(out, " \\\n        '(-{} --{})'{{\"-{}\",\"--{}\"}}'[{}]:{}:{}'",
+                    short, long, short, long, esc_desc, flag.type_name, action);
             }
         } else if flag.is_boolean {
             let _ = write!(out, " \\\n        '--{}[{}]'", long, esc_desc);
         } else {
-            let _ = write!(out, " \\\n        '--{}[{}]:{}:'", long, esc_desc, flag.type_name);
+            let _ = write!(out, " \\\n        '--{}[{}]:{}:{}'", long, esc_desc, flag.type_name, action);
+        }
+        if let Some(negated) = flag.negated_name() {
+            let _ = write!(out, " \\\n        '--{}[Disable --{}]'", negated, long);
         }
     }
 
-    if !cmds.is_empty() {
-        let _ = writeln!(out, " \\\n        '*::command:->commands'");
+    if level.cmds.is_empty() && !level.positionals.is_empty() {
+        let _ = write!(out, " \\\n        '*:argument:{{{}}}'", complete);
+    }
+
+    if !level.cmds.is_empty() {
+        let _ = writeln!(out, " \\\n        '1:command:->command' \\\n        '*::arg:->args'");
         let _ = writeln!(out);
         let _ = writeln!(out, "    case \"$state\" in");
-        let _ = writeln!(out, "        commands)");
+        let _ = writeln!(out, "        command)");
         let _ = writeln!(out, "            _describe 'command' commands");
         let _ = writeln!(out, "            ;;");
+        let _ = writeln!(out, "        args)");
+        let _ = writeln!(out, "            case \"${{words[1]}}\" in");
+        for (name, _) in &level.cmds {
+            let mut path = level.path.clone();
+            path.push(name.clone());
+            let _ = writeln!(out, "                {}) {} ;;", name, level_func(cmd_name, &path));
+        }
+        let _ = writeln!(out, "            esac");
+        let _ = writeln!(out, "            ;;");
         let _ = writeln!(out, "    esac");
     } else {
         let _ = writeln!(out);
     }
 
     let _ = writeln!(out, "}}");
-    let _ = writeln!(out);
-    let _ = writeln!(out, "{} \"$@\"", func_name);
 }
 
-/// Generate fish completion script.
+/// Generate fish completion script. A helper function walks the command
+/// line to the current command path; every completion is conditioned on it.
 fn generate_fish_completion<W: Write>(
     out: &mut W,
     cmd_name: &str,
@@ -227,29 +619,567 @@ fn generate_fish_completion<W: Write>(
     usage_pairs: &[String],
     args_arr: &[String],
 ) {
-    let cmds = extract_subcommands(usage_pairs);
-    let flags = extract_flags(args_arr);
+    let levels = completion_levels(usage_pairs, args_arr);
+    let at_func = format!("_{}_at", level_func(cmd_name, &[]));
 
     let _ = writeln!(out, "# fish completion for {}", cmd_name);
 
-    // Subcommands
-    for cmd in &cmds {
-        let esc_desc = fish_escape(&cmd.desc);
-        let _ = writeln!(out, "complete -c {} -n '__fish_use_subcommand' -a '{}' -d '{}'",
-            cmd_name, cmd.name, esc_desc);
+    // `<at_func> PATH` succeeds when the words before the cursor lead to PATH
+    let _ = writeln!(out, "function {}", at_func);
+    let _ = writeln!(out, "    set -l path ''");
+    let _ = writeln!(out, "    set -l skip 0");
+    let _ = writeln!(out, "    set -l words (commandline -opc)");
+    let _ = writeln!(out, "    set -e words[1]");
+    let _ = writeln!(out, "    for word in $words");
+    let _ = writeln!(out, "        if test $skip = 1");
+    let _ = writeln!(out, "            set skip 0");
+    let _ = writeln!(out, "            continue");
+    let _ = writeln!(out, "        end");
+    let _ = writeln!(out, "        switch \"$path:$word\"");
+    for level in &levels {
+        let key = level.key();
+        for (name, _) in &level.cmds {
+            let next = if key.is_empty() { name.clone() } else { format!("{} {}", key, name) };
+            let _ = writeln!(out, "            case '{}:{}'", key, name);
+            let _ = writeln!(out, "                set path '{}'", next);
+        }
+        let values = level.value_flag_words();
+        if !values.is_empty() {
+            let patterns: Vec<String> = values.iter().map(|w| format!("'{}:{}'", key, w)).collect();
+            let _ = writeln!(out, "            case {}", patterns.join(" "));
+            let _ = writeln!(out, "                set skip 1");
+        }
+    }
+    let _ = writeln!(out, "        end");
+    let _ = writeln!(out, "    end");
+    let _ = writeln!(out, "    test \"$path\" = \"$argv[1]\"");
+    let _ = writeln!(out, "end");
+
+    // Candidates from `__complete`, the last line being the directive
+    let complete = complete_func(cmd_name);
+    let _ = writeln!(out);
+    let _ = writeln!(out, "function {}", complete);
+    let _ = writeln!(out, "    set -l cur (commandline -ct)");
+    let _ = writeln!(out, "    set -l words (commandline -opc) \"$cur\"");
+    let _ = writeln!(out, "    set -l lines ($words[1] __complete $words[2..-1] 2>/dev/null)");
+    let _ = writeln!(out, "    test (count $lines) -gt 0; or return");
+    let _ = writeln!(out, "    switch $lines[-1]");
+    let _ = writeln!(out, "        case :file");
+    let _ = writeln!(out, "            __fish_complete_path \"$cur\"");
+    let _ = writeln!(out, "        case :dir");
+    let _ = writeln!(out, "            __fish_complete_directories \"$cur\"");
+    let _ = writeln!(out, "        case '*'");
+    let _ = writeln!(out, "            set -e lines[-1]");
+    let _ = writeln!(out, "            string join \\n -- $lines");
+    let _ = writeln!(out, "    end");
+    let _ = writeln!(out, "end");
+
+    for level in &levels {
+        let cond = format!("-n '{} \"{}\"'", at_func, level.key());
+
+        // Subcommands
+        for (name, desc) in &level.cmds {
+            let _ = writeln!(out, "complete -c {} -f -a '{}' -d '{}' {}",
+                cmd_name, name, fish_escape(desc), cond);
+        }
+
+        // Arguments
+        if level.cmds.is_empty() && !level.positionals.is_empty() {
+            let _ = writeln!(out, "complete -c {} -f -a '({})' {}", cmd_name, complete, cond);
+        }
+
+        // Flags
+        for flag in &level.flags {
+            let mut parts = format!("complete -c {} -l '{}'", cmd_name, flag.name);
+            if let Some(ref short) = flag.short {
+                parts.push_str(&format!(" -s '{}'", short));
+            }
+            if !flag.is_boolean {
+                parts.push_str(" -r");
+            }
+            if !flag.choices.is_empty() {
+                parts.push_str(&format!(" -f -a '{}'", fish_escape(&flag.choices.join(" "))));
+            } else if !flag.is_boolean {
+                parts.push_str(&format!(" -f -a '({})'", complete));
+            }
+            parts.push_str(&format!(" -d '{}' {}", fish_escape(&flag.desc), cond));
+            let _ = writeln!(out, "{}", parts);
+            if let Some(negated) = flag.negated_name() {
+                let _ = writeln!(out, "complete -c {} -l '{}' -d 'Disable --{}' {}", cmd_name, negated, flag.name, cond);
+            }
+        }
+    }
+}
+
+/// Generate Nushell completion module: one `extern` per command level, with
+/// typed flags and arguments. Enum values come from a completer per flag,
+/// other values from `__complete`.
+fn generate_nu_completion<W: Write>(
+    out: &mut W,
+    cmd_name: &str,
+    title: &str,
+    usage_pairs: &[String],
+    args_arr: &[String],
+) {
+    let levels = completion_levels(usage_pairs, args_arr);
+    write_nu_completion(out, cmd_name, title, &levels);
+}
+
+fn write_nu_completion<W: Write>(out: &mut W, cmd_name: &str, title: &str, levels: &[Level]) {
+    let complete = format!("nu-complete {}", cmd_name);
+
+    let _ = writeln!(out, "# nu completion for {}", cmd_name);
+    let _ = writeln!(out);
+    let _ = writeln!(out, "# Candidates from `{} __complete`; null lets nushell complete paths", cmd_name);
+    let _ = writeln!(out, "def \"{}\" [context: string] {{", complete);
+    // Runs of blanks separate words; a trailing blank leaves the empty word
+    // under the cursor. Quotes are not parsed: nushell passes the raw line.
+    let _ = writeln!(out, "    let words = ($context | str trim --left | split row -r '\\s+')");
+    let _ = writeln!(out, "    let lines = (run-external ($words | first) __complete ...($words | skip 1) | lines)");
+    let _ = writeln!(out, "    if ($lines | is-empty) or ($lines | last) != ':none' {{");
+    let _ = writeln!(out, "        return null");
+    let _ = writeln!(out, "    }}");
+    let _ = writeln!(out, "    $lines | drop 1");
+    let _ = writeln!(out, "}}");
+
+    for level in levels {
+        let command = nu_command(cmd_name, &level.path);
+        for flag in level.flags.iter().filter(|f| !f.choices.is_empty()) {
+            let choices: Vec<String> = flag.choices.iter().map(|c| nu_quote(c)).collect();
+            let _ = writeln!(out);
+            let _ = writeln!(out, "def \"nu-complete {} {}\" [] {{ [{}] }}", command, flag.name, choices.join(" "));
+        }
+    }
+
+    for level in levels {
+        let command = nu_command(cmd_name, &level.path);
+        let desc = if level.path.is_empty() {
+            title.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or_default().to_string()
+        } else {
+            level_desc(levels, level)
+        };
+
+        let _ = writeln!(out);
+        if !desc.is_empty() {
+            let _ = writeln!(out, "# {}", nu_comment(&desc));
+        }
+        let _ = writeln!(out, "export extern \"{}\" [", command);
+        // nushell gives every command its own --help
+        for flag in level.flags.iter().filter(|f| f.name != "help") {
+            let mut param = format!("--{}", flag.name);
+            if let Some(ref short) = flag.short {
+                param.push_str(&format!("(-{})", short));
+            }
+            if !flag.is_boolean {
+                param.push_str(&format!(": {}", nu_type(&flag.type_name)));
+                if !flag.choices.is_empty() {
+                    param.push_str(&format!("@\"nu-complete {} {}\"", command, flag.name));
+                } else if nu_completes(&flag.type_name) {
+                    param.push_str(&format!("@\"{}\"", complete));
+                }
+            }
+            let _ = writeln!(out, "    {}  # {}", param, nu_comment(&flag.desc));
+            if let Some(negated) = flag.negated_name() {
+                let _ = writeln!(out, "    --{}  # Disable --{}", negated, flag.name);
+            }
+        }
+        for pos in &level.positionals {
+            let mut param = match (pos.is_variadic, pos.required) {
+                (true, _) => format!("...{}", pos.name),
+                (false, true) => pos.name.clone(),
+                (false, false) => format!("{}?", pos.name),
+            };
+            param.push_str(&format!(": {}", nu_type(&pos.type_name)));
+            if nu_completes(&pos.type_name) {
+                param.push_str(&format!("@\"{}\"", complete));
+            }
+            let _ = writeln!(out, "    {}  # {}", param, nu_comment(&pos.desc));
+        }
+        let _ = writeln!(out, "]");
+    }
+}
+
+/// Command name of a level as nushell spells it: `tool remote add`.
+fn nu_command(cmd_name: &str, path: &[String]) -> String {
+    std::iter::once(cmd_name).chain(path.iter().map(String::as_str)).collect::<Vec<_>>().join(" ")
+}
+
+/// Nushell type for an argsh type; anything nushell cannot parse is a string.
+fn nu_type(type_name: &str) -> &'static str {
+    match type_name {
+        "int" => "int",
+        "float" => "number",
+        "file" | "path" => "path",
+        "dir" => "directory",
+        _ => "string",
+    }
+}
+
+/// Whether values of a type are asked from `__complete`; nushell completes
+/// paths and directories itself.
+fn nu_completes(type_name: &str) -> bool {
+    !matches!(nu_type(type_name), "path" | "directory")
+}
+
+fn nu_quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn nu_comment(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Description of a level, from its entry in the parent's subcommands.
+fn level_desc(levels: &[Level], level: &Level) -> String {
+    let Some((name, parent)) = level.path.split_last() else {
+        return String::new();
+    };
+    levels
+        .iter()
+        .find(|l| l.path == parent)
+        .and_then(|l| l.cmds.iter().find(|(n, _)| n == name))
+        .map(|(_, desc)| desc.clone())
+        .unwrap_or_default()
+}
+
+/// Generate PowerShell completion script: tables of the subcommands and
+/// flags of every command path, and a native argument completer that walks
+/// the command line through them.
+fn generate_powershell_completion<W: Write>(
+    out: &mut W,
+    cmd_name: &str,
+    _title: &str,
+    usage_pairs: &[String],
+    args_arr: &[String],
+) {
+    let levels = completion_levels(usage_pairs, args_arr);
+    write_powershell_completion(out, cmd_name, &levels);
+}
+
+fn write_powershell_completion<W: Write>(out: &mut W, cmd_name: &str, levels: &[Level]) {
+    let _ = writeln!(out, "# powershell completion for {}", cmd_name);
+    let _ = writeln!(out, "Register-ArgumentCompleter -Native -CommandName {} -ScriptBlock {{", ps_quote(cmd_name));
+    let _ = writeln!(out, "    param($wordToComplete, $commandAst, $cursorPosition)");
+    let _ = writeln!(out);
+
+    let _ = writeln!(out, "    # Subcommands and flags by command path");
+    let _ = writeln!(out, "    $commands = @{{");
+    for level in levels.iter().filter(|l| !l.cmds.is_empty()) {
+        let _ = writeln!(out, "        {} = @(", ps_quote(&level.key()));
+        for (name, desc) in &level.cmds {
+            let _ = writeln!(out, "            @{{ Name = {}; Desc = {} }}", ps_quote(name), ps_quote(ps_tooltip(desc, name)));
+        }
+        let _ = writeln!(out, "        )");
+    }
+    let _ = writeln!(out, "    }}");
+    let _ = writeln!(out, "    $flags = @{{");
+    for level in levels {
+        let _ = writeln!(out, "        {} = @(", ps_quote(&level.key()));
+        for flag in &level.flags {
+            let type_name = if flag.is_boolean { "" } else if flag.type_name.is_empty() { "string" } else { &flag.type_name };
+            let choices: Vec<String> = flag.choices.iter().map(|c| ps_quote(c)).collect();
+            let words = std::iter::once(format!("--{}", flag.name)).chain(flag.short.iter().map(|s| format!("-{}", s)));
+            for word in words {
+                let _ = writeln!(out, "            @{{ Word = {}; Type = {}; Desc = {}; Choices = @({}) }}",
+                    ps_quote(&word), ps_quote(type_name), ps_quote(ps_tooltip(&flag.desc, &word)), choices.join(", "));
+            }
+            if let Some(negated) = flag.negated_name() {
+                let _ = writeln!(out, "            @{{ Word = {}; Type = ''; Desc = {}; Choices = @() }}",
+                    ps_quote(&format!("--{}", negated)), ps_quote(&format!("Disable --{}", flag.name)));
+            }
+        }
+        let _ = writeln!(out, "        )");
+    }
+    let _ = writeln!(out, "    }}");
+    let takes_args: Vec<String> = levels
+        .iter()
+        .filter(|l| l.cmds.is_empty() && !l.positionals.is_empty())
+        .map(|l| ps_quote(&l.key()))
+        .collect();
+    let _ = writeln!(out, "    # Command paths whose arguments come from `__complete`");
+    let _ = writeln!(out, "    $arguments = @({})", takes_args.join(", "));
+    let _ = writeln!(out);
+
+    // Walk the command path, skipping the values of flags
+    let _ = writeln!(out, "    $words = @($commandAst.CommandElements |");
+    let _ = writeln!(out, "        Where-Object {{ $_.Extent.EndOffset -lt $cursorPosition }} |");
+    let _ = writeln!(out, "        ForEach-Object {{ $_.ToString() }})");
+    let _ = writeln!(out, "    $path = ''");
+    let _ = writeln!(out, "    for ($i = 1; $i -lt $words.Count; $i++) {{");
+    let _ = writeln!(out, "        if ($flags[$path] | Where-Object {{ $_.Type -and $_.Word -ceq $words[$i] }}) {{");
+    let _ = writeln!(out, "            $i++");
+    let _ = writeln!(out, "        }} elseif ($commands[$path] | Where-Object {{ $_.Name -ceq $words[$i] }}) {{");
+    let _ = writeln!(out, "            $path = \"$path $($words[$i])\".Trim()");
+    let _ = writeln!(out, "        }}");
+    let _ = writeln!(out, "    }}");
+    let _ = writeln!(out);
+
+    // Candidates from `__complete`; path directives fall back to PowerShell
+    let _ = writeln!(out, "    $dynamic = {{");
+    let _ = writeln!(out, "        $lines = @(& $words[0] __complete @($words | Select-Object -Skip 1) $wordToComplete 2>$null)");
+    let _ = writeln!(out, "        if ($lines.Count -and $lines[-1] -ceq ':none') {{");
+    let _ = writeln!(out, "            $lines | Select-Object -SkipLast 1 | ForEach-Object {{");
+    let _ = writeln!(out, "                [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_)");
+    let _ = writeln!(out, "            }}");
+    let _ = writeln!(out, "        }}");
+    let _ = writeln!(out, "    }}");
+    let _ = writeln!(out);
+
+    let _ = writeln!(out, "    $flag = $flags[$path] | Where-Object {{ $_.Type -and $_.Word -ceq $words[-1] }} | Select-Object -First 1");
+    let _ = writeln!(out, "    $completions = if ($flag -and $flag.Choices) {{");
+    let _ = writeln!(out, "        $flag.Choices | ForEach-Object {{");
+    let _ = writeln!(out, "            [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_)");
+    let _ = writeln!(out, "        }}");
+    let _ = writeln!(out, "    }} elseif ($flag) {{");
+    let _ = writeln!(out, "        & $dynamic");
+    let _ = writeln!(out, "    }} elseif ($wordToComplete.StartsWith('-')) {{");
+    let _ = writeln!(out, "        $flags[$path] | ForEach-Object {{");
+    let _ = writeln!(out, "            $item = if ($_.Type) {{ \"$($_.Word) <$($_.Type)>\" }} else {{ $_.Word }}");
+    let _ = writeln!(out, "            [System.Management.Automation.CompletionResult]::new($_.Word, $item, 'ParameterName', $_.Desc)");
+    let _ = writeln!(out, "        }}");
+    let _ = writeln!(out, "    }} elseif ($commands[$path]) {{");
+    let _ = writeln!(out, "        $commands[$path] | ForEach-Object {{");
+    let _ = writeln!(out, "            [System.Management.Automation.CompletionResult]::new($_.Name, $_.Name, 'ParameterValue', $_.Desc)");
+    let _ = writeln!(out, "        }}");
+    let _ = writeln!(out, "    }} elseif ($arguments -contains $path) {{");
+    let _ = writeln!(out, "        & $dynamic");
+    let _ = writeln!(out, "    }}");
+    let _ = writeln!(out, "    $completions | Where-Object {{ $_.CompletionText.StartsWith($wordToComplete) }}");
+    let _ = writeln!(out, "}}");
+}
+
+/// `CompletionResult` rejects an empty tooltip, so the word stands in for a
+/// missing description.
+fn ps_tooltip<'a>(desc: &'a str, word: &'a str) -> &'a str {
+    if desc.is_empty() { word } else { desc }
+}
+
+/// Quote a string for PowerShell: single quotes, doubled inside.
+fn ps_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flag(name: &str, short: Option<&str>, is_boolean: bool, choices: &[&str]) -> FlagInfo {
+        FlagInfo {
+            name: name.to_string(),
+            short: short.map(str::to_string),
+            desc: format!("The {} flag", name),
+            is_boolean,
+            default_on: is_boolean, // offers the `--no-` form
+            type_name: if is_boolean { String::new() } else { "string".to_string() },
+            choices: choices.iter().map(|c| c.to_string()).collect(),
+            ..FlagInfo::help()
+        }
     }
 
-    // Flags
-    for flag in &flags {
-        let esc_desc = fish_escape(&flag.desc);
-        let mut parts = format!("complete -c {} -l '{}'", cmd_name, flag.name);
-        if let Some(ref short) = flag.short {
-            parts.push_str(&format!(" -s '{}'", short));
+    fn positional(name: &str, type_name: &str, is_variadic: bool) -> PositionalInfo {
+        PositionalInfo {
+            name: name.to_string(),
+            desc: format!("The {} argument", name),
+            type_name: type_name.to_string(),
+            choices: Vec::new(),
+            required: !is_variadic,
+            is_variadic,
         }
-        if !flag.is_boolean {
-            parts.push_str(" -r");
+    }
+
+    fn levels() -> Vec<Level> {
+        let path = |p: &[&str]| p.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let cmds = |c: &[&str]| c.iter().map(|s| (s.to_string(), format!("{} it", s))).collect::<Vec<_>>();
+        vec![
+            Level {
+                path: Vec::new(),
+                cmds: cmds(&["remote", "log"]),
+                flags: vec![flag("config", Some("c"), false, &[]), FlagInfo::help()],
+                positionals: Vec::new(),
+            },
+            Level { path: path(&["remote"]), cmds: cmds(&["add"]), flags: vec![FlagInfo::help()], positionals: Vec::new() },
+            Level {
+                path: path(&["remote", "add"]),
+                cmds: Vec::new(),
+                flags: vec![flag("fetch", Some("f"), true, &[]), flag("mode", None, false, &["push", "pull"])],
+                positionals: vec![positional("name", "string", false), positional("urls", "url", true)],
+            },
+            Level { path: path(&["log"]), cmds: Vec::new(), flags: vec![FlagInfo::help()], positionals: Vec::new() },
+        ]
+    }
+
+    fn render(write: impl Fn(&mut Vec<u8>, &[Level])) -> String {
+        let mut out = Vec::new();
+        write(&mut out, &levels());
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_bash_walks_command_path() {
+        let script = render(|out, levels| write_bash_completion(out, "tool", levels));
+        assert!(script.contains("\"remote:add\") path=\"remote add\" ;;"));
+        // A flag value is skipped, so `tool -c remote` stays at the top level
+        assert!(script.contains("\":--config\"|\":-c\") (( i++ )) ;;"));
+        let remote_add = &script[script.find("        \"remote add\")").unwrap()..];
+        assert!(remote_add.starts_with("        \"remote add\")\n            case \"${prev}\" in\n                --mode)\n"));
+        assert!(remote_add.contains("COMPREPLY=($(compgen -W 'push pull' -- \"${cur}\"))"));
+        assert!(script.contains("compgen -W '--fetch -f --no-fetch --mode'"));
+        assert!(script.contains("compgen -W 'add' -- "));
+    }
+
+    #[test]
+    fn test_bash_asks_complete_for_values() {
+        let script = render(|out, levels| write_bash_completion(out, "tool", levels));
+        assert!(script.contains("__tool_complete() {\n    local -a lines\n    mapfile -t lines < <(\"${COMP_WORDS[0]}\" __complete "));
+        // Free-form flag values and the arguments of a leaf command
+        assert!(script.contains("                --config|-c)\n                    __tool_complete\n                    return\n"));
+        let remote_add = &script[script.find("        \"remote add\")").unwrap()..];
+        assert!(remote_add.contains("            else\n                __tool_complete\n            fi"));
+        let log = &script[script.find("        \"log\")").unwrap()..];
+        assert!(!log.contains("__tool_complete"));
+    }
+
+    #[test]
+    fn test_zsh_function_per_level() {
+        let script = render(|out, levels| {
+            for level in levels {
+                write_zsh_level(out, "tool", level);
+            }
+        });
+        assert!(script.contains("_tool__remote() {"));
+        assert!(script.contains("                add) _tool__remote__add ;;"));
+        assert!(script.contains("_tool__remote__add() {\n    _arguments -s \\\n        '(-f --fetch)'"));
+        assert!(script.contains("'(-c --config)'{\"-c\",\"--config\"}'[The config flag]:string:{__tool_complete}'"));
+        assert!(script.contains("'*:argument:{__tool_complete}'"));
+        assert!(script.contains("_tool() {\n    local -a __tool_complete_words=(\"${words[@]}\")\n"));
+    }
+
+    #[test]
+    fn test_nu_extern_per_level() {
+        let script = render(|out, levels| write_nu_completion(out, "tool", "Tool\n\n  More", levels));
+        assert!(script.contains("    let words = ($context | str trim --left | split row -r '\\s+')\n"));
+        assert!(script.contains("def \"nu-complete tool remote add mode\" [] { [\"push\" \"pull\"] }"));
+        assert!(script.contains("# Tool\nexport extern \"tool\" [\n    --config(-c): string@\"nu-complete tool\"  # The config flag\n]"));
+        assert!(script.contains("# add it\nexport extern \"tool remote add\" [\n    --fetch(-f)  # The fetch flag\n    --no-fetch  # Disable --fetch\n"));
+        assert!(script.contains("    --mode: string@\"nu-complete tool remote add mode\"  # The mode flag\n"));
+        assert!(script.contains("    name: string@\"nu-complete tool\"  # The name argument\n    ...urls: string@\"nu-complete tool\"  # The urls argument\n]"));
+        assert!(!script.contains("--help"));
+    }
+
+    #[test]
+    fn test_nu_type() {
+        assert_eq!(nu_type("int"), "int");
+        assert_eq!(nu_type("dir"), "directory");
+        assert_eq!(nu_type("duration"), "string");
+        assert!(!nu_completes("file"));
+        assert!(nu_completes("url"));
+    }
+
+    #[test]
+    fn test_powershell_tables() {
+        let script = render(|out, levels| write_powershell_completion(out, "tool", levels));
+        assert!(script.contains("Register-ArgumentCompleter -Native -CommandName 'tool' -ScriptBlock {"));
+        assert!(script.contains("        'remote' = @(\n            @{ Name = 'add'; Desc = 'add it' }\n        )"));
+        assert!(script.contains("            @{ Word = '-c'; Type = 'string'; Desc = 'The config flag'; Choices = @() }"));
+        assert!(script.contains("            @{ Word = '--mode'; Type = 'string'; Desc = 'The mode flag'; Choices = @('push', 'pull') }"));
+        assert!(script.contains("            @{ Word = '--no-fetch'; Type = ''; Desc = 'Disable --fetch'; Choices = @() }"));
+        assert!(script.contains("    $arguments = @('remote add')"));
+        assert_eq!(ps_quote("it's"), "'it''s'");
+    }
+
+    #[test]
+    fn test_collect_levels_adds_help_once() {
+        let node = |name: &str, flags: Vec<FlagInfo>| CommandNode {
+            name: name.to_string(),
+            desc: String::new(),
+            full_path: vec![name.to_string()],
+            flags,
+            positionals: Vec::new(),
+            children: Vec::new(),
+            hidden: false,
+            annotations: Vec::new(),
+            func: None,
+        };
+        let mut levels = Vec::new();
+        collect_levels(vec![node("log", Vec::new()), node("show", vec![FlagInfo::help()])], &mut levels);
+        for level in &levels {
+            assert_eq!(level.flags.iter().filter(|f| f.name == "help").count(), 1);
         }
-        parts.push_str(&format!(" -d '{}'", esc_desc));
-        let _ = writeln!(out, "{}", parts);
+    }
+
+    #[test]
+    fn test_level_func() {
+        let path = vec!["remote".to_string(), "set-url".to_string()];
+        assert_eq!(level_func("my-tool", &[]), "_my_tool");
+        assert_eq!(level_func("my-tool", &path), "_my_tool__remote__set_url");
+        let joined = vec!["remote-set".to_string(), "url".to_string()];
+        assert_ne!(level_func("my-tool", &joined), level_func("my-tool", &path));
+    }
+
+    #[test]
+    fn test_parse_install_args() {
+        let args = |a: &[&str]| parse_install_args(&a.iter().map(|s| s.to_string()).collect::<Vec<_>>());
+        assert_eq!(args(&[]), Ok(InstallOpts::default()));
+        assert_eq!(
+            args(&["zsh", "--dir", "/tmp/c", "--dry-run"]),
+            Ok(InstallOpts { shell: Some("zsh".to_string()), dir: Some("/tmp/c".to_string()), dry_run: true })
+        );
+        assert_eq!(args(&["--dir=/tmp/c"]).unwrap().dir.as_deref(), Some("/tmp/c"));
+        assert!(args(&["--dir"]).is_err());
+        assert!(args(&["--force"]).is_err());
+        assert!(args(&["bash", "zsh"]).is_err());
+    }
+
+    #[test]
+    fn test_shell_name() {
+        assert_eq!(shell_name(Some("/usr/bin/zsh")).as_deref(), Some("zsh"));
+        assert_eq!(shell_name(Some("fish")).as_deref(), Some("fish"));
+        assert_eq!(shell_name(None), None);
+    }
+
+    #[test]
+    fn test_install_path() {
+        let vars = |pairs: &'static [(&'static str, &'static str)]| {
+            move |name: &str| pairs.iter().find(|(n, _)| *n == name).map(|(_, v)| v.to_string())
+        };
+        let home = vars(&[("HOME", "/home/u")]);
+        let path = |shell, var| install_path(shell, "tool", None, var).map(|p| p.display().to_string());
+        assert_eq!(path("bash", home).as_deref(), Some("/home/u/.local/share/bash-completion/completions/tool"));
+        assert_eq!(path("zsh", home).as_deref(), Some("/home/u/.local/share/zsh/site-functions/_tool"));
+        assert_eq!(path("fish", home).as_deref(), Some("/home/u/.config/fish/completions/tool.fish"));
+
+        let xdg = vars(&[("HOME", "/home/u"), ("XDG_DATA_HOME", "/data"), ("XDG_CONFIG_HOME", "relative")]);
+        assert_eq!(path("bash", xdg).as_deref(), Some("/data/bash-completion/completions/tool"));
+        assert_eq!(path("fish", xdg).as_deref(), Some("/home/u/.config/fish/completions/tool.fish"));
+
+        let user_dir = vars(&[("BASH_COMPLETION_USER_DIR", "/bc")]);
+        assert_eq!(path("bash", user_dir).as_deref(), Some("/bc/completions/tool"));
+
+        assert_eq!(path("zsh", xdg).as_deref(), Some("/data/zsh/site-functions/_tool"));
+
+        // $fpath entries are not guessed from
+        let fpath = vars(&[("HOME", "/home/u"), ("FPATH", "/usr/share/zsh/functions:/home/u/.zfunc")]);
+        assert_eq!(path("zsh", fpath).as_deref(), Some("/home/u/.local/share/zsh/site-functions/_tool"));
+
+        assert_eq!(path("fish", vars(&[])), None);
+        let dir = install_path("zsh", "tool", Some("/tmp/c"), vars(&[]));
+        assert_eq!(dir, Some(PathBuf::from("/tmp/c/_tool")));
+    }
+
+    #[test]
+    fn test_install_script_is_idempotent() {
+        let dir = std::env::temp_dir().join(format!("argsh-install-{}", std::process::id()));
+        let path = dir.join("completions/tool");
+
+        assert_eq!(install_script(&path, b"v1", true).unwrap(), Installed::Created);
+        assert!(!path.exists());
+        assert_eq!(install_script(&path, b"v1", false).unwrap(), Installed::Created);
+        assert_eq!(install_script(&path, b"v1", false).unwrap(), Installed::UpToDate);
+        assert_eq!(install_script(&path, b"v2", true).unwrap(), Installed::Updated);
+        assert_eq!(std::fs::read(&path).unwrap(), b"v1");
+        assert_eq!(install_script(&path, b"v2", false).unwrap(), Installed::Updated);
+        assert_eq!(std::fs::read(&path).unwrap(), b"v2");
+
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 }
@@ -10,9 +10,12 @@ use std::io::Write;
 use super::{
     extract_subcommands, extract_flags, extract_flags_for_llm,
     json_escape, sanitize_tool_name, write_tool_properties,
-    build_command_tree, flatten_leaves, flatten_all,
+    build_command_tree, flatten_leaves, flatten_all, flag_constraints, tool_flags,
     FlagInfo, CommandNode,
 };
+use super::style::{self, example_line};
+use super::tree;
+use std::path::Path;
 
 // -- :usage::docgen builtin registration --------------------------------------
 
@@ -54,7 +57,7 @@ extern "C" fn usage_docgen_builtin_fn(word_list: *const WordList) -> c_int {
 
 /// Main entry point for :usage::docgen builtin.
 /// Called via "${usage[@]}" — generates documentation in various formats.
-/// Args: [format] [-- title original_usage_pairs...]
+/// Args: [format] [--out <dir> ...] [-- title original_usage_pairs...]
 pub fn usage_docgen_main(args: &[String]) -> i32 {
     let sep = args.iter().position(|s| s == "--");
     let (user_args, meta) = match sep {
@@ -70,13 +73,15 @@ pub fn usage_docgen_main(args: &[String]) -> i32 {
             shell::get_script_name() // coverage:off - defensive_check: always called via :usage dispatch which sets COMMANDNAME
         };
         println!("Generate documentation in various formats.\n");
-        println!("Usage: {} docgen <format>\n", cmd_str);
+        println!("Usage: {} docgen <format>", cmd_str);
+        println!("       {} docgen md --out <dir> [--frontmatter docusaurus|mkdocs] [--sidebar]\n", cmd_str);
         println!("Available formats:");
         println!("  man     Man page (troff format)");
         println!("  md      Markdown");
         println!("  rst     reStructuredText");
         println!("  yaml    YAML");
         println!("  llm     LLM tool schema (claude, openai, gemini, kimi)");
+        println!("\nWith --out, md writes one page per command to <dir>.");
         return shared::HELP_EXIT;
     }
 
@@ -97,14 +102,31 @@ pub fn usage_docgen_main(args: &[String]) -> i32 {
     // Build command tree for nested subcommand discovery
     let caller = shell::get_funcname(0);
     let tree = build_command_tree(usage_pairs, &args_arr, caller.as_deref());
+    let examples = style::read_examples();
 
     let out = std::io::stdout();
     let mut out = out.lock();
 
     match format.as_str() {
-        "man" => generate_man_page(&mut out, &cmd_name, title, usage_pairs, &args_arr, &tree),
-        "md" => generate_markdown(&mut out, &cmd_name, title, usage_pairs, &args_arr, &tree),
-        "rst" => generate_rst(&mut out, &cmd_name, title, usage_pairs, &args_arr, &tree),
+        "man" => {
+            generate_man_page(&mut out, &cmd_name, title, usage_pairs, &args_arr, &tree);
+            write_man_examples(&mut out, &cmd_name, &examples);
+        }
+        "md" => {
+            let opts = match parse_site_args(&user_args[1..]) {
+                Ok(opts) => opts,
+                Err(msg) => return shared::error_usage("", &msg),
+            };
+            if opts.out.is_some() {
+                return generate_markdown_site(&mut out, &cmd_name, title, &args_arr, &tree, &examples, &opts);
+            }
+            generate_markdown(&mut out, &cmd_name, title, usage_pairs, &args_arr, &tree);
+            write_markdown_examples(&mut out, &cmd_name, &examples);
+        }
+        "rst" => {
+            generate_rst(&mut out, &cmd_name, title, usage_pairs, &args_arr, &tree);
+            write_rst_examples(&mut out, &cmd_name, &examples);
+        }
         "yaml" => generate_yaml(&mut out, &cmd_name, title, usage_pairs, &args_arr, &tree),
         "llm" => {
             let provider = user_args.get(1).map(|s| s.as_str());
@@ -183,14 +205,14 @@ fn generate_man_page<W: Write>(
         for node in &all_nodes {
             let _ = writeln!(out, ".TP");
             let _ = writeln!(out, ".B {}", node.full_path.join(" "));
-            let _ = writeln!(out, "{}", man_escape(&node.desc));
+            let _ = writeln!(out, "{}", man_escape(&node.doc_desc()));
         }
     } else if !cmds.is_empty() {
         let _ = writeln!(out, ".SH COMMANDS");
         for cmd in &cmds {
             let _ = writeln!(out, ".TP");
             let _ = writeln!(out, ".B {}", cmd.name);
-            let _ = writeln!(out, "{}", man_escape(&cmd.desc));
+            let _ = writeln!(out, "{}", man_escape(&cmd.doc_desc()));
         }
     }
 
@@ -201,17 +223,17 @@ fn generate_man_page<W: Write>(
             let _ = writeln!(out, ".TP");
             if let Some(ref short) = flag.short {
                 if flag.is_boolean {
-                    let _ = writeln!(out, ".BR \\-{} \", \" \\-\\-{}", short, flag.name);
+                    let _ = writeln!(out, ".BR \\-{} \", \" \\-\\-{}{}", short, flag.name, man_negated(flag));
                 } else {
                     let _ = writeln!(out, ".BR \\-{} \", \" \\-\\-{} \" \" \\fI{}\\fR",
-                        short, flag.name, flag.type_name);
+                        short, flag.name, flag.value_label());
                 }
             } else if flag.is_boolean {
-                let _ = writeln!(out, ".BR \\-\\-{}", flag.name);
+                let _ = writeln!(out, ".BR \\-\\-{}{}", flag.name, man_negated(flag));
             } else {
-                let _ = writeln!(out, ".BR \\-\\-{} \" \" \\fI{}\\fR", flag.name, flag.type_name);
+                let _ = writeln!(out, ".BR \\-\\-{} \" \" \\fI{}\\fR", flag.name, flag.value_label());
             }
-            let _ = writeln!(out, "{}", man_escape(&flag.desc));
+            let _ = writeln!(out, "{}", man_escape(&flag.doc_desc()));
         }
     }
 
@@ -223,22 +245,47 @@ fn generate_man_page<W: Write>(
                 let _ = writeln!(out, ".TP");
                 if let Some(ref short) = flag.short {
                     if flag.is_boolean {
-                        let _ = writeln!(out, ".BR \\-{} \", \" \\-\\-{}", short, flag.name);
+                        let _ = writeln!(out, ".BR \\-{} \", \" \\-\\-{}{}", short, flag.name, man_negated(flag));
                     } else {
                         let _ = writeln!(out, ".BR \\-{} \", \" \\-\\-{} \" \" \\fI{}\\fR",
-                            short, flag.name, flag.type_name);
+                            short, flag.name, flag.value_label());
                     }
                 } else if flag.is_boolean {
-                    let _ = writeln!(out, ".BR \\-\\-{}", flag.name);
+                    let _ = writeln!(out, ".BR \\-\\-{}{}", flag.name, man_negated(flag));
                 } else {
-                    let _ = writeln!(out, ".BR \\-\\-{} \" \" \\fI{}\\fR", flag.name, flag.type_name);
+                    let _ = writeln!(out, ".BR \\-\\-{} \" \" \\fI{}\\fR", flag.name, flag.value_label());
                 }
-                let _ = writeln!(out, "{}", man_escape(&flag.desc));
+                let _ = writeln!(out, "{}", man_escape(&flag.doc_desc()));
             }
         }
     }
 }
 
+/// EXAMPLES section from the `examples` array: description, then the command.
+fn write_man_examples<W: Write>(out: &mut W, cmd_name: &str, examples: &[(String, String)]) {
+    if examples.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, ".SH EXAMPLES");
+    for (command, desc) in examples {
+        let _ = writeln!(out, ".PP");
+        if !desc.is_empty() {
+            let _ = writeln!(out, "{}", man_escape(desc));
+        }
+        let _ = writeln!(out, ".PP");
+        let _ = writeln!(out, ".RS 4");
+        let _ = writeln!(out, ".B {}", man_escape(&example_line(cmd_name, command)));
+        let _ = writeln!(out, ".RE");
+    }
+}
+
+/// `.BR` continuation naming the `--no-` form of a boolean flag, if any.
+fn man_negated(flag: &FlagInfo) -> String {
+    flag.negated_name()
+        .map(|n| format!(" \", \" \\-\\-{}", man_escape(&n)))
+        .unwrap_or_default()
+}
+
 /// Escape special troff characters.
 /// Also neutralizes lines starting with '.' or '\'' which roff interprets as macros.
 fn man_escape(s: &str) -> String {
@@ -272,6 +319,45 @@ fn generate_markdown<W: Write>(
     let cmds = extract_subcommands(usage_pairs);
     let flags = extract_flags(args_arr);
     let all_nodes = flatten_all(tree);
+
+    write_markdown_header(out, cmd_name, title, !cmds.is_empty());
+
+    // Commands — show all discovered nodes (flattened with full path)
+    if !all_nodes.is_empty() {
+        let _ = writeln!(out, "## Commands\n");
+        let _ = writeln!(out, "| Command | Description |");
+        let _ = writeln!(out, "|---------|-------------|");
+        for node in &all_nodes {
+            let _ = writeln!(out, "| `{}` | {} |", node.full_path.join(" "), node.doc_desc());
+        }
+        let _ = writeln!(out);
+    } else if !cmds.is_empty() {
+        let _ = writeln!(out, "## Commands\n");
+        let _ = writeln!(out, "| Command | Description |");
+        let _ = writeln!(out, "|---------|-------------|");
+        for cmd in &cmds {
+            let _ = writeln!(out, "| `{}` | {} |", cmd.name, cmd.doc_desc());
+        }
+        let _ = writeln!(out);
+    }
+
+    // Options
+    if !flags.is_empty() {
+        let _ = writeln!(out, "## Options\n");
+        write_markdown_flags(out, &flags);
+    }
+
+    // Per-command options
+    for node in &all_nodes {
+        if !node.flags.is_empty() {
+            let _ = writeln!(out, "### {} options\n", node.full_path.join(" "));
+            write_markdown_flags(out, &node.flags);
+        }
+    }
+}
+
+/// Title, summary, synopsis and the rest of the title as description.
+fn write_markdown_header<W: Write>(out: &mut W, cmd_name: &str, title: &str, has_commands: bool) {
     let first_line = title.lines().next().unwrap_or(title).trim();
 
     let _ = writeln!(out, "# {}\n", cmd_name);
@@ -280,7 +366,7 @@ fn generate_markdown<W: Write>(
     // Synopsis
     let _ = writeln!(out, "## Synopsis\n");
     let _ = write!(out, "```\n{}", cmd_name);
-    if !cmds.is_empty() {
+    if has_commands {
         let _ = write!(out, " [command]");
     }
     let _ = writeln!(out, " [options]\n```\n");
@@ -294,63 +380,292 @@ fn generate_markdown<W: Write>(
         }
         let _ = writeln!(out);
     }
+}
 
-    // Commands — show all discovered nodes (flattened with full path)
-    if !all_nodes.is_empty() {
-        let _ = writeln!(out, "## Commands\n");
-        let _ = writeln!(out, "| Command | Description |");
-        let _ = writeln!(out, "|---------|-------------|");
-        for node in &all_nodes {
-            let _ = writeln!(out, "| `{}` | {} |", node.full_path.join(" "), node.desc);
+/// Options table, followed by the flag-group constraints.
+fn write_markdown_flags<W: Write>(out: &mut W, flags: &[FlagInfo]) {
+    let _ = writeln!(out, "| Flag | Description |");
+    let _ = writeln!(out, "|------|-------------|");
+    for flag in flags {
+        let mut flag_str = format!("`--{}`", flag.name);
+        if let Some(ref short) = flag.short {
+            flag_str = format!("`-{}`, {}", short, flag_str);
         }
-        let _ = writeln!(out);
-    } else if !cmds.is_empty() {
-        let _ = writeln!(out, "## Commands\n");
-        let _ = writeln!(out, "| Command | Description |");
-        let _ = writeln!(out, "|---------|-------------|");
-        for cmd in &cmds {
-            let _ = writeln!(out, "| `{}` | {} |", cmd.name, cmd.desc);
+        if let Some(negated) = flag.negated_name() {
+            flag_str.push_str(&format!(", `--{}`", negated));
         }
-        let _ = writeln!(out);
+        if !flag.is_boolean {
+            flag_str.push_str(&format!(" *{}*", flag.value_label()));
+        }
+        let _ = writeln!(out, "| {} | {} |", flag_str, flag.doc_desc());
     }
+    let _ = writeln!(out);
+    write_markdown_constraints(out, flags);
+}
 
-    // Options
-    if !flags.is_empty() {
-        let _ = writeln!(out, "## Options\n");
-        let _ = writeln!(out, "| Flag | Description |");
-        let _ = writeln!(out, "|------|-------------|");
-        for flag in &flags {
-            let mut flag_str = format!("`--{}`", flag.name);
-            if let Some(ref short) = flag.short {
-                flag_str = format!("`-{}`, {}", short, flag_str);
-            }
-            if !flag.is_boolean {
-                flag_str.push_str(&format!(" *{}*", flag.type_name));
-            }
-            let _ = writeln!(out, "| {} | {} |", flag_str, flag.desc);
+/// List flag-group constraints below a Markdown options table.
+fn write_markdown_constraints<W: Write>(out: &mut W, flags: &[FlagInfo]) {
+    let constraints = flag_constraints(flags);
+    if constraints.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "Constraints:\n");
+    for constraint in &constraints {
+        let _ = writeln!(out, "- {}", constraint.describe(|f| format!("`--{}`", f)));
+    }
+    let _ = writeln!(out);
+}
+
+/// Examples section: each description followed by a shell code block.
+fn write_markdown_examples<W: Write>(out: &mut W, cmd_name: &str, examples: &[(String, String)]) {
+    if examples.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "## Examples\n");
+    for (command, desc) in examples {
+        if !desc.is_empty() {
+            let _ = writeln!(out, "{}\n", desc);
         }
-        let _ = writeln!(out);
+        let _ = writeln!(out, "```bash\n{}\n```\n", example_line(cmd_name, command));
     }
+}
 
-    // Per-command options
-    for node in &all_nodes {
-        if !node.flags.is_empty() {
-            let _ = writeln!(out, "### {} options\n", node.full_path.join(" "));
-            let _ = writeln!(out, "| Flag | Description |");
-            let _ = writeln!(out, "|------|-------------|");
-            for flag in &node.flags {
-                let mut flag_str = format!("`--{}`", flag.name);
-                if let Some(ref short) = flag.short {
-                    flag_str = format!("`-{}`, {}", short, flag_str);
-                }
-                if !flag.is_boolean {
-                    flag_str.push_str(&format!(" *{}*", flag.type_name));
-                }
-                let _ = writeln!(out, "| {} | {} |", flag_str, flag.desc);
+// -- Markdown site generation -------------------------------------------------
+
+/// Frontmatter at the top of each page of a Markdown site.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Frontmatter {
+    Docusaurus,
+    MkDocs,
+}
+
+/// Options of `docgen md`: `--out <dir>` writes a site instead of a single
+/// page, `--frontmatter` and `--sidebar` add to it.
+#[derive(Debug, Default, PartialEq)]
+struct SiteOpts {
+    out: Option<String>,
+    frontmatter: Option<Frontmatter>,
+    sidebar: bool,
+}
+
+fn parse_site_args(args: &[String]) -> Result<SiteOpts, String> {
+    let mut opts = SiteOpts::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) => (name, Some(value.to_string())),
+            None => (arg.as_str(), None),
+        };
+        let mut value = || inline.clone().or_else(|| args.next().cloned())
+            .ok_or_else(|| format!("missing value for flag: {}", name));
+        match name {
+            "--out" => opts.out = Some(value()?),
+            "--frontmatter" => {
+                opts.frontmatter = Some(match value()?.as_str() {
+                    "docusaurus" => Frontmatter::Docusaurus,
+                    "mkdocs" => Frontmatter::MkDocs,
+                    other => return Err(format!("unknown frontmatter: {}. Use docusaurus or mkdocs", other)),
+                });
             }
-            let _ = writeln!(out);
+            "--sidebar" if inline.is_none() => opts.sidebar = true,
+            _ => return Err(format!("unknown flag: {}", arg)),
+        }
+    }
+    if opts.out.is_none() && (opts.frontmatter.is_some() || opts.sidebar) {
+        return Err("--frontmatter and --sidebar require --out <dir>".to_string());
+    }
+    Ok(opts)
+}
+
+/// Write one Markdown page per command below `--out`, linked to its parent
+/// and children, and print the path of every file written. Pages of
+/// commands with subcommands are the `index.md` of their own directory:
+/// `index.md`, `remote/index.md`, `remote/add.md`, `log.md`.
+fn generate_markdown_site<W: Write>(
+    out: &mut W,
+    cmd_name: &str,
+    title: &str,
+    args_arr: &[String],
+    tree: &[CommandNode],
+    examples: &[(String, String)],
+    opts: &SiteOpts,
+) -> i32 {
+    let dir = Path::new(opts.out.as_deref().unwrap_or("."));
+    let first_line = title.lines().next().unwrap_or(title).trim();
+    let mut files: Vec<(String, Vec<u8>)> = Vec::new();
+
+    let mut page = Vec::new();
+    write_frontmatter(&mut page, opts.frontmatter, cmd_name, cmd_name, 1, first_line);
+    write_markdown_header(&mut page, cmd_name, title, site_children(tree).next().is_some());
+    let flags = extract_flags(args_arr);
+    if !flags.is_empty() {
+        let _ = writeln!(page, "## Options\n");
+        write_markdown_flags(&mut page, &flags);
+    }
+    write_site_commands(&mut page, "index.md", tree);
+    write_markdown_examples(&mut page, cmd_name, examples);
+    files.push(("index.md".to_string(), page));
+
+    let mut nodes = Vec::new();
+    site_nodes(tree, &mut nodes);
+    for (node, position) in nodes {
+        let file = page_file(node);
+        let mut page = Vec::new();
+        let command = example_line(cmd_name, &node.full_path.join(" "));
+        let name = node.full_path.last().map(String::as_str).unwrap_or_default();
+        write_frontmatter(&mut page, opts.frontmatter, &command, name, position, &node.desc);
+        write_site_page(&mut page, cmd_name, node, &file);
+        if opts.frontmatter == Some(Frontmatter::Docusaurus) && file.ends_with("/index.md") {
+            // Label and place the directory in an autogenerated sidebar
+            let category = serde_json::json!({ "label": name, "position": position });
+            let path = file.replace("index.md", "_category_.json");
+            files.push((path, format!("{:#}\n", category).into_bytes()));
+        }
+        files.push((file, page));
+    }
+
+    if opts.sidebar {
+        let sidebar = serde_json::json!([{
+            "label": cmd_name,
+            "path": "index.md",
+            "items": sidebar_items(tree),
+        }]);
+        files.push(("sidebar.json".to_string(), format!("{:#}\n", sidebar).into_bytes()));
+    }
+
+    for (file, content) in files {
+        let path = dir.join(&file);
+        let written = path.parent().map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(&path, content));
+        if let Err(e) = written {
+            eprintln!("Error: cannot write {}: {}", path.display(), e);
+            return 1;
+        }
+        let _ = writeln!(out, "{}", path.display());
+    }
+    shared::HELP_EXIT
+}
+
+/// The page of one command: synopsis, arguments, options, links to its
+/// subcommands and back to its parent.
+fn write_site_page<W: Write>(out: &mut W, cmd_name: &str, node: &CommandNode, file: &str) {
+    let _ = writeln!(out, "# {}\n", example_line(cmd_name, &node.full_path.join(" ")));
+    let desc = node.doc_desc();
+    if !desc.is_empty() {
+        let _ = writeln!(out, "{}\n", desc);
+    }
+
+    let _ = writeln!(out, "## Synopsis\n");
+    let _ = writeln!(out, "```\n{}\n```\n", tree::synopsis(cmd_name, node));
+
+    if !node.positionals.is_empty() {
+        let _ = writeln!(out, "## Arguments\n");
+        let _ = writeln!(out, "| Argument | Description |");
+        let _ = writeln!(out, "|----------|-------------|");
+        for pos in &node.positionals {
+            let _ = writeln!(out, "| `{}` *{}* | {} |", tree::positional_param(pos), pos.type_name, pos.desc);
         }
+        let _ = writeln!(out);
     }
+
+    if !node.flags.is_empty() {
+        let _ = writeln!(out, "## Options\n");
+        write_markdown_flags(out, &node.flags);
+    }
+
+    write_site_commands(out, file, &node.children);
+
+    let parent = &node.full_path[..node.full_path.len().saturating_sub(1)];
+    let parent_file = if parent.is_empty() { "index.md".to_string() } else { format!("{}/index.md", parent.join("/")) };
+    let _ = writeln!(out, "## See also\n");
+    let _ = writeln!(out, "- [`{}`]({})", example_line(cmd_name, &parent.join(" ")), relative_link(file, &parent_file));
+}
+
+/// Commands table linking to the pages of `nodes`, seen from `file`.
+fn write_site_commands<W: Write>(out: &mut W, file: &str, nodes: &[CommandNode]) {
+    if site_children(nodes).next().is_none() {
+        return;
+    }
+    let _ = writeln!(out, "## Commands\n");
+    let _ = writeln!(out, "| Command | Description |");
+    let _ = writeln!(out, "|---------|-------------|");
+    for node in site_children(nodes) {
+        let link = relative_link(file, &page_file(node));
+        let _ = writeln!(out, "| [`{}`]({}) | {} |", node.full_path.join(" "), link, node.doc_desc());
+    }
+    let _ = writeln!(out);
+}
+
+/// `title`, `description` and, for Docusaurus, the sidebar label and
+/// position among the sibling commands.
+fn write_frontmatter<W: Write>(
+    out: &mut W,
+    frontmatter: Option<Frontmatter>,
+    title: &str,
+    label: &str,
+    position: usize,
+    desc: &str,
+) {
+    let Some(frontmatter) = frontmatter else {
+        return;
+    };
+    let _ = writeln!(out, "---");
+    let _ = writeln!(out, "title: \"{}\"", yaml_escape(title));
+    if frontmatter == Frontmatter::Docusaurus {
+        let _ = writeln!(out, "sidebar_label: \"{}\"", yaml_escape(label));
+        let _ = writeln!(out, "sidebar_position: {}", position);
+    }
+    if !desc.is_empty() {
+        let _ = writeln!(out, "description: \"{}\"", yaml_escape(desc));
+    }
+    let _ = writeln!(out, "---\n");
+}
+
+/// Commands that get a page: hidden ones are left out, deprecated ones
+/// are badged like in the single page.
+fn site_children(nodes: &[CommandNode]) -> impl Iterator<Item = &CommandNode> {
+    nodes.iter().filter(|n| !n.hidden)
+}
+
+/// Every command with a page, parents first, with its 1-based position
+/// among its siblings.
+fn site_nodes<'a>(nodes: &'a [CommandNode], all: &mut Vec<(&'a CommandNode, usize)>) {
+    for (i, node) in site_children(nodes).enumerate() {
+        all.push((node, i + 1));
+        site_nodes(&node.children, all);
+    }
+}
+
+/// Page of a command, relative to the site root.
+fn page_file(node: &CommandNode) -> String {
+    let path = node.full_path.join("/");
+    if site_children(&node.children).next().is_some() {
+        format!("{}/index.md", path)
+    } else {
+        format!("{}.md", path)
+    }
+}
+
+/// Link from one page to another, both relative to the site root.
+fn relative_link(from: &str, to: &str) -> String {
+    let from_dir: Vec<&str> = from.split('/').collect();
+    let from_dir = &from_dir[..from_dir.len() - 1];
+    let to: Vec<&str> = to.split('/').collect();
+    let common = from_dir.iter().zip(&to[..to.len() - 1]).take_while(|(a, b)| a == b).count();
+    format!("{}{}", "../".repeat(from_dir.len() - common), to[common..].join("/"))
+}
+
+/// `sidebar.json` entries of `nodes`: label, page and nested items.
+fn sidebar_items(nodes: &[CommandNode]) -> Vec<serde_json::Value> {
+    site_children(nodes)
+        .map(|node| {
+            let mut item = serde_json::json!({ "label": node.name, "path": page_file(node) });
+            if site_children(&node.children).next().is_some() {
+                item["items"] = serde_json::Value::Array(sidebar_items(&node.children));
+            }
+            item
+        })
+        .collect()
 }
 
 // -- reStructuredText generation ----------------------------------------------
@@ -402,14 +717,14 @@ fn generate_rst<W: Write>(
         let _ = writeln!(out, "--------\n");
         for node in &all_nodes {
             let _ = writeln!(out, "**{}**", node.full_path.join(" "));
-            let _ = writeln!(out, "   {}\n", node.desc);
+            let _ = writeln!(out, "   {}\n", node.doc_desc());
         }
     } else if !cmds.is_empty() {
         let _ = writeln!(out, "Commands");
         let _ = writeln!(out, "--------\n");
         for cmd in &cmds {
             let _ = writeln!(out, "**{}**", cmd.name);
-            let _ = writeln!(out, "   {}\n", cmd.desc);
+            let _ = writeln!(out, "   {}\n", cmd.doc_desc());
         }
     }
 
@@ -422,11 +737,14 @@ fn generate_rst<W: Write>(
             if let Some(ref short) = flag.short {
                 flag_str = format!("-{}, {}", short, flag_str);
             }
+            if let Some(negated) = flag.negated_name() {
+                flag_str.push_str(&format!(", --{}", negated));
+            }
             if !flag.is_boolean {
-                flag_str.push_str(&format!(" *{}*", flag.type_name));
+                flag_str.push_str(&format!(" *{}*", flag.value_label()));
             }
             let _ = writeln!(out, "**{}**", flag_str);
-            let _ = writeln!(out, "   {}\n", flag.desc);
+            let _ = writeln!(out, "   {}\n", flag.doc_desc());
         }
     }
 
@@ -441,16 +759,35 @@ fn generate_rst<W: Write>(
                 if let Some(ref short) = flag.short {
                     flag_str = format!("-{}, {}", short, flag_str);
                 }
+                if let Some(negated) = flag.negated_name() {
+                    flag_str.push_str(&format!(", --{}", negated));
+                }
                 if !flag.is_boolean {
-                    flag_str.push_str(&format!(" *{}*", flag.type_name));
+                    flag_str.push_str(&format!(" *{}*", flag.value_label()));
                 }
                 let _ = writeln!(out, "**{}**", flag_str);
-                let _ = writeln!(out, "   {}\n", flag.desc);
+                let _ = writeln!(out, "   {}\n", flag.doc_desc());
             }
         }
     }
 }
 
+/// Examples section: each description followed by a bash code block.
+fn write_rst_examples<W: Write>(out: &mut W, cmd_name: &str, examples: &[(String, String)]) {
+    if examples.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "Examples");
+    let _ = writeln!(out, "--------\n");
+    for (command, desc) in examples {
+        if !desc.is_empty() {
+            let _ = writeln!(out, "{}\n", desc);
+        }
+        let _ = writeln!(out, ".. code-block:: bash\n");
+        let _ = writeln!(out, "   {}\n", example_line(cmd_name, command));
+    }
+}
+
 // -- YAML generation ----------------------------------------------------------
 
 /// Escape a string for YAML double-quoted output.
@@ -461,6 +798,23 @@ fn yaml_escape(s: &str) -> String {
         .replace('\r', "\\r")
 }
 
+/// Format strings as a YAML flow sequence of double-quoted scalars.
+fn yaml_list(items: &[String]) -> String {
+    let quoted: Vec<String> = items.iter().map(|s| format!("\"{}\"", yaml_escape(s))).collect();
+    format!("[{}]", quoted.join(", "))
+}
+
+/// Write `deprecated: true` (plus `replaced_by`) for a deprecated entry.
+fn write_yaml_deprecated<W: Write>(out: &mut W, deprecated: Option<&str>, indent: &str) {
+    let Some(replacement) = deprecated else {
+        return;
+    };
+    let _ = writeln!(out, "{}deprecated: true", indent);
+    if !replacement.is_empty() {
+        let _ = writeln!(out, "{}replaced_by: \"{}\"", indent, yaml_escape(replacement));
+    }
+}
+
 /// Generate documentation as YAML.
 fn generate_yaml<W: Write>(
     out: &mut W,
@@ -491,12 +845,14 @@ fn generate_yaml<W: Write>(
         for node in &all_nodes {
             let _ = writeln!(out, "  - name: \"{}\"", yaml_escape(&node.full_path.join(" ")));
             let _ = writeln!(out, "    description: \"{}\"", yaml_escape(&node.desc));
+            write_yaml_deprecated(out, node.deprecated().as_deref(), "    ");
         }
     } else if !cmds.is_empty() {
         let _ = writeln!(out, "commands:");
         for cmd in &cmds {
             let _ = writeln!(out, "  - name: \"{}\"", yaml_escape(&cmd.name));
             let _ = writeln!(out, "    description: \"{}\"", yaml_escape(&cmd.desc));
+            write_yaml_deprecated(out, cmd.deprecated.as_deref(), "    ");
         }
     }
 
@@ -511,8 +867,39 @@ fn generate_yaml<W: Write>(
             let _ = writeln!(out, "    description: \"{}\"", yaml_escape(&flag.desc));
             if flag.is_boolean {
                 let _ = writeln!(out, "    type: boolean");
+                if flag.is_counter {
+                    let _ = writeln!(out, "    repeatable: true");
+                }
             } else {
                 let _ = writeln!(out, "    type: \"{}\"", yaml_escape(&flag.type_name));
+                if flag.is_map {
+                    let _ = writeln!(out, "    map: true");
+                }
+            }
+            if let Some(negated) = flag.negated_name() {
+                let _ = writeln!(out, "    negated: \"{}\"", yaml_escape(&negated));
+            }
+            write_yaml_deprecated(out, flag.deprecated.as_deref(), "    ");
+            if !flag.choices.is_empty() {
+                let _ = writeln!(out, "    choices: {}", yaml_list(&flag.choices));
+            }
+            if let Some(ref min) = flag.minimum {
+                let _ = writeln!(out, "    minimum: {}", min);
+            }
+            if let Some(ref max) = flag.maximum {
+                let _ = writeln!(out, "    maximum: {}", max);
+            }
+            if let Some(ref pattern) = flag.pattern {
+                let _ = writeln!(out, "    pattern: \"{}\"", yaml_escape(pattern));
+            }
+            if let Some(ref var) = flag.env_var {
+                let _ = writeln!(out, "    env: \"{}\"", yaml_escape(var));
+            }
+            if let Some(ref group) = flag.group {
+                let _ = writeln!(out, "    group: \"{}\"", yaml_escape(group));
+            }
+            if !flag.requires.is_empty() {
+                let _ = writeln!(out, "    requires: {}", yaml_list(&flag.requires));
             }
         }
     }
@@ -532,8 +919,39 @@ fn generate_yaml<W: Write>(
                 let _ = writeln!(out, "        description: \"{}\"", yaml_escape(&flag.desc));
                 if flag.is_boolean {
                     let _ = writeln!(out, "        type: boolean");
+                    if flag.is_counter {
+                        let _ = writeln!(out, "        repeatable: true");
+                    }
                 } else {
                     let _ = writeln!(out, "        type: \"{}\"", yaml_escape(&flag.type_name));
+                    if flag.is_map {
+                        let _ = writeln!(out, "        map: true");
+                    }
+                }
+                if let Some(negated) = flag.negated_name() {
+                    let _ = writeln!(out, "        negated: \"{}\"", yaml_escape(&negated));
+                }
+                write_yaml_deprecated(out, flag.deprecated.as_deref(), "        ");
+                if !flag.choices.is_empty() {
+                    let _ = writeln!(out, "        choices: {}", yaml_list(&flag.choices));
+                }
+                if let Some(ref min) = flag.minimum {
+                    let _ = writeln!(out, "        minimum: {}", min);
+                }
+                if let Some(ref max) = flag.maximum {
+                    let _ = writeln!(out, "        maximum: {}", max);
+                }
+                if let Some(ref pattern) = flag.pattern {
+                    let _ = writeln!(out, "        pattern: \"{}\"", yaml_escape(pattern));
+                }
+                if let Some(ref var) = flag.env_var {
+                    let _ = writeln!(out, "        env: \"{}\"", yaml_escape(var));
+                }
+                if let Some(ref group) = flag.group {
+                    let _ = writeln!(out, "        group: \"{}\"", yaml_escape(group));
+                }
+                if !flag.requires.is_empty() {
+                    let _ = writeln!(out, "        requires: {}", yaml_list(&flag.requires));
                 }
             }
         }
@@ -558,7 +976,7 @@ fn generate_llm_claude<W: Write>(
 
     if leaves.is_empty() {
         // No subcommands — single tool for the script itself
-        let flags = extract_flags_for_llm(args_arr);
+        let flags = tool_flags(&extract_flags_for_llm(args_arr));
         write_claude_tool(out, &sanitize_tool_name(cmd_name), first_line, &flags, true);
     } else {
         for (i, leaf) in leaves.iter().enumerate() {
@@ -566,7 +984,7 @@ fn generate_llm_claude<W: Write>(
                 &format!("{}_{}", cmd_name, leaf.full_path.join("_")),
             );
             let desc = if leaf.desc.is_empty() { first_line } else { &leaf.desc };
-            write_claude_tool(out, &tool_name, desc, &leaf.flags, i == leaves.len() - 1);
+            write_claude_tool(out, &tool_name, desc, &tool_flags(&leaf.flags), i == leaves.len() - 1);
         }
     }
 
@@ -602,7 +1020,7 @@ fn generate_llm_openai<W: Write>(
 
     if leaves.is_empty() {
         // No subcommands — single tool for the script itself
-        let flags = extract_flags_for_llm(args_arr);
+        let flags = tool_flags(&extract_flags_for_llm(args_arr));
         write_openai_tool(out, &sanitize_tool_name(cmd_name), first_line, &flags, true);
     } else {
         for (i, leaf) in leaves.iter().enumerate() {
@@ -610,7 +1028,7 @@ fn generate_llm_openai<W: Write>(
                 &format!("{}_{}", cmd_name, leaf.full_path.join("_")),
             );
             let desc = if leaf.desc.is_empty() { first_line } else { &leaf.desc };
-            write_openai_tool(out, &tool_name, desc, &leaf.flags, i == leaves.len() - 1);
+            write_openai_tool(out, &tool_name, desc, &tool_flags(&leaf.flags), i == leaves.len() - 1);
         }
     }
 
@@ -631,3 +1049,91 @@ fn write_openai_tool<W: Write>(out: &mut W, name: &str, description: &str, flags
     let trailing = if is_last { "" } else { "," };
     let _ = writeln!(out, "  }}{}", trailing);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(path: &[&str], children: Vec<CommandNode>) -> CommandNode {
+        CommandNode {
+            name: path.last().unwrap().to_string(),
+            desc: format!("The {} command", path.last().unwrap()),
+            full_path: path.iter().map(|s| s.to_string()).collect(),
+            flags: Vec::new(),
+            positionals: Vec::new(),
+            children,
+            hidden: false,
+            annotations: Vec::new(),
+            func: None,
+        }
+    }
+
+    fn tree() -> Vec<CommandNode> {
+        let mut debug = node(&["debug"], Vec::new());
+        debug.hidden = true;
+        vec![
+            node(&["remote"], vec![node(&["remote", "add"], Vec::new())]),
+            node(&["log"], Vec::new()),
+            debug,
+        ]
+    }
+
+    #[test]
+    fn test_parse_site_args() {
+        let args = |a: &[&str]| parse_site_args(&a.iter().map(|s| s.to_string()).collect::<Vec<_>>());
+        assert_eq!(args(&[]), Ok(SiteOpts::default()));
+        assert_eq!(
+            args(&["--out", "docs/cli", "--frontmatter=mkdocs", "--sidebar"]),
+            Ok(SiteOpts { out: Some("docs/cli".to_string()), frontmatter: Some(Frontmatter::MkDocs), sidebar: true })
+        );
+        assert!(args(&["--out"]).is_err());
+        assert!(args(&["--out", "d", "--frontmatter", "hugo"]).is_err());
+        assert!(args(&["--sidebar"]).is_err());
+        assert!(args(&["--out", "d", "--pages"]).is_err());
+    }
+
+    #[test]
+    fn test_relative_link() {
+        assert_eq!(relative_link("index.md", "remote/index.md"), "remote/index.md");
+        assert_eq!(relative_link("remote/index.md", "remote/add.md"), "add.md");
+        assert_eq!(relative_link("remote/add.md", "remote/index.md"), "index.md");
+        assert_eq!(relative_link("remote/index.md", "index.md"), "../index.md");
+        assert_eq!(relative_link("a/b/c.md", "a/d/index.md"), "../d/index.md");
+    }
+
+    #[test]
+    fn test_site_pages() {
+        let tree = tree();
+        let mut nodes = Vec::new();
+        site_nodes(&tree, &mut nodes);
+        let pages: Vec<(String, usize)> = nodes.iter().map(|(n, pos)| (page_file(n), *pos)).collect();
+        assert_eq!(pages, [
+            ("remote/index.md".to_string(), 1),
+            ("remote/add.md".to_string(), 1),
+            ("log.md".to_string(), 2),
+        ]);
+
+        let mut page = Vec::new();
+        write_site_page(&mut page, "tool", &tree[0], "remote/index.md");
+        let page = String::from_utf8(page).unwrap();
+        assert!(page.starts_with("# tool remote\n\nThe remote command\n\n## Synopsis\n\n```\ntool remote <command>\n```\n"));
+        assert!(page.contains("| [`remote add`](add.md) | The add command |"));
+        assert!(page.ends_with("## See also\n\n- [`tool`](../index.md)\n"));
+    }
+
+    #[test]
+    fn test_frontmatter_and_sidebar() {
+        let mut out = Vec::new();
+        write_frontmatter(&mut out, Some(Frontmatter::Docusaurus), "tool log", "log", 2, "Show \"the\" log");
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "---\ntitle: \"tool log\"\nsidebar_label: \"log\"\nsidebar_position: 2\ndescription: \"Show \\\"the\\\" log\"\n---\n\n"
+        );
+
+        let items = serde_json::Value::Array(sidebar_items(&tree()));
+        assert_eq!(
+            items.to_string(),
+            r#"[{"items":[{"label":"add","path":"remote/add.md"}],"label":"remote","path":"remote/index.md"},{"label":"log","path":"log.md"}]"#
+        );
+    }
+}
@@ -14,10 +14,11 @@ use std::process::{Command, Stdio};
 #[allow(unused_imports)]
 use super::{
     extract_subcommands, extract_flags_for_llm,
-    json_escape, argsh_type_to_json, sanitize_tool_name,
-    build_command_tree, flatten_leaves,
-    FlagInfo, SubCmd,
+    json_escape, json_string_array, argsh_type_to_json, sanitize_tool_name,
+    build_command_tree, flatten_leaves, tool_flags,
+    extract_positionals, flag_constraints, FlagConstraint, FlagInfo, PositionalInfo, SubCmd,
 };
+use super::version::VersionInfo;
 
 // -- :usage::mcp builtin registration ----------------------------------------
 
@@ -115,6 +116,9 @@ pub fn mcp_main(args: &[String]) -> i32 {
         shell::get_script_name() // coverage:off - defensive_check: always called via :usage dispatch which sets COMMANDNAME
     };
     let script_path = shell::get_script_path();
+    // Detected on first read: finding the commit may run git
+    let app_name = commandname.first().unwrap_or(&cmd_name).clone();
+    let version = std::cell::OnceCell::new();
 
     // Build command tree and extract leaf tools
     let caller = shell::get_funcname(0);
@@ -124,12 +128,13 @@ pub fn mcp_main(args: &[String]) -> i32 {
     // Build owned leaf data for the session (tool_name, full_path, flags)
     let leaf_tools: Vec<LeafTool> = if leaves.is_empty() {
         // No subcommands (or all hidden) — single tool for the script itself
-        let top_flags = extract_flags_for_llm(&args_arr);
+        let top_flags = tool_flags(&extract_flags_for_llm(&args_arr));
         vec![LeafTool {
             tool_name: sanitize_tool_name(&cmd_name),
             full_path: Vec::new(),
             desc: title.lines().next().unwrap_or(title).trim().to_string(),
             flags: top_flags,
+            positionals: extract_positionals(&args_arr, shell::is_array),
             annotations: Vec::new(),
         }]
     } else {
@@ -141,7 +146,8 @@ pub fn mcp_main(args: &[String]) -> i32 {
                 tool_name,
                 full_path: leaf.full_path.clone(),
                 desc: leaf.desc.clone(),
-                flags: leaf.flags.clone(),
+                flags: tool_flags(&leaf.flags),
+                positionals: leaf.positionals.clone(),
                 annotations: leaf.annotations.clone(),
             }
         }).collect()
@@ -189,7 +195,9 @@ pub fn mcp_main(args: &[String]) -> i32 {
             }
             Some("resources/read") => {
                 let params = extract_json_field(&line, "params").unwrap_or_default();
-                handle_resources_read(&mut writer, &id, &params, &cmd_name, title, &leaf_tools);
+                handle_resources_read(&mut writer, &id, &params, &cmd_name, title, &leaf_tools, || {
+                    version.get_or_init(|| VersionInfo::detect(&app_name))
+                });
             }
             Some("prompts/list") => {
                 handle_prompts_list(&mut writer, &id);
@@ -260,6 +268,7 @@ pub(crate) struct LeafTool {
     pub(crate) full_path: Vec<String>,
     pub(crate) desc: String,
     pub(crate) flags: Vec<FlagInfo>,
+    pub(crate) positionals: Vec<PositionalInfo>,
     pub(crate) annotations: Vec<String>,
 }
 
@@ -276,7 +285,7 @@ pub(crate) fn handle_tools_list_v2<W: Write>(
         if i > 0 {
             tools.push(',');
         }
-        tools.push_str(&format_tool(&leaf.tool_name, &leaf.desc, &leaf.flags, &leaf.annotations));
+        tools.push_str(&format_tool(&leaf.tool_name, &leaf.desc, &leaf.flags, &leaf.positionals, &leaf.annotations));
     }
 
     tools.push_str("]}");
@@ -303,6 +312,7 @@ fn handle_tools_list<W: Write>(
             first_line,
             flags,
             &[],
+            &[],
         ));
     } else {
         for (i, cmd) in subcmds.iter().enumerate() {
@@ -311,7 +321,7 @@ fn handle_tools_list<W: Write>(
             }
             let tool_name = sanitize_tool_name(&format!("{}_{}", cmd_name, cmd.name));
             let desc = if cmd.desc.is_empty() { first_line } else { &cmd.desc }; // coverage:off - empty_desc: test subcmds always have descriptions
-            tools.push_str(&format_tool(&tool_name, desc, flags, &[]));
+            tools.push_str(&format_tool(&tool_name, desc, flags, &[], &[]));
         }
     }
 
@@ -319,15 +329,60 @@ fn handle_tools_list<W: Write>(
     write_jsonrpc_response(writer, id, &tools);
 }
 
+/// JSON Schema for `:%group` / `:&flag` constraints: one `oneOf` per group
+/// (wrapped in `allOf`) and a `dependentRequired` map. Empty if there are none.
+fn schema_constraints(flags: &[FlagInfo]) -> String {
+    let present = |name: &str| format!("{{\"required\":[\"{}\"]}}", json_escape(name));
+    let mut groups = Vec::new();
+    let mut dependent: Vec<(String, Vec<String>)> = Vec::new();
+    for constraint in flag_constraints(flags) {
+        match constraint {
+            FlagConstraint::Exclusive { members, required } => {
+                let mut alternatives: Vec<String> = members.iter().map(|m| present(m)).collect();
+                if !required {
+                    // "none of them" is also allowed
+                    alternatives.push(format!("{{\"not\":{{\"anyOf\":[{}]}}}}", alternatives.join(",")));
+                }
+                groups.push(format!("{{\"oneOf\":[{}]}}", alternatives.join(",")));
+            }
+            FlagConstraint::Requires { flag, other } => {
+                match dependent.iter_mut().find(|(f, _)| *f == flag) {
+                    Some((_, others)) => others.push(other),
+                    None => dependent.push((flag, vec![other])),
+                }
+            }
+        }
+    }
+
+    let mut s = String::new();
+    if !groups.is_empty() {
+        s.push_str(&format!(",\"allOf\":[{}]", groups.join(",")));
+    }
+    if !dependent.is_empty() {
+        let entries: Vec<String> = dependent
+            .iter()
+            .map(|(flag, others)| format!("\"{}\":{}", json_escape(flag), json_string_array(others, ",")))
+            .collect();
+        s.push_str(&format!(",\"dependentRequired\":{{{}}}", entries.join(",")));
+    }
+    s
+}
+
 /// Format a single MCP tool definition.
-pub(crate) fn format_tool(name: &str, description: &str, flags: &[FlagInfo], annotations: &[String]) -> String {
+pub(crate) fn format_tool(
+    name: &str,
+    description: &str,
+    flags: &[FlagInfo],
+    positionals: &[PositionalInfo],
+    annotations: &[String],
+) -> String {
     let mut s = String::from("{");
     s.push_str(&format!("\"name\":\"{}\",", json_escape(name)));
     s.push_str(&format!("\"title\":\"{}\",", json_escape(description)));
     s.push_str(&format!("\"description\":\"{}\",", json_escape(description)));
 
     // inputSchema — use minimal schema when no flags are present
-    if flags.is_empty() {
+    if flags.is_empty() && positionals.is_empty() {
         s.push_str("\"inputSchema\":{\"type\":\"object\",\"additionalProperties\":false}");
     } else {
         s.push_str("\"inputSchema\":{\"type\":\"object\",\"properties\":{");
@@ -336,19 +391,51 @@ pub(crate) fn format_tool(name: &str, description: &str, flags: &[FlagInfo], ann
             if i > 0 {
                 s.push(',');
             }
-            let json_type = argsh_type_to_json(&flag.type_name, flag.is_boolean);
+            let json_type = flag.json_type();
+            let keywords: String = flag
+                .schema_keywords(",")
+                .into_iter()
+                .map(|(key, value)| format!(",\"{}\":{}", key, value))
+                .collect();
             s.push_str(&format!(
-                "\"{}\":{{\"type\":\"{}\",\"description\":\"{}\"}}",
+                "\"{}\":{{\"type\":\"{}\"{},\"description\":\"{}\"}}",
                 json_escape(&flag.name),
                 json_type,
-                json_escape(&flag.desc)
+                keywords,
+                json_escape(&flag.doc_desc())
+            ));
+        }
+        for (i, pos) in positionals.iter().enumerate() {
+            if i > 0 || !flags.is_empty() {
+                s.push(',');
+            }
+            let mut value_schema = format!("\"type\":\"{}\"", argsh_type_to_json(&pos.type_name, false));
+            if !pos.choices.is_empty() {
+                value_schema.push_str(&format!(",\"enum\":{}", json_string_array(&pos.choices, ",")));
+            }
+            if pos.is_variadic {
+                value_schema = format!("\"type\":\"array\",\"items\":{{{}}}", value_schema);
+            }
+            s.push_str(&format!(
+                "\"{}\":{{{},\"description\":\"{}\"}}",
+                json_escape(&pos.name),
+                value_schema,
+                json_escape(&pos.desc)
             ));
         }
 
         s.push_str("},\"required\":[");
         let mut first = true;
+        for pos in positionals.iter().filter(|p| p.required) {
+            if !first {
+                s.push(',');
+            }
+            s.push_str(&format!("\"{}\"", json_escape(&pos.name)));
+            first = false;
+        }
         for flag in flags {
-            if flag.required { // coverage:off - required_flags: test fixtures don't use required flags
+            // Required members of a :%group are alternatives (see oneOf below)
+            if flag.required && flag.group.is_none() { // coverage:off - required_flags: test fixtures don't use required flags
                 if !first { // coverage:off
                     s.push(','); // coverage:off
                 } // coverage:off
@@ -356,7 +443,9 @@ pub(crate) fn format_tool(name: &str, description: &str, flags: &[FlagInfo], ann
                 first = false; // coverage:off
             } // coverage:off
         }
-        s.push_str("],\"additionalProperties\":false}");
+        s.push(']');
+        s.push_str(&schema_constraints(flags));
+        s.push_str(",\"additionalProperties\":false}");
     }
 
     // outputSchema for @json annotated tools
@@ -387,7 +476,8 @@ pub(crate) fn format_tool(name: &str, description: &str, flags: &[FlagInfo], ann
 /// Handle `resources/list` request.
 pub(crate) fn handle_resources_list<W: Write>(writer: &mut W, id: &Option<String>, cmd_name: &str) {
     let result = format!(
-        "{{\"resources\":[{{\"uri\":\"script:///help\",\"name\":\"Help\",\"description\":\"Help output for {}\",\"mimeType\":\"text/plain\"}},{{\"uri\":\"script:///version\",\"name\":\"Version\",\"description\":\"argsh version\",\"mimeType\":\"text/plain\"}}]}}",
+        "{{\"resources\":[{{\"uri\":\"script:///help\",\"name\":\"Help\",\"description\":\"Help output for {}\",\"mimeType\":\"text/plain\"}},{{\"uri\":\"script:///version\",\"name\":\"Version\",\"description\":\"Version of {} and argsh\",\"mimeType\":\"application/json\"}}]}}",
+        json_escape(cmd_name),
         json_escape(cmd_name)
     );
     write_jsonrpc_response(writer, id, &result);
@@ -396,13 +486,14 @@ pub(crate) fn handle_resources_list<W: Write>(writer: &mut W, id: &Option<String
 /// Handle `resources/read` request.
 /// Generates help text in-process from the already-parsed metadata to avoid
 /// re-executing the script (which could have side effects).
-pub(crate) fn handle_resources_read<W: Write>(
+pub(crate) fn handle_resources_read<'v, W: Write>(
     writer: &mut W,
     id: &Option<String>,
     params: &str,
     cmd_name: &str,
     title: &str,
     leaf_tools: &[LeafTool],
+    version: impl FnOnce() -> &'v VersionInfo,
 ) {
     let uri = extract_json_string(params, "uri");
     match uri.as_deref() {
@@ -420,10 +511,9 @@ pub(crate) fn handle_resources_read<W: Write>(
             write_jsonrpc_response(writer, id, &result);
         }
         Some("script:///version") => {
-            let version = shell::get_scalar("ARGSH_VERSION").unwrap_or_default();
             let result = format!(
-                "{{\"contents\":[{{\"uri\":\"script:///version\",\"mimeType\":\"text/plain\",\"text\":\"{}\"}}]}}",
-                json_escape(&version)
+                "{{\"contents\":[{{\"uri\":\"script:///version\",\"mimeType\":\"application/json\",\"text\":\"{}\"}}]}}",
+                json_escape(&version().to_json())
             );
             write_jsonrpc_response(writer, id, &result);
         }
@@ -529,11 +619,27 @@ fn handle_tools_call_v2<W: Write>(
     // Build CLI args — prepend all path segments then flags
     let mut cli_args: Vec<String> = leaf.full_path.clone();
     for (key, value) in &arg_pairs {
-        let flag = leaf.flags.iter().find(|f| f.name == *key);
-        if flag.is_none() {
+        let Some(flag) = leaf.flags.iter().find(|f| f.name == *key) else {
             continue; // Unknown arg — ignore (lenient for LLM hallucinations)
-        }
+        };
         match value {
+            // A :++ counter is given as often as the requested count
+            JsonValue::Number(n) if flag.is_counter => {
+                let count = n.parse::<usize>().unwrap_or(0);
+                cli_args.extend((0..count).map(|_| format!("--{}", key)));
+            }
+            // A map flag is given once per `key=value` entry
+            JsonValue::Str(s) if flag.is_map && s.starts_with('{') => {
+                for (entry_key, entry_value) in parse_flat_json_object(s) {
+                    let entry_value = match entry_value {
+                        JsonValue::Str(v) | JsonValue::Number(v) => v,
+                        JsonValue::Bool(b) => b.to_string(),
+                        JsonValue::Null => continue,
+                    };
+                    cli_args.push(format!("--{}", key));
+                    cli_args.push(format!("{}={}", entry_key, entry_value));
+                }
+            }
             JsonValue::Bool(true) => {
                 cli_args.push(format!("--{}", key));
             }
@@ -551,6 +657,29 @@ fn handle_tools_call_v2<W: Write>(
         }
     }
 
+    // Positionals follow the flags, in declaration order, after `--` so a
+    // value that starts with `-` is not taken for a flag. A `rest` field
+    // takes everything after `--`, so the separator goes right before it.
+    let mut flags_end = cli_args.len();
+    for pos in &leaf.positionals {
+        if pos.name == "rest" {
+            flags_end = cli_args.len();
+        }
+        let Some((_, value)) = arg_pairs.iter().find(|(key, _)| *key == pos.name) else {
+            continue;
+        };
+        match value {
+            JsonValue::Str(s) if pos.is_variadic && s.starts_with('[') => {
+                cli_args.extend(parse_json_array_items(s));
+            }
+            JsonValue::Str(s) | JsonValue::Number(s) => cli_args.push(s.clone()),
+            JsonValue::Bool(_) | JsonValue::Null => {}
+        }
+    }
+    if cli_args.len() > flags_end {
+        cli_args.insert(flags_end, "--".to_string());
+    }
+
     // Execute
     let (exit_code, stdout_text, stderr_text) = execute_tool(script_path, &cli_args);
 
@@ -1087,6 +1216,34 @@ pub fn parse_flat_json_object(json: &str) -> Vec<(String, JsonValue)> {
     pairs
 }
 
+/// Parse the scalar items of a JSON array (`["a", 1]`) as strings.
+pub fn parse_json_array_items(json: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let trimmed = json.trim();
+    if !trimmed.starts_with('[') || !trimmed.ends_with(']') {
+        return items;
+    }
+    let inner = &trimmed[1..trimmed.len() - 1];
+
+    let mut pos = 0;
+    while pos < inner.len() {
+        let rest = inner[pos..].trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+        if rest.is_empty() {
+            break;
+        }
+        pos = inner.len() - rest.len();
+        let (value, used) = parse_json_value(rest);
+        pos += used;
+        match value {
+            JsonValue::Str(s) | JsonValue::Number(s) => items.push(s),
+            JsonValue::Bool(b) => items.push(b.to_string()),
+            JsonValue::Null => {}
+        }
+    }
+
+    items
+}
+
 /// Parse a single JSON value, returning the value and number of bytes consumed
 /// (relative to the original input, including any leading whitespace).
 fn parse_json_value(s: &str) -> (JsonValue, usize) {
@@ -2,14 +2,19 @@
 //!
 //! Mirrors: libraries/args.sh (:usage function)
 
+pub mod complete;
 pub mod completion;
 pub mod docgen;
 pub mod mcp;
+pub mod style;
+pub mod tree;
+pub mod version;
 
 use crate::{word_list_to_vec, BashBuiltin, SyncPtr, WordList, BUILTIN_ENABLED};
 use crate::field;
 use crate::shared;
 use crate::shell;
+use argsh_syntax::usage::{deprecation, parse_annotations};
 use std::ffi::{c_char, c_int};
 use std::io::Write;
 
@@ -96,8 +101,12 @@ extern "C" fn usage_help_builtin_fn(word_list: *const WordList) -> c_int {
 
 /// Main entry point for :usage::help builtin.
 /// Called via "${usage[@]}" after caller's setup code has run.
-/// Args: title [original_usage_pairs...]
+/// Args: title [original_usage_pairs...], or for the command tree
+/// (`help <command>`, `--help-all`): [command...] -- title [original_usage_pairs...]
 pub fn usage_help_main(args: &[String]) -> i32 {
+    if let Some(sep) = tree::separator(args) {
+        return tree::help_tree_main(args, sep);
+    }
     if args.is_empty() { // coverage:off - defensive_check: always called via deferred dispatch with title
         return shared::error_usage("", ":usage::help requires a title argument"); // coverage:off
     } // coverage:off
@@ -127,6 +136,7 @@ pub fn usage_main(args: &[String]) -> i32 {
     let usage_arr = shell::read_array("usage");
     let args_arr = field::dedup_inherited(&shell::read_array("args"));
     shell::write_array("args", &args_arr);
+    field::capture_defaults(&args_arr);
 
     // Validate usage array is pairs (REVIEW finding 4: use % for Rust <1.87 compat)
     #[allow(clippy::manual_is_multiple_of)]
@@ -147,6 +157,12 @@ pub fn usage_main(args: &[String]) -> i32 {
         return 0;
     }
 
+    // --help-all renders the whole command tree, deferred like --help
+    if cli_args[0] == "--help-all" {
+        defer_help_tree(title, &usage_arr, &[]);
+        return 0;
+    }
+
     // Handle --argsh
     let commandname = shell::get_commandname();
     if commandname.is_empty() && cli_args.first().map(|s| s.as_str()) == Some("--argsh") {
@@ -156,8 +172,22 @@ pub fn usage_main(args: &[String]) -> i32 {
         return shared::HELP_EXIT;
     }
 
-    // Parse flags and find command
     let mut cli: Vec<String> = cli_args.to_vec();
+
+    // --version is the `version` command (the script's own or the built-in one),
+    // unless the script declares a `version` flag
+    if cli[0] == "--version" && field::field_lookup("version", &args_arr).is_none() {
+        cli[0] = "version".to_string();
+    }
+
+    // `__complete` answers the generated completion scripts. The words after
+    // it are a partial command line, so they are not parsed here.
+    if cli[0] == "__complete" {
+        defer_builtin_command("complete", title, &usage_arr, cli.split_off(1));
+        return 0;
+    }
+
+    // Parse flags and find command
     let mut cmd: Option<String> = None;
     let mut matched: Vec<String> = Vec::new();
 
@@ -174,8 +204,9 @@ pub fn usage_main(args: &[String]) -> i32 {
             continue;
         }
 
-        // Try parsing as flag -- set_bool for :usage uses set_or_increment
-        match shared::parse_flag_at(&mut cli, idx, &args_arr, &mut matched, set_or_increment) {
+        // Try parsing as flag -- set_bool for :usage uses set_or_increment. Names must
+        // be exact here so an abbreviation is never claimed from a subcommand.
+        match shared::parse_flag_at(&mut cli, idx, &args_arr, &mut matched, set_or_increment, false) {
             Ok(true) => {
                 // idx stays the same since parse_flag_at modifies cli
             }
@@ -188,6 +219,12 @@ pub fn usage_main(args: &[String]) -> i32 {
         }
     }
 
+    // Flags absent from the command line take their :$VAR environment variable
+    let ret = shared::apply_env_flags(&args_arr, &mut matched);
+    if ret != 0 {
+        return ret;
+    }
+
     // Check required flags
     let ret = shared::check_required_flags(&args_arr, &matched);
     if ret != 0 {
@@ -223,6 +260,7 @@ pub fn usage_main(args: &[String]) -> i32 {
                 if entry.contains(":-") {
                     func = entry.split(":-").nth(1).unwrap_or("").to_string();
                     func = func.strip_prefix('#').unwrap_or(&func).to_string();
+                    func = func.split('@').next().unwrap_or("").to_string();
                 } else {
                     // Use first part (before |) as function name
                     func = entry_clean.split('|').next().unwrap_or("").to_string();
@@ -239,26 +277,48 @@ pub fn usage_main(args: &[String]) -> i32 {
         // Command not found in usage array — check built-in special commands.
         // These are always available without being listed in the usage array.
         return match cmd.as_str() {
-            "completion" | "docgen" | "mcp" => {
+            "completion" | "docgen" | "mcp" | "version" => {
                 defer_builtin_command(&cmd, title, &usage_arr, cli);
                 0
             }
+            // `help` alone is the usual help; `help <command>...` shows that part of the tree
+            "help" if cli.is_empty() => {
+                let mut new_usage = vec![":usage::help".to_string(), title.clone()];
+                new_usage.extend_from_slice(&usage_arr);
+                shell::write_array("usage", &new_usage);
+                0
+            }
+            "help" => {
+                defer_help_tree(title, &usage_arr, &cli);
+                0
+            }
             _ => {
-                let msg = match shared::suggest_command(&cmd, &usage_arr) {
-                    Some(suggestion) => format!("Invalid command: {}. Did you mean '{}'?", cmd, suggestion),
+                let suggestion = shared::suggest_command(&cmd, &usage_arr);
+                let msg = match suggestion {
+                    Some(ref suggestion) => format!("Invalid command: {}. Did you mean '{}'?", cmd, suggestion),
                     None => format!("Invalid command: {}", cmd),
                 };
-                shared::error_usage(&cmd, &msg)
+                shared::error_suggest(shared::ErrorKind::UnknownCommand, "", &cmd, &msg, suggestion.as_deref())
             }
         };
     }
 
+    // A deprecated command keeps working but warns
+    if let Some(replacement) = deprecation(&parse_annotations(&found_field).1) {
+        shared::warn_deprecated(&cmd, &replacement);
+    }
+
     // Resolve function with prefix fallback
     let explicit = found_field.contains(":-");
 
     if explicit {
         if !shell::function_exists(&func) && !is_deferred_builtin(&func) {
-            return shared::error_usage(&cmd, &format!("Invalid command: {}", cmd));
+            return shared::error_kind(
+                shared::ErrorKind::UnknownCommand,
+                "",
+                &cmd,
+                &format!("Invalid command: {}", cmd),
+            );
         }
     } else {
         // Resolution order:
@@ -301,7 +361,12 @@ pub fn usage_main(args: &[String]) -> i32 {
         }
 
         if !resolved && !shell::function_exists(&func) {
-            return shared::error_usage(&cmd, &format!("Invalid command: {}", cmd));
+            return shared::error_kind(
+                shared::ErrorKind::UnknownCommand,
+                "",
+                &cmd,
+                &format!("Invalid command: {}", cmd),
+            );
         }
     }
 
@@ -332,12 +397,15 @@ pub fn usage_main(args: &[String]) -> i32 {
     0 // EXECUTION_SUCCESS
 }
 
-/// Check if a function name is a deferred :usage:: builtin (completion, docgen, mcp).
+/// Check if a function name is a deferred :usage:: builtin (complete, completion, docgen, mcp, version).
 fn is_deferred_builtin(name: &str) -> bool {
-    matches!(name, ":usage::completion" | ":usage::docgen" | ":usage::mcp")
+    matches!(
+        name,
+        ":usage::complete" | ":usage::completion" | ":usage::docgen" | ":usage::mcp" | ":usage::version"
+    )
 }
 
-/// Defer a built-in special command (completion, docgen, mcp) via the usage array.
+/// Defer a built-in special command (completion, docgen, mcp, version) via the usage array.
 fn defer_builtin_command(cmd: &str, title: &str, usage_arr: &[String], cli: Vec<String>) {
     let builtin_name = format!(":usage::{}", cmd);
     let mut new_usage = vec![builtin_name];
@@ -349,6 +417,16 @@ fn defer_builtin_command(cmd: &str, title: &str, usage_arr: &[String], cli: Vec<
     shell::append_commandname(cmd);
 }
 
+/// Defer the command tree below `path` to `:usage::help` (see `tree`).
+fn defer_help_tree(title: &str, usage_arr: &[String], path: &[String]) {
+    let mut new_usage = vec![":usage::help".to_string()];
+    new_usage.extend(path.iter().filter(|word| *word != "--").cloned());
+    new_usage.push("--".to_string());
+    new_usage.push(title.to_string());
+    new_usage.extend_from_slice(usage_arr);
+    shell::write_array("usage", &new_usage);
+}
+
 fn set_or_increment(name: &str) {
     if shell::is_array(name) {
         shell::array_append(name, "1"); // coverage:off - dead_code: parse_flag_at handles array booleans directly, never calls set_bool for arrays
@@ -364,6 +442,7 @@ fn usage_help_text(title: &str, usage_arr: &[String], args_arr: &[String]) {
     let out = std::io::stdout();
     let mut out = out.lock();
     let fw = shell::get_field_width();
+    let style = style::Style::detect();
     let commandname = shell::get_commandname();
     let cmdname_str = commandname.join(" ");
 
@@ -374,38 +453,40 @@ fn usage_help_text(title: &str, usage_arr: &[String], args_arr: &[String]) {
 
     // Usage line
     let _ = writeln!(out);
-    let _ = writeln!(out, "Usage: {} <command> [args]", cmdname_str);
+    let _ = writeln!(out, "{} {} <command> [args]", style.heading("Usage:"), cmdname_str);
 
     // Commands
     let first_is_group = usage_arr.first().map(|s| s.as_str()) == Some("-");
     if !first_is_group {
-        let _ = writeln!(out, "\nAvailable Commands:");
+        let _ = writeln!(out, "\n{}", style.heading("Available Commands:"));
     }
 
     for i in (0..usage_arr.len()).step_by(2) {
         let entry = &usage_arr[i];
         let desc = usage_arr.get(i + 1).map(|s| s.as_str()).unwrap_or("");
 
-        // Hidden commands (# prefix)
-        if entry.starts_with('#') {
+        // Hidden (# prefix) and deprecated commands
+        if entry.starts_with('#') || deprecation(&parse_annotations(entry).1).is_some() {
             continue;
         }
 
         // Group separator
         if entry == "-" {
             let _ = writeln!(out);
-            let _ = writeln!(out, "{}", desc);
+            let _ = writeln!(out, "{}", style.heading(desc));
             continue;
         }
 
         // Command name (before | or : or @)
         let raw_name = entry.split(['|', ':']).next().unwrap_or(entry);
         let name = raw_name.split('@').next().unwrap_or(raw_name);
-        let _ = writeln!(out, "  {:width$} {}", name, desc, width = fw);
+        let _ = write!(out, "  {} ", style.name_padded(name, fw));
+        style.write_desc(&mut out, desc, 3 + fw.max(name.len()));
     }
 
     // Flags section
-    print_flags_section(&mut out, args_arr, fw);
+    print_flags_section(&mut out, args_arr, &style);
+    style::print_examples(&mut out, &style, &cmdname_str);
 
     let _ = writeln!(out);
     let _ = writeln!(
@@ -416,7 +497,7 @@ fn usage_help_text(title: &str, usage_arr: &[String], args_arr: &[String]) {
 }
 
 /// Print the flags/options section (shared between :usage and :args help).
-pub fn print_flags_section<W: Write>(out: &mut W, args_arr: &[String], _fw: usize) {
+pub fn print_flags_section<W: Write>(out: &mut W, args_arr: &[String], style: &style::Style) {
     // Build args with help added if not present
     let mut args_with_help: Vec<String> = args_arr.to_vec();
     let has_help = args_arr.iter().any(|s| s == "help|h:+");
@@ -429,7 +510,7 @@ pub fn print_flags_section<W: Write>(out: &mut W, args_arr: &[String], _fw: usiz
     let mut flag_indices: Vec<usize> = Vec::new();
     for i in (0..args_with_help.len()).step_by(2) {
         let entry = &args_with_help[i];
-        if entry.contains('|') || entry == "-" {
+        if field::is_flag_spec(entry) || entry == "-" {
             flag_indices.push(i);
         }
     }
@@ -441,22 +522,24 @@ pub fn print_flags_section<W: Write>(out: &mut W, args_arr: &[String], _fw: usiz
     // Check if first flag is a group separator
     let first_is_group = args_with_help.get(flag_indices[0]).map(|s| s.as_str()) == Some("-");
     if !first_is_group {
-        let _ = writeln!(out, "\nOptions:");
+        let _ = writeln!(out, "\n{}", style.heading("Options:"));
     }
 
     for &i in &flag_indices {
         let entry = &args_with_help[i];
         let desc = args_with_help.get(i + 1).map(|s| s.as_str()).unwrap_or("");
 
-        // Hidden (# prefix or :# modifier)
-        if entry.starts_with('#') || field::parse_field(entry).map(|f| f.is_hidden).unwrap_or(false) {
+        // Hidden (# prefix or :# modifier) and deprecated flags
+        if entry.starts_with('#')
+            || field::parse_field(entry).is_ok_and(|f| f.hidden || f.deprecated.is_some())
+        {
             continue;
         }
 
         // Group separator
         if entry == "-" {
             let _ = writeln!(out);
-            let _ = writeln!(out, "{}", desc);
+            let _ = writeln!(out, "{}", style.heading(desc));
             continue;
         }
 
@@ -469,10 +552,19 @@ pub fn print_flags_section<W: Write>(out: &mut W, args_arr: &[String], _fw: usiz
             } // coverage:off
         };
         let field_fmt = field::format_field(&def);
-        let _ = writeln!(out, "{}", field_fmt);
+        let _ = writeln!(out, "{}", style.name(&field_fmt));
 
         // Description (indented 11 spaces)
-        let _ = writeln!(out, "           {}", desc);
+        let _ = write!(out, "           ");
+        style.write_desc(out, desc, 11);
+    }
+
+    let constraints = flag_constraints(&extract_flags(args_arr));
+    if !constraints.is_empty() {
+        let _ = writeln!(out, "\n{}", style.heading("Constraints:"));
+        for constraint in &constraints {
+            let _ = writeln!(out, "  {}", constraint.describe(|f| format!("--{}", f)));
+        }
     }
 }
 
@@ -482,6 +574,14 @@ pub fn print_flags_section<W: Write>(out: &mut W, args_arr: &[String], _fw: usiz
 pub struct SubCmd {
     pub name: String,
     pub desc: String,
+    pub deprecated: Option<String>, // @deprecated[=new-name] — replacement, empty if none
+}
+
+impl SubCmd {
+    /// Description for generated docs, with a deprecation badge.
+    pub fn doc_desc(&self) -> String {
+        format!("{}{}", deprecated_badge(self.deprecated.as_deref(), ""), self.desc)
+    }
 }
 
 /// Extracted flag info from args array.
@@ -490,8 +590,205 @@ pub struct FlagInfo {
     pub short: Option<String>,
     pub desc: String,
     pub is_boolean: bool,
+    pub is_counter: bool, // :++ — counts repetitions instead of taking a value
+    pub is_map: bool,     // `local -A` variable — repeated `key=value` pairs
+    pub default_on: bool, // boolean that starts out on — offers `--no-<name>`
+    pub type_name: String,
+    pub type_label: String, // `FieldDef::type_label`, e.g. `{a|b}` or `int[1..10]`
+    pub required: bool,
+    pub choices: Vec<String>,
+    pub minimum: Option<String>,
+    pub maximum: Option<String>,
+    pub pattern: Option<String>,
+    pub env_var: Option<String>,
+    pub group: Option<String>,
+    pub requires: Vec<String>,
+    pub deprecated: Option<String>, // @deprecated[=new-name] — replacement, empty if none
+}
+
+impl FlagInfo {
+    fn from_field(def: field::FieldDef, desc: &str) -> Self {
+        let type_label = def.type_label();
+        let default_on = def.is_default_on();
+        let spec = def.spec;
+        FlagInfo {
+            name: spec.display_name,
+            short: spec.short,
+            desc: desc.to_string(),
+            is_boolean: spec.is_boolean,
+            is_counter: spec.is_counter,
+            is_map: def.is_map,
+            default_on,
+            type_name: spec.type_name,
+            type_label,
+            required: spec.required,
+            choices: spec.choices,
+            minimum: spec.minimum,
+            maximum: spec.maximum,
+            pattern: spec.pattern,
+            env_var: spec.env_var,
+            group: spec.group,
+            requires: spec.requires,
+            deprecated: spec.deprecated,
+        }
+    }
+
+    /// Type as shown in generated docs; map flags add `key=`.
+    pub fn value_label(&self) -> String {
+        if self.is_map {
+            format!("key={}", self.type_label)
+        } else {
+            self.type_label.clone()
+        }
+    }
+
+    /// JSON Schema type; a `:++` counter is an integer, not a boolean, and a
+    /// map flag is an object.
+    pub fn json_type(&self) -> &'static str {
+        if self.is_map {
+            "object"
+        } else if self.is_counter {
+            "integer"
+        } else {
+            argsh_type_to_json(&self.type_name, self.is_boolean)
+        }
+    }
+
+    /// JSON Schema validation keywords (`enum`, `minimum`, `maximum`,
+    /// `pattern`) as key/raw-JSON pairs; `sep` joins enum array items. For a
+    /// map flag they describe the values, nested in `additionalProperties`.
+    pub fn schema_keywords(&self, sep: &str) -> Vec<(&'static str, String)> {
+        let mut kw = Vec::new();
+        if self.is_counter {
+            kw.push(("minimum", "0".to_string()));
+        }
+        if !self.choices.is_empty() {
+            kw.push(("enum", json_string_array(&self.choices, sep)));
+        }
+        if let Some(ref min) = self.minimum {
+            kw.push(("minimum", min.clone()));
+        }
+        if let Some(ref max) = self.maximum {
+            kw.push(("maximum", max.clone()));
+        }
+        if let Some(ref pattern) = self.pattern {
+            kw.push(("pattern", format!("\"{}\"", json_escape(pattern))));
+        }
+        if !self.is_map {
+            return kw;
+        }
+        let mut value = format!("{{\"type\":\"{}\"", argsh_type_to_json(&self.type_name, false));
+        for (key, raw) in kw {
+            value.push_str(&format!("{}\"{}\":{}", sep, key, raw));
+        }
+        value.push('}');
+        vec![("additionalProperties", value)]
+    }
+
+    /// The built-in `-h, --help` flag.
+    pub fn help() -> Self {
+        FlagInfo {
+            name: "help".to_string(),
+            short: Some("h".to_string()),
+            desc: "Show this help message".to_string(),
+            is_boolean: true,
+            is_counter: false,
+            is_map: false,
+            default_on: false,
+            type_name: String::new(),
+            type_label: String::new(),
+            required: false,
+            choices: Vec::new(),
+            minimum: None,
+            maximum: None,
+            pattern: None,
+            env_var: None,
+            group: None,
+            requires: Vec::new(),
+            deprecated: None,
+        }
+    }
+
+    /// The `no-<name>` form that switches a boolean flag off, offered for
+    /// flags that default to on — the same rule as the help text.
+    pub fn negated_name(&self) -> Option<String> {
+        self.default_on.then(|| format!("no-{}", self.name))
+    }
+
+    /// Description as shown in generated docs and tool schemas, noting the
+    /// `:$VAR` environment fallback when one is declared and a deprecation.
+    pub fn doc_desc(&self) -> String {
+        let desc = format!("{}{}", deprecated_badge(self.deprecated.as_deref(), "--"), self.desc);
+        match self.env_var {
+            Some(ref var) => format!("{} (env: {})", desc, var),
+            None => desc,
+        }
+    }
+}
+
+/// Extracted positional argument info from args array.
+#[derive(Clone)]
+pub struct PositionalInfo {
+    pub name: String,
+    pub desc: String,
     pub type_name: String,
+    pub choices: Vec<String>,
     pub required: bool,
+    pub is_variadic: bool, // backed by a `local -a` array
+}
+
+/// A flag-group constraint from `:%group` / `:&flag`, for help text and docs.
+pub enum FlagConstraint {
+    /// Members of a `:%group`: at most one, or exactly one if any is `:!`.
+    Exclusive { members: Vec<String>, required: bool },
+    /// `flag` may only be given together with `other`.
+    Requires { flag: String, other: String },
+}
+
+impl FlagConstraint {
+    /// Human-readable form; `fmt_flag` renders each flag name.
+    pub fn describe(&self, fmt_flag: impl Fn(&str) -> String) -> String {
+        match self {
+            FlagConstraint::Exclusive { members, required } => {
+                let names: Vec<String> = members.iter().map(|m| fmt_flag(m)).collect();
+                let quantity = if *required { "exactly one of" } else { "at most one of" };
+                format!("{} {}", quantity, names.join(", "))
+            }
+            FlagConstraint::Requires { flag, other } => {
+                format!("{} requires {}", fmt_flag(flag), fmt_flag(other))
+            }
+        }
+    }
+}
+
+/// Collect the group constraints declared on `flags`, in declaration order.
+pub fn flag_constraints(flags: &[FlagInfo]) -> Vec<FlagConstraint> {
+    let mut out = Vec::new();
+    let mut seen_groups: Vec<&str> = Vec::new();
+    for flag in flags {
+        let Some(ref group) = flag.group else { continue };
+        if seen_groups.contains(&group.as_str()) {
+            continue;
+        }
+        seen_groups.push(group);
+        let members: Vec<&FlagInfo> = flags.iter().filter(|f| f.group.as_ref() == Some(group)).collect();
+        out.push(FlagConstraint::Exclusive {
+            members: members.iter().map(|f| f.name.clone()).collect(),
+            required: members.iter().any(|f| f.required),
+        });
+    }
+    for flag in flags {
+        for other in &flag.requires {
+            let other_name = field::field_name(other, true);
+            let other = flags
+                .iter()
+                .find(|f| field::field_name(&f.name, true) == other_name)
+                .map(|f| f.name.clone())
+                .unwrap_or_else(|| other.clone());
+            out.push(FlagConstraint::Requires { flag: flag.name.clone(), other });
+        }
+    }
+    out
 }
 
 // -- Shared extraction helpers ------------------------------------------------
@@ -513,6 +810,7 @@ pub fn extract_subcommands(usage_pairs: &[String]) -> Vec<SubCmd> {
         cmds.push(SubCmd {
             name: name.to_string(),
             desc: desc.to_string(),
+            deprecated: deprecation(&parse_annotations(entry).1),
         });
     }
     cmds
@@ -528,7 +826,7 @@ pub fn extract_flags(args_arr: &[String]) -> Vec<FlagInfo> {
         let desc = args_arr.get(i + 1).map(|s| s.as_str()).unwrap_or("");
 
         // Only process flags (have | separator), skip positionals and group separators
-        if !entry.contains('|') || entry == "-" || entry.starts_with('#') {
+        if !field::is_flag_spec(entry) || entry == "-" || entry.starts_with('#') {
             continue;
         }
 
@@ -536,26 +834,12 @@ pub fn extract_flags(args_arr: &[String]) -> Vec<FlagInfo> {
             if def.name == "help" {
                 has_help = true;
             }
-            flags.push(FlagInfo {
-                name: def.display_name,
-                short: def.short,
-                desc: desc.to_string(),
-                is_boolean: def.is_boolean,
-                type_name: def.type_name,
-                required: def.required,
-            });
+            flags.push(FlagInfo::from_field(def, desc));
         }
     }
 
     if !has_help {
-        flags.push(FlagInfo {
-            name: "help".to_string(),
-            short: Some("h".to_string()),
-            desc: "Show this help message".to_string(),
-            is_boolean: true,
-            type_name: String::new(),
-            required: false,
-        });
+        flags.push(FlagInfo::help());
     }
 
     flags
@@ -569,7 +853,7 @@ pub fn extract_flags_for_llm(args_arr: &[String]) -> Vec<FlagInfo> {
         let entry = &args_arr[i];
         let desc = args_arr.get(i + 1).map(|s| s.as_str()).unwrap_or("");
 
-        if !entry.contains('|') || entry == "-" || entry.starts_with('#') {
+        if !field::is_flag_spec(entry) || entry == "-" || entry.starts_with('#') {
             continue;
         }
 
@@ -577,18 +861,144 @@ pub fn extract_flags_for_llm(args_arr: &[String]) -> Vec<FlagInfo> {
             if def.name == "help" {
                 continue;
             }
-            flags.push(FlagInfo {
-                name: def.display_name,
-                short: def.short,
+            flags.push(FlagInfo::from_field(def, desc));
+        }
+    }
+
+    flags
+}
+
+/// Extract visible positionals from args array. `is_array` tells whether a
+/// variable is declared as an array (variadic positional).
+pub fn extract_positionals(args_arr: &[String], is_array: impl Fn(&str) -> bool) -> Vec<PositionalInfo> {
+    let mut positionals = Vec::new();
+
+    for i in field::positional_fields(args_arr) {
+        let entry = &args_arr[i];
+        let desc = args_arr.get(i + 1).map(|s| s.as_str()).unwrap_or("");
+        if entry.starts_with('#') {
+            continue;
+        }
+
+        if let Ok(def) = field::parse_field(entry) {
+            let is_variadic = is_array(&def.name);
+            positionals.push(PositionalInfo {
+                required: if is_variadic { def.required } else { !def.is_optional && !def.has_default },
+                name: def.spec.name,
                 desc: desc.to_string(),
-                is_boolean: def.is_boolean,
-                type_name: def.type_name,
-                required: def.required,
+                type_name: def.spec.type_name,
+                choices: def.spec.choices,
+                is_variadic,
             });
         }
     }
 
-    flags
+    positionals
+}
+
+/// Names declared with `local -a` / `declare -a` in a `declare -f` body.
+pub fn declared_arrays(body: &str) -> Vec<String> {
+    declared_with(body, 'a')
+}
+
+/// Names declared with `local -A` / `declare -A` in a `declare -f` body.
+pub fn declared_maps(body: &str) -> Vec<String> {
+    declared_with(body, 'A')
+}
+
+/// Names a `declare -f` body initializes to an "on" value, anything but
+/// empty or `0` (`local color=1`); the boolean flags that offer `--no-`.
+pub fn declared_on(body: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    for line in body.lines() {
+        let words = declaration_words(line.trim().trim_end_matches(';'));
+        let mut words = words.iter();
+        if !matches!(words.next().map(String::as_str), Some("local" | "declare" | "typeset")) {
+            continue;
+        }
+        for word in words.filter(|w| !w.starts_with('-')) {
+            let Some((name, value)) = word.split_once('=') else { continue };
+            let value = value.trim_matches(|c| c == '\'' || c == '"');
+            if !matches!(value, "" | "0") {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names
+}
+
+/// Names declared by `local` / `declare` / `typeset` with the attribute
+/// letter `attr`.
+///
+/// Like `parse_shell_array_from_body`, this reads bash's own `declare -f`
+/// reprint, where each declaration is a simple command on its own line
+/// ending in `;`. Initializers are skipped as whole words (quotes and
+/// `(...)` included), so names after them are still found. Declarations
+/// whose names are computed at runtime (`local -a "${name}"`) are not seen.
+fn declared_with(body: &str, attr: char) -> Vec<String> {
+    let mut names = Vec::new();
+    for line in body.lines() {
+        let words = declaration_words(line.trim().trim_end_matches(';'));
+        let mut words = words.iter();
+        if !matches!(words.next().map(String::as_str), Some("local" | "declare" | "typeset")) {
+            continue;
+        }
+        let mut has_attr = false;
+        for word in words {
+            if let Some(opts) = word.strip_prefix('-') {
+                has_attr |= opts.contains(attr);
+                continue;
+            }
+            if !has_attr {
+                break;
+            }
+            let name = word.split(['=', '+', '[']).next().unwrap_or(word);
+            if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names
+}
+
+/// Split a declaration into words at unquoted blanks outside `(...)`,
+/// keeping quotes so an initializer stays one word.
+fn declaration_words(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for c in line.chars() {
+        if escaped {
+            escaped = false;
+        } else if let Some(q) = quote {
+            if c == q {
+                quote = None;
+            } else {
+                escaped = c == '\\' && q == '"';
+            }
+        } else {
+            match c {
+                '\\' => escaped = true,
+                '\'' | '"' => quote = Some(c),
+                '(' => depth += 1,
+                ')' => depth = depth.saturating_sub(1),
+                ' ' | '\t' if depth == 0 => {
+                    if !word.is_empty() {
+                        words.push(std::mem::take(&mut word));
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
 }
 
 // -- Command tree walker ------------------------------------------------------
@@ -600,27 +1010,32 @@ pub struct CommandNode {
     pub desc: String,           // description
     pub full_path: Vec<String>, // full command path (e.g. ["cluster", "up"])
     pub flags: Vec<FlagInfo>,   // per-command flags from args array (excludes help)
+    pub positionals: Vec<PositionalInfo>, // positionals from the command's own args array
     pub children: Vec<CommandNode>, // nested subcommands
     pub hidden: bool,           // #-prefixed entries
     pub annotations: Vec<String>, // e.g. ["readonly", "json"] from @readonly, @json suffixes
+    pub func: Option<String>,   // resolved function, None if it does not exist
 }
 
-/// Parse `@` annotations from a usage entry name.
-///
-/// Format: `name@readonly`, `name@destructive@json` (multiple annotations).
-/// Returns (entry_without_annotations, annotations_vec).
-pub fn parse_entry_annotations(entry: &str) -> (String, Vec<String>) {
-    if let Some(at_pos) = entry.find('@') {
-        let name_part = entry[..at_pos].to_string();
-        let annot_part = &entry[at_pos + 1..];
-        let annotations: Vec<String> = annot_part
-            .split('@')
-            .filter(|s| !s.is_empty())
-            .map(|s| s.to_string())
-            .collect();
-        (name_part, annotations)
-    } else {
-        (entry.to_string(), Vec::new())
+impl CommandNode {
+    /// Replacement for a `@deprecated` command (see `deprecation`).
+    pub fn deprecated(&self) -> Option<String> {
+        deprecation(&self.annotations)
+    }
+
+    /// Description for generated docs, with a deprecation badge.
+    pub fn doc_desc(&self) -> String {
+        format!("{}{}", deprecated_badge(self.deprecated().as_deref(), ""), self.desc)
+    }
+}
+
+/// Badge prepended to the description of a deprecated command or flag in
+/// generated docs; `prefix` is put before the replacement name (`--`).
+pub fn deprecated_badge(deprecated: Option<&str>, prefix: &str) -> String {
+    match deprecated {
+        None => String::new(),
+        Some("") => "[deprecated] ".to_string(),
+        Some(replacement) => format!("[deprecated: use {}{}] ", prefix, replacement),
     }
 }
 
@@ -650,7 +1065,7 @@ pub fn build_command_tree(
         let entry_clean = entry.strip_prefix('#').unwrap_or(entry);
 
         // Extract annotations from @ suffix (e.g. "serve@readonly" -> ["readonly"])
-        let (entry_no_annot, annotations) = parse_entry_annotations(entry_clean);
+        let (entry_no_annot, annotations) = parse_annotations(entry_clean);
 
         // Extract command name (before | or :)
         let entry_cmd_part = entry_no_annot.split(':').next().unwrap_or(&entry_no_annot);
@@ -667,9 +1082,11 @@ pub fn build_command_tree(
                     desc: desc.to_string(),
                     full_path: vec![name.to_string()],
                     flags: parent_flags.clone(),
+                    positionals: Vec::new(),
                     children: Vec::new(),
                     hidden,
                     annotations: annotations.clone(),
+                    func: None,
                 });
                 continue;
             }
@@ -686,9 +1103,11 @@ pub fn build_command_tree(
                     desc: desc.to_string(),
                     full_path: vec![name.to_string()],
                     flags: parent_flags.clone(),
+                    positionals: Vec::new(),
                     children: Vec::new(),
                     hidden,
                     annotations: annotations.clone(),
+                    func: Some(func_name.clone()),
                 });
                 continue;
             }
@@ -697,14 +1116,24 @@ pub fn build_command_tree(
         // Parse usage and args arrays from the function body
         let sub_usage = parse_shell_array_from_body(&body, "usage");
         let sub_args = parse_shell_array_from_body(&body, "args");
+        let arrays = declared_arrays(&body);
+        let positionals = extract_positionals(&sub_args, |name| arrays.iter().any(|a| a == name));
 
         // Determine flags for this node: its own args if present, else inherit parent
         let own_flags = if sub_args.is_empty() {
             parent_flags.clone()
         } else {
-            // Merge parent flags with own flags (own flags take precedence)
-            let own = extract_flags_for_llm(&sub_args);
-            let mut merged = own;
+            // Merge parent flags with own flags (own flags take precedence);
+            // the function's locals are not live, so maps and defaults come
+            // from its body
+            let maps = declared_maps(&body);
+            let on = declared_on(&body);
+            let mut merged = extract_flags_for_llm(&sub_args);
+            for flag in &mut merged {
+                let var = field::field_name(&flag.name, true);
+                flag.is_map |= maps.contains(&var);
+                flag.default_on = flag.is_boolean && on.contains(&var);
+            }
             // Add parent flags that aren't overridden by child
             for f in parent_flags.iter() {
                 if !merged.iter().any(|existing| existing.name == f.name) {
@@ -721,9 +1150,11 @@ pub fn build_command_tree(
                 desc: desc.to_string(),
                 full_path: vec![name.to_string()],
                 flags: own_flags,
+                positionals,
                 children: Vec::new(),
                 hidden,
                 annotations: annotations.clone(),
+                func: Some(func_name.clone()),
             });
         } else {
             // Recurse into children
@@ -744,9 +1175,11 @@ pub fn build_command_tree(
                 desc: desc.to_string(),
                 full_path: vec![name.to_string()],
                 flags: own_flags,
+                positionals,
                 children,
                 hidden,
                 annotations: annotations.clone(),
+                func: Some(func_name),
             });
         }
     }
@@ -962,11 +1395,11 @@ fn parse_shell_words(content: &str) -> Vec<String> {
 }
 
 /// Flatten a command tree to leaf nodes only (nodes with no children).
-/// Skips hidden nodes.
+/// Skips hidden and deprecated nodes, which are not offered as tools.
 pub fn flatten_leaves(nodes: &[CommandNode]) -> Vec<&CommandNode> {
     let mut leaves = Vec::new();
     for node in nodes {
-        if node.hidden {
+        if node.hidden || node.deprecated().is_some() {
             continue;
         }
         if node.children.is_empty() {
@@ -978,6 +1411,11 @@ pub fn flatten_leaves(nodes: &[CommandNode]) -> Vec<&CommandNode> {
     leaves
 }
 
+/// Flags offered in LLM/MCP tool schemas; deprecated flags are left out.
+pub fn tool_flags(flags: &[FlagInfo]) -> Vec<FlagInfo> {
+    flags.iter().filter(|f| f.deprecated.is_none()).cloned().collect()
+}
+
 /// Flatten a command tree to all visible nodes (both intermediate and leaf).
 pub fn flatten_all(nodes: &[CommandNode]) -> Vec<&CommandNode> {
     let mut all = Vec::new();
@@ -1000,8 +1438,20 @@ impl Clone for FlagInfo {
             short: self.short.clone(),
             desc: self.desc.clone(),
             is_boolean: self.is_boolean,
+            is_counter: self.is_counter,
+            is_map: self.is_map,
+            default_on: self.default_on,
             type_name: self.type_name.clone(),
+            type_label: self.type_label.clone(),
             required: self.required,
+            choices: self.choices.clone(),
+            minimum: self.minimum.clone(),
+            maximum: self.maximum.clone(),
+            pattern: self.pattern.clone(),
+            env_var: self.env_var.clone(),
+            group: self.group.clone(),
+            requires: self.requires.clone(),
+            deprecated: self.deprecated.clone(),
         }
     }
 }
@@ -1029,6 +1479,12 @@ pub fn argsh_type_to_json(type_name: &str, is_boolean: bool) -> &'static str {
     }
 }
 
+/// Format strings as a JSON array (`["a", "b"]`), joined with `sep`.
+pub fn json_string_array(items: &[String], sep: &str) -> String {
+    let quoted: Vec<String> = items.iter().map(|s| format!("\"{}\"", json_escape(s))).collect();
+    format!("[{}]", quoted.join(sep))
+}
+
 /// Sanitize a string for use as a tool/function name (only [a-zA-Z0-9_-]).
 pub fn sanitize_tool_name(s: &str) -> String {
     s.chars()
@@ -1040,18 +1496,22 @@ pub fn sanitize_tool_name(s: &str) -> String {
 pub fn write_tool_properties<W: Write>(out: &mut W, flags: &[FlagInfo], indent: &str) {
     let _ = writeln!(out, "{}\"properties\": {{", indent);
     for (i, flag) in flags.iter().enumerate() {
-        let json_type = argsh_type_to_json(&flag.type_name, flag.is_boolean);
+        let json_type = flag.json_type();
         let trailing = if i < flags.len() - 1 { "," } else { "" };
         let _ = writeln!(out, "{}  \"{}\": {{", indent, json_escape(&flag.name));
         let _ = writeln!(out, "{}    \"type\": \"{}\",", indent, json_type);
-        let _ = writeln!(out, "{}    \"description\": \"{}\"", indent, json_escape(&flag.desc));
+        for (key, value) in flag.schema_keywords(", ") {
+            let _ = writeln!(out, "{}    \"{}\": {},", indent, key, value);
+        }
+        let _ = writeln!(out, "{}    \"description\": \"{}\"", indent, json_escape(&flag.doc_desc()));
         let _ = writeln!(out, "{}  }}{}", indent, trailing);
     }
     let _ = writeln!(out, "{}}},", indent);
 
+    // Required members of a :%group are alternatives, not each required
     let required: Vec<&str> = flags
         .iter()
-        .filter(|f| f.required)
+        .filter(|f| f.required && f.group.is_none())
         .map(|f| f.name.as_str())
         .collect();
     let _ = write!(out, "{}\"required\": [", indent);
@@ -1182,18 +1642,22 @@ mod tests {
                 desc: "Start".to_string(),
                 full_path: vec!["serve".to_string()],
                 flags: Vec::new(),
+                positionals: Vec::new(),
                 children: Vec::new(),
                 hidden: false,
                 annotations: Vec::new(),
+                func: None,
             },
             CommandNode {
                 name: "build".to_string(),
                 desc: "Build".to_string(),
                 full_path: vec!["build".to_string()],
                 flags: Vec::new(),
+                positionals: Vec::new(),
                 children: Vec::new(),
                 hidden: false,
                 annotations: Vec::new(),
+                func: None,
             },
         ];
         let leaves = flatten_leaves(&nodes);
@@ -1210,28 +1674,34 @@ mod tests {
                 desc: "Cluster".to_string(),
                 full_path: vec!["cluster".to_string()],
                 flags: Vec::new(),
+                positionals: Vec::new(),
                 children: vec![
                     CommandNode {
                         name: "up".to_string(),
                         desc: "Start".to_string(),
                         full_path: vec!["cluster".to_string(), "up".to_string()],
                         flags: Vec::new(),
+                        positionals: Vec::new(),
                         children: Vec::new(),
                         hidden: false,
                         annotations: Vec::new(),
+                        func: None,
                     },
                     CommandNode {
                         name: "down".to_string(),
                         desc: "Stop".to_string(),
                         full_path: vec!["cluster".to_string(), "down".to_string()],
                         flags: Vec::new(),
+                        positionals: Vec::new(),
                         children: Vec::new(),
                         hidden: false,
                         annotations: Vec::new(),
+                        func: None,
                     },
                 ],
                 hidden: false,
                 annotations: Vec::new(),
+                func: None,
             },
         ];
         let leaves = flatten_leaves(&nodes);
@@ -1248,18 +1718,22 @@ mod tests {
                 desc: "Visible".to_string(),
                 full_path: vec!["visible".to_string()],
                 flags: Vec::new(),
+                positionals: Vec::new(),
                 children: Vec::new(),
                 hidden: false,
                 annotations: Vec::new(),
+                func: None,
             },
             CommandNode {
                 name: "hidden".to_string(),
                 desc: "Hidden".to_string(),
                 full_path: vec!["hidden".to_string()],
                 flags: Vec::new(),
+                positionals: Vec::new(),
                 children: Vec::new(),
                 hidden: true,
                 annotations: Vec::new(),
+                func: None,
             },
         ];
         let leaves = flatten_leaves(&nodes);
@@ -1275,28 +1749,34 @@ mod tests {
                 desc: "Start".to_string(),
                 full_path: vec!["serve".to_string()],
                 flags: Vec::new(),
+                positionals: Vec::new(),
                 children: Vec::new(),
                 hidden: false,
                 annotations: Vec::new(),
+                func: None,
             },
             CommandNode {
                 name: "cluster".to_string(),
                 desc: "Cluster".to_string(),
                 full_path: vec!["cluster".to_string()],
                 flags: Vec::new(),
+                positionals: Vec::new(),
                 children: vec![
                     CommandNode {
                         name: "up".to_string(),
                         desc: "Start".to_string(),
                         full_path: vec!["cluster".to_string(), "up".to_string()],
                         flags: Vec::new(),
+                        positionals: Vec::new(),
                         children: Vec::new(),
                         hidden: false,
                         annotations: Vec::new(),
+                        func: None,
                     },
                 ],
                 hidden: false,
                 annotations: Vec::new(),
+                func: None,
             },
         ];
         let all = flatten_all(&nodes);
@@ -1307,45 +1787,21 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_entry_annotations_none() {
-        let (name, annots) = parse_entry_annotations("serve");
-        assert_eq!(name, "serve");
-        assert!(annots.is_empty());
-    }
-
-    #[test]
-    fn test_parse_entry_annotations_single() {
-        let (name, annots) = parse_entry_annotations("serve@readonly");
-        assert_eq!(name, "serve");
-        assert_eq!(annots, vec!["readonly"]);
-    }
-
-    #[test]
-    fn test_parse_entry_annotations_multiple() {
-        let (name, annots) = parse_entry_annotations("build@destructive@json");
-        assert_eq!(name, "build");
-        assert_eq!(annots, vec!["destructive", "json"]);
-    }
-
-    #[test]
-    fn test_parse_entry_annotations_with_alias() {
-        let (name, annots) = parse_entry_annotations("serve|s@readonly");
-        assert_eq!(name, "serve|s");
-        assert_eq!(annots, vec!["readonly"]);
-    }
-
-    #[test]
-    fn test_parse_entry_annotations_with_explicit_mapping() {
-        let (name, annots) = parse_entry_annotations("serve:-my_serve@readonly");
-        assert_eq!(name, "serve:-my_serve");
-        assert_eq!(annots, vec!["readonly"]);
+    fn test_deprecation() {
+        let annots = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(deprecation(&annots(&["readonly"])), None);
+        assert_eq!(deprecation(&annots(&["readonly", "deprecated"])), Some(String::new()));
+        assert_eq!(deprecation(&annots(&["deprecated=release"])), Some("release".to_string()));
+        assert_eq!(deprecated_badge(None, "--"), "");
+        assert_eq!(deprecated_badge(Some(""), "--"), "[deprecated] ");
+        assert_eq!(deprecated_badge(Some("dry-run"), "--"), "[deprecated: use --dry-run] ");
     }
 
     // -- MCP pure function tests (live here to avoid bash FFI linker errors) --
 
     #[test]
     fn test_mcp_format_tool_no_flags_no_annotations() {
-        let result = mcp::format_tool("my_tool", "A test tool", &[], &[]);
+        let result = mcp::format_tool("my_tool", "A test tool", &[], &[], &[]);
         assert!(result.contains("\"name\":\"my_tool\""));
         assert!(result.contains("\"title\":\"A test tool\""));
         assert!(result.contains("\"description\":\"A test tool\""));
@@ -1363,42 +1819,366 @@ mod tests {
             short: Some("p".to_string()),
             desc: "Port number".to_string(),
             is_boolean: false,
+            is_counter: false,
+            is_map: false,
+            default_on: false,
             type_name: "int".to_string(),
+            type_label: "int".to_string(),
             required: false,
+            choices: Vec::new(),
+            minimum: None,
+            maximum: None,
+            pattern: None,
+            env_var: None,
+            group: None,
+            requires: Vec::new(),
+            deprecated: None,
         }];
-        let result = mcp::format_tool("serve", "Start server", &flags, &[]);
+        let result = mcp::format_tool("serve", "Start server", &flags, &[], &[]);
         assert!(result.contains("\"port\":{\"type\":\"integer\""));
         assert!(result.contains("\"additionalProperties\":false"));
         assert!(result.contains("\"title\":\"Start server\""));
     }
 
+    #[test]
+    fn test_mcp_format_tool_enum_choices() {
+        let flags = vec![FlagInfo {
+            name: "env".to_string(),
+            short: Some("e".to_string()),
+            desc: "Target environment".to_string(),
+            is_boolean: false,
+            is_counter: false,
+            is_map: false,
+            default_on: false,
+            type_name: "enum".to_string(),
+            type_label: "enum".to_string(),
+            required: false,
+            choices: vec!["dev".to_string(), "staging".to_string(), "prod".to_string()],
+            minimum: None,
+            maximum: None,
+            pattern: None,
+            env_var: None,
+            group: None,
+            requires: Vec::new(),
+            deprecated: None,
+        }];
+        let result = mcp::format_tool("deploy", "Deploy", &flags, &[], &[]);
+        assert!(result.contains(
+            "\"env\":{\"type\":\"string\",\"enum\":[\"dev\",\"staging\",\"prod\"],\"description\":\"Target environment\"}"
+        ));
+    }
+
+    #[test]
+    fn test_mcp_format_tool_env_fallback_in_description() {
+        let flags = vec![FlagInfo {
+            name: "port".to_string(),
+            short: Some("p".to_string()),
+            desc: "Port number".to_string(),
+            is_boolean: false,
+            is_counter: false,
+            is_map: false,
+            default_on: false,
+            type_name: "int".to_string(),
+            type_label: "int".to_string(),
+            required: true,
+            choices: Vec::new(),
+            minimum: None,
+            maximum: None,
+            pattern: None,
+            env_var: Some("APP_PORT".to_string()),
+            group: None,
+            requires: Vec::new(),
+            deprecated: None,
+        }];
+        let result = mcp::format_tool("serve", "Start server", &flags, &[], &[]);
+        assert!(result.contains("\"description\":\"Port number (env: APP_PORT)\""));
+    }
+
+    #[test]
+    fn test_write_tool_properties_enum_choices() {
+        let flags = vec![FlagInfo {
+            name: "env".to_string(),
+            short: None,
+            desc: "Target environment".to_string(),
+            is_boolean: false,
+            is_counter: false,
+            is_map: false,
+            default_on: false,
+            type_name: "enum".to_string(),
+            type_label: "enum".to_string(),
+            required: true,
+            choices: vec!["dev".to_string(), "prod".to_string()],
+            minimum: None,
+            maximum: None,
+            pattern: None,
+            env_var: None,
+            group: None,
+            requires: Vec::new(),
+            deprecated: None,
+        }];
+        let mut buf = Vec::new();
+        write_tool_properties(&mut buf, &flags, "");
+        let output = String::from_utf8(buf).unwrap();
+        assert!(output.contains("    \"enum\": [\"dev\", \"prod\"],\n"));
+        assert!(output.contains("\"required\": [\"env\"]"));
+    }
+
+    #[test]
+    fn test_mcp_format_tool_range_and_pattern() {
+        let flags = vec![
+            FlagInfo {
+                name: "port".to_string(),
+                short: Some("p".to_string()),
+                desc: "Port number".to_string(),
+                is_boolean: false,
+                is_counter: false,
+                is_map: false,
+                default_on: false,
+                type_name: "int".to_string(),
+                type_label: "int".to_string(),
+                required: false,
+                choices: Vec::new(),
+                minimum: Some("1".to_string()),
+                maximum: Some("65535".to_string()),
+                pattern: None,
+                env_var: None,
+                group: None,
+                requires: Vec::new(),
+                deprecated: None,
+            },
+            FlagInfo {
+                name: "tag".to_string(),
+                short: None,
+                desc: "Release tag".to_string(),
+                is_boolean: false,
+                is_counter: false,
+                is_map: false,
+                default_on: false,
+                type_name: "string".to_string(),
+                type_label: "string".to_string(),
+                required: false,
+                choices: Vec::new(),
+                minimum: None,
+                maximum: None,
+                pattern: Some("^v[0-9]+\\.".to_string()),
+                env_var: None,
+                group: None,
+                requires: Vec::new(),
+                deprecated: None,
+            },
+        ];
+        let result = mcp::format_tool("serve", "Start server", &flags, &[], &[]);
+        assert!(result.contains("\"port\":{\"type\":\"integer\",\"minimum\":1,\"maximum\":65535,\"description\""));
+        assert!(result.contains("\"tag\":{\"type\":\"string\",\"pattern\":\"^v[0-9]+\\\\.\",\"description\""));
+    }
+
+    #[test]
+    fn test_write_tool_properties_minimum_only() {
+        let flags = vec![FlagInfo {
+            name: "ratio".to_string(),
+            short: None,
+            desc: "Sampling ratio".to_string(),
+            is_boolean: false,
+            is_counter: false,
+            is_map: false,
+            default_on: false,
+            type_name: "float".to_string(),
+            type_label: "float".to_string(),
+            required: false,
+            choices: Vec::new(),
+            minimum: Some("0.5".to_string()),
+            maximum: None,
+            pattern: None,
+            env_var: None,
+            group: None,
+            requires: Vec::new(),
+            deprecated: None,
+        }];
+        let mut buf = Vec::new();
+        write_tool_properties(&mut buf, &flags, "");
+        let output = String::from_utf8(buf).unwrap();
+        assert!(output.contains("    \"minimum\": 0.5,\n"));
+        assert!(!output.contains("maximum"));
+    }
+
+    fn group_flag(name: &str, group: Option<&str>, requires: &[&str], required: bool) -> FlagInfo {
+        FlagInfo {
+            name: name.to_string(),
+            short: None,
+            desc: String::new(),
+            is_boolean: true,
+            is_counter: false,
+            is_map: false,
+            default_on: false,
+            type_name: String::new(),
+            type_label: String::new(),
+            required,
+            choices: Vec::new(),
+            minimum: None,
+            maximum: None,
+            pattern: None,
+            env_var: None,
+            group: group.map(str::to_string),
+            requires: requires.iter().map(|r| r.to_string()).collect(),
+            deprecated: None,
+        }
+    }
+
+    #[test]
+    fn test_flag_constraints_describe() {
+        let flags = vec![
+            group_flag("file", Some("input"), &[], true),
+            group_flag("url", Some("input"), &[], false),
+            group_flag("json", Some("format"), &[], false),
+            group_flag("yaml", Some("format"), &[], false),
+            group_flag("tls-cert", None, &["tls_key"], false),
+            group_flag("tls-key", None, &[], false),
+        ];
+        let described: Vec<String> = flag_constraints(&flags)
+            .iter()
+            .map(|c| c.describe(|f| format!("--{}", f)))
+            .collect();
+        assert_eq!(described, vec![
+            "exactly one of --file, --url",
+            "at most one of --json, --yaml",
+            "--tls-cert requires --tls-key",
+        ]);
+    }
+
+    #[test]
+    fn test_mcp_format_tool_flag_groups() {
+        let flags = vec![
+            group_flag("file", Some("input"), &[], true),
+            group_flag("url", Some("input"), &[], true),
+            group_flag("json", Some("format"), &[], false),
+            group_flag("yaml", Some("format"), &[], false),
+            group_flag("tls-cert", None, &["tls-key"], false),
+            group_flag("tls-key", None, &[], false),
+        ];
+        let result = mcp::format_tool("fetch", "Fetch", &flags, &[], &[]);
+        assert!(result.contains("\"required\":[]"));
+        assert!(result.contains(
+            "\"allOf\":[{\"oneOf\":[{\"required\":[\"file\"]},{\"required\":[\"url\"]}]},\
+             {\"oneOf\":[{\"required\":[\"json\"]},{\"required\":[\"yaml\"]},\
+             {\"not\":{\"anyOf\":[{\"required\":[\"json\"]},{\"required\":[\"yaml\"]}]}}]}]"
+        ));
+        assert!(result.contains("\"dependentRequired\":{\"tls-cert\":[\"tls-key\"]}"));
+    }
+
+    #[test]
+    fn test_flag_negated_name() {
+        let mut color = group_flag("color", None, &[], false);
+        assert_eq!(color.negated_name(), None);
+        color.default_on = true;
+        assert_eq!(color.negated_name(), Some("no-color".to_string()));
+        assert_eq!(FlagInfo::help().negated_name(), None);
+    }
+
+    #[test]
+    fn test_mcp_format_tool_counter() {
+        let mut verbose = group_flag("verbose", None, &[], false);
+        verbose.is_counter = true;
+        assert_eq!(verbose.json_type(), "integer");
+        let result = mcp::format_tool("run", "Run", &[verbose], &[], &[]);
+        assert!(result.contains("\"verbose\":{\"type\":\"integer\""), "{}", result);
+        assert!(result.contains("\"minimum\":0"), "{}", result);
+    }
+
+    #[test]
+    fn test_mcp_format_tool_map() {
+        let mut label = group_flag("label", None, &[], false);
+        label.is_boolean = false;
+        label.type_name = "int".to_string();
+        label.minimum = Some("0".to_string());
+        label.is_map = true;
+        let result = mcp::format_tool("run", "Run", &[label], &[], &[]);
+        assert!(
+            result.contains("\"label\":{\"type\":\"object\",\"additionalProperties\":{\"type\":\"integer\",\"minimum\":0}"),
+            "{}",
+            result
+        );
+    }
+
+    #[test]
+    fn test_mcp_format_tool_positionals() {
+        let positionals = vec![
+            PositionalInfo {
+                name: "src".to_string(),
+                desc: "Sources".to_string(),
+                type_name: "file".to_string(),
+                choices: Vec::new(),
+                required: true,
+                is_variadic: true,
+            },
+            PositionalInfo {
+                name: "dest".to_string(),
+                desc: "Destination".to_string(),
+                type_name: "string".to_string(),
+                choices: Vec::new(),
+                required: false,
+                is_variadic: false,
+            },
+        ];
+        let result = mcp::format_tool("cp", "Copy", &[], &positionals, &[]);
+        assert!(result.contains(
+            "\"src\":{\"type\":\"array\",\"items\":{\"type\":\"string\"},\"description\":\"Sources\"}"
+        ));
+        assert!(result.contains("\"dest\":{\"type\":\"string\",\"description\":\"Destination\"}"));
+        assert!(result.contains("\"required\":[\"src\"]"));
+    }
+
+    #[test]
+    fn test_parse_json_array_items() {
+        assert_eq!(mcp::parse_json_array_items("[\"a b\", 2, true]"), vec!["a b", "2", "true"]);
+        assert!(mcp::parse_json_array_items("[]").is_empty());
+        assert!(mcp::parse_json_array_items("\"a\"").is_empty());
+    }
+
+    #[test]
+    fn test_declared_arrays() {
+        let body = r#"cp ()
+{
+    local -a src;
+    local dest;
+    local -a verbose args=('src' "Sources" 'dest' "Destination");
+    :args "Copy" "${@}"
+}"#;
+        assert_eq!(declared_arrays(body), vec!["src", "verbose", "args"]);
+        let body = "run ()\n{\n    local -a x=(\"a b\" 'c) d') y;\n    declare -ar z;\n    typeset -a t;\n    local n=(1) -a m;\n}";
+        assert_eq!(declared_arrays(body), vec!["x", "y", "z", "t"]);
+        let body = "run ()\n{\n    local -A labels;\n    local -a tags;\n}";
+        assert_eq!(declared_maps(body), vec!["labels"]);
+        let body = "run ()\n{\n    local color=1 quiet=0 name='' force=\"yes\";\n}";
+        assert_eq!(declared_on(body), vec!["color", "force"]);
+    }
+
     #[test]
     fn test_mcp_format_tool_readonly_annotation() {
-        let result = mcp::format_tool("serve", "desc", &[], &["readonly".to_string()]);
+        let result = mcp::format_tool("serve", "desc", &[], &[], &["readonly".to_string()]);
         assert!(result.contains("\"annotations\":{\"readOnlyHint\":true}"));
     }
 
     #[test]
     fn test_mcp_format_tool_destructive_annotation() {
-        let result = mcp::format_tool("build", "desc", &[], &["destructive".to_string()]);
+        let result = mcp::format_tool("build", "desc", &[], &[], &["destructive".to_string()]);
         assert!(result.contains("\"annotations\":{\"destructiveHint\":true}"));
     }
 
     #[test]
     fn test_mcp_format_tool_idempotent_annotation() {
-        let result = mcp::format_tool("up", "desc", &[], &["idempotent".to_string()]);
+        let result = mcp::format_tool("up", "desc", &[], &[], &["idempotent".to_string()]);
         assert!(result.contains("\"annotations\":{\"idempotentHint\":true}"));
     }
 
     #[test]
     fn test_mcp_format_tool_openworld_annotation() {
-        let result = mcp::format_tool("search", "desc", &[], &["openworld".to_string()]);
+        let result = mcp::format_tool("search", "desc", &[], &[], &["openworld".to_string()]);
         assert!(result.contains("\"annotations\":{\"openWorldHint\":true}"));
     }
 
     #[test]
     fn test_mcp_format_tool_json_annotation() {
-        let result = mcp::format_tool("status", "desc", &[], &["json".to_string()]);
+        let result = mcp::format_tool("status", "desc", &[], &[], &["json".to_string()]);
         assert!(result.contains("\"outputSchema\":{}"));
         assert!(!result.contains("\"annotations\""));
     }
@@ -1406,7 +2186,7 @@ mod tests {
     #[test]
     fn test_mcp_format_tool_multiple_annotations() {
         let annots = vec!["readonly".to_string(), "json".to_string()];
-        let result = mcp::format_tool("status", "desc", &[], &annots);
+        let result = mcp::format_tool("status", "desc", &[], &[], &annots);
         assert!(result.contains("\"outputSchema\":{}"));
         assert!(result.contains("\"annotations\":{\"readOnlyHint\":true}"));
     }
@@ -1492,6 +2272,7 @@ mod tests {
                 full_path: vec!["serve".to_string()],
                 desc: "Start the server".to_string(),
                 flags: Vec::new(),
+                positionals: Vec::new(),
                 annotations: vec!["readonly".to_string()],
             },
             mcp::LeafTool {
@@ -1499,6 +2280,7 @@ mod tests {
                 full_path: vec!["status".to_string()],
                 desc: "Get status".to_string(),
                 flags: Vec::new(),
+                positionals: Vec::new(),
                 annotations: vec!["json".to_string()],
             },
         ];
@@ -1599,16 +2381,27 @@ mod tests {
         assert_eq!(subcmd, "serve");
     }
 
+    fn app_version() -> version::VersionInfo {
+        version::VersionInfo {
+            name: "app".to_string(),
+            version: Some("1.2.0".to_string()),
+            commit: None,
+            argsh: None,
+        }
+    }
+
     #[test]
     fn test_mcp_resources_read_help() {
         let mut buf = Vec::new();
         let id = Some("1".to_string());
+        let version = app_version();
         let tools = vec![
             mcp::LeafTool {
                 tool_name: "app_serve".to_string(),
                 full_path: vec!["serve".to_string()],
                 desc: "Start server".to_string(),
                 flags: vec![],
+                positionals: Vec::new(),
                 annotations: vec![],
             },
             mcp::LeafTool {
@@ -1616,13 +2409,14 @@ mod tests {
                 full_path: vec!["build".to_string()],
                 desc: "Build project".to_string(),
                 flags: vec![],
+                positionals: Vec::new(),
                 annotations: vec![],
             },
         ];
         mcp::handle_resources_read(
             &mut buf, &id,
             r#"{"uri":"script:///help"}"#,
-            "app", "My app", &tools,
+            "app", "My app", &tools, || &version,
         );
         let output = String::from_utf8(buf).unwrap();
         assert!(output.contains("My app"));
@@ -1635,14 +2429,16 @@ mod tests {
         let mut buf = Vec::new();
         let id = Some("1".to_string());
         let tools = vec![];
+        let version = app_version();
         mcp::handle_resources_read(
             &mut buf, &id,
             r#"{"uri":"script:///version"}"#,
-            "app", "My app", &tools,
+            "app", "My app", &tools, || &version,
         );
         let output = String::from_utf8(buf).unwrap();
         assert!(output.contains("\"result\""));
         assert!(output.contains("script:///version"));
+        assert!(output.contains(r#"\"version\":\"1.2.0\""#));
     }
 
     #[test]
@@ -1650,10 +2446,11 @@ mod tests {
         let mut buf = Vec::new();
         let id = Some("1".to_string());
         let tools = vec![];
+        let version = app_version();
         mcp::handle_resources_read(
             &mut buf, &id,
             r#"{"uri":"script:///nonexistent"}"#,
-            "app", "My app", &tools,
+            "app", "My app", &tools, || &version,
         );
         let output = String::from_utf8(buf).unwrap();
         assert!(output.contains("-32602"));
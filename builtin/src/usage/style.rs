@@ -0,0 +1,180 @@
+//! Help text styling -- ANSI colors, description wrapping and the examples
+//! block shared by :usage and :args help.
+//!
+//! Colors are used when stdout is a terminal, unless `NO_COLOR` is set;
+//! `FORCE_COLOR` turns them on for pipes. Descriptions wrap to `$COLUMNS`,
+//! falling back to the terminal width when stdout is a terminal.
+
+use crate::shell;
+use std::io::Write;
+
+const BOLD: &str = "1";
+const CYAN: &str = "36";
+
+/// Narrowest description column worth wrapping to; below it lines are left long.
+const MIN_WRAP: usize = 20;
+
+pub struct Style {
+    color: bool,
+    columns: Option<usize>,
+}
+
+impl Style {
+    /// Detect color support and the wrap width from the environment.
+    pub fn detect() -> Self {
+        let tty = crate::is::stdout_is_tty();
+        let set = |name: &str| shell::get_scalar(name).is_some_and(|v| !v.is_empty());
+        let forced = shell::get_scalar("FORCE_COLOR").is_some_and(|v| !matches!(v.as_str(), "" | "0"));
+        let dumb = shell::get_scalar("TERM").as_deref() == Some("dumb");
+        let color = !set("NO_COLOR") && (forced || (tty && !dumb));
+        let columns = shell::get_scalar("COLUMNS")
+            .and_then(|c| c.trim().parse().ok())
+            .or_else(|| if tty { terminal_width() } else { None });
+        Style { color, columns }
+    }
+
+    /// Section headings (`Usage:`, `Options:`, group titles).
+    pub fn heading(&self, text: &str) -> String {
+        self.paint(BOLD, text)
+    }
+
+    /// Command, flag and argument names.
+    pub fn name(&self, text: &str) -> String {
+        self.paint(CYAN, text)
+    }
+
+    /// `name` padded to `width` columns; padding is added outside the color.
+    pub fn name_padded(&self, text: &str, width: usize) -> String {
+        let pad = width.saturating_sub(text.chars().count());
+        format!("{}{}", self.name(text), " ".repeat(pad))
+    }
+
+    fn paint(&self, code: &str, text: &str) -> String {
+        if self.color && !text.is_empty() {
+            format!("\x1b[{}m{}\x1b[0m", code, text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// Write a description starting at column `indent`; the caller has
+    /// already written the text before it. Continuation lines are indented.
+    pub fn write_desc<W: Write>(&self, out: &mut W, desc: &str, indent: usize) {
+        let lines = match self.columns {
+            Some(columns) if columns >= indent + MIN_WRAP => wrap(desc, columns - indent),
+            _ => vec![desc.to_string()],
+        };
+        for (i, line) in lines.iter().enumerate() {
+            if i == 0 {
+                let _ = writeln!(out, "{}", line);
+            } else {
+                let _ = writeln!(out, "{:indent$}{}", "", line, indent = indent);
+            }
+        }
+    }
+}
+
+/// Terminal width of stdout, if it reports one.
+fn terminal_width() -> Option<usize> {
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    let ok = unsafe { libc::ioctl(1, libc::TIOCGWINSZ, &mut size) } == 0;
+    (ok && size.ws_col > 0).then_some(size.ws_col as usize)
+}
+
+/// Greedy word wrap to `width` columns. Existing line breaks are kept and a
+/// word longer than `width` gets a line of its own.
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        lines.push(line);
+    }
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+    lines
+}
+
+/// Print the `Examples:` block from the conventional `examples` array
+/// (pairs of command line and description). Command lines are shown after
+/// the command name; nothing is printed when the array is empty or belongs
+/// to a parent command.
+pub fn print_examples<W: Write>(out: &mut W, style: &Style, cmdname: &str) {
+    let examples = read_examples();
+    if examples.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "\n{}", style.heading("Examples:"));
+    for (command, desc) in &examples {
+        if !desc.is_empty() {
+            let _ = write!(out, "  # ");
+            style.write_desc(out, desc, 4);
+        }
+        let _ = writeln!(out, "  {}", style.name(&example_line(cmdname, command)));
+    }
+}
+
+/// `(command line, description)` pairs from the `examples` array declared
+/// in the calling function. A parent command's array is visible through
+/// dynamic scope but describes the parent, so it is ignored.
+pub fn read_examples() -> Vec<(String, String)> {
+    if !shell::is_caller_local("examples") {
+        return Vec::new();
+    }
+    let examples = shell::read_array("examples");
+    examples
+        .chunks(2)
+        .map(|pair| (pair[0].clone(), pair.get(1).cloned().unwrap_or_default()))
+        .collect()
+}
+
+/// An example command line prefixed with the command name.
+pub fn example_line(cmdname: &str, command: &str) -> String {
+    if command.is_empty() {
+        cmdname.to_string()
+    } else {
+        format!("{} {}", cmdname, command)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(wrap("one two three four", 9), vec!["one two", "three", "four"]);
+        assert_eq!(wrap("a reallylongword b", 6), vec!["a", "reallylongword", "b"]);
+        assert_eq!(wrap("first\nsecond line", 20), vec!["first", "second line"]);
+        assert_eq!(wrap("", 10), vec![""]);
+    }
+
+    #[test]
+    fn test_style_plain() {
+        let style = Style { color: false, columns: Some(30) };
+        assert_eq!(style.name_padded("run", 6), "run   ");
+        let mut out = Vec::new();
+        style.write_desc(&mut out, "Deploy the application to the selected environment", 10);
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Deploy the\n          application to the\n          selected environment\n"
+        );
+    }
+
+    #[test]
+    fn test_style_color() {
+        let style = Style { color: true, columns: None };
+        assert_eq!(style.heading("Options:"), "\x1b[1mOptions:\x1b[0m");
+        assert_eq!(style.name_padded("run", 5), "\x1b[36mrun\x1b[0m  ");
+        assert_eq!(style.name(""), "");
+    }
+}
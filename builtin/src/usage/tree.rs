@@ -0,0 +1,233 @@
+//! `help <command>` and `--help-all` -- the nested command tree rendered
+//! for the terminal, with the arguments and flags of every leaf command.
+//!
+//! Both are deferred to `:usage::help` like plain help, with the command
+//! path before a `--`: `:usage::help [command...] -- title usage_pairs...`.
+
+use crate::field;
+use crate::shared;
+use crate::shell;
+use std::io::Write;
+use super::style::{self, Style};
+use super::{build_command_tree, print_flags_section, CommandNode, FlagInfo, PositionalInfo};
+
+/// Position of the `--` that ends the command path of a tree call, or None
+/// for plain help (`title [usage_pairs...]`). The path never contains `--`,
+/// so only the first one counts; it must be followed by the title and whole
+/// usage pairs, which a `--` inside the usage pairs of plain help is not.
+#[allow(clippy::manual_is_multiple_of)] // Rust <1.87 compat
+pub(crate) fn separator(args: &[String]) -> Option<usize> {
+    let sep = args.iter().position(|s| s == "--")?;
+    ((args.len() - sep) % 2 == 0).then_some(sep)
+}
+
+/// Render the tree below `path` (the whole tree when `path` is empty).
+/// Args: [command...] -- title usage_pairs...
+pub fn help_tree_main(args: &[String], sep: usize) -> i32 {
+    let path = &args[..sep];
+    let meta = args.get(sep + 1..).unwrap_or_default();
+    let title = meta.first().map(|s| s.as_str()).unwrap_or("");
+    let usage_pairs = meta.get(1..).unwrap_or_default();
+    let args_arr = shell::read_array("args");
+
+    let caller = shell::get_funcname(0);
+    let tree = build_command_tree(usage_pairs, &args_arr, caller.as_deref());
+    let cmdname = shell::get_commandname().join(" ");
+    let fw = shell::get_field_width();
+    let style = Style::detect();
+
+    let out = std::io::stdout();
+    let mut out = out.lock();
+
+    if path.is_empty() {
+        for line in title.lines() {
+            let _ = writeln!(out, "{}", line.trim_start());
+        }
+        let _ = writeln!(out);
+        let _ = writeln!(out, "{} {} <command> [args]", style.heading("Usage:"), cmdname);
+        print_flags_section(&mut out, &args_arr, &style);
+        let _ = writeln!(out, "\n{}", style.heading("Commands:"));
+        write_nodes(&mut out, &style, fw, &cmdname, &tree);
+        return 0;
+    }
+
+    let node = match find_node(&tree, path) {
+        Ok(node) => node,
+        Err((name, siblings)) => {
+            let suggestion = shared::closest_match(name, visible(siblings).map(|n| n.name.as_str()));
+            let msg = match suggestion {
+                Some(ref suggestion) => format!("Invalid command: {}. Did you mean '{}'?", name, suggestion),
+                None => format!("Invalid command: {}", name),
+            };
+            return shared::error_suggest(shared::ErrorKind::UnknownCommand, "", name, &msg, suggestion.as_deref());
+        }
+    };
+
+    let _ = writeln!(out, "{}", node.desc);
+    let _ = writeln!(out);
+    let _ = writeln!(out, "{} {}", style.heading("Usage:"), synopsis(&cmdname, node));
+    if node.children.is_empty() {
+        let _ = writeln!(out);
+        write_leaf(&mut out, &style, fw, node);
+    } else {
+        let _ = writeln!(out, "\n{}", style.heading("Commands:"));
+        write_nodes(&mut out, &style, fw, &cmdname, &node.children);
+    }
+    0
+}
+
+/// Look up a command path in the tree. On a miss, returns the unknown name
+/// and the commands it was looked up among.
+fn find_node<'a>(
+    nodes: &'a [CommandNode],
+    path: &'a [String],
+) -> Result<&'a CommandNode, (&'a str, &'a [CommandNode])> {
+    let mut level = nodes;
+    let mut found = None;
+    for name in path {
+        match level.iter().find(|n| n.name == *name) {
+            Some(node) => {
+                found = Some(node);
+                level = &node.children;
+            }
+            None => return Err((name, level)),
+        }
+    }
+    found.ok_or(("", nodes))
+}
+
+/// Hidden and deprecated commands are left out of the tree.
+fn visible(nodes: &[CommandNode]) -> impl Iterator<Item = &CommandNode> {
+    nodes.iter().filter(|n| !n.hidden && n.deprecated().is_none())
+}
+
+/// Write each command with its full path and description, then its children.
+fn write_nodes<W: Write>(out: &mut W, style: &Style, fw: usize, cmdname: &str, nodes: &[CommandNode]) {
+    for node in visible(nodes) {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "{}", style.name(&synopsis(cmdname, node)));
+        if !node.desc.is_empty() {
+            let _ = write!(out, "  ");
+            style.write_desc(out, &node.desc, 2);
+        }
+        if node.children.is_empty() {
+            write_leaf(out, style, fw, node);
+        } else {
+            write_nodes(out, style, fw, cmdname, &node.children);
+        }
+    }
+}
+
+/// The arguments and flags of a leaf command.
+fn write_leaf<W: Write>(out: &mut W, style: &Style, fw: usize, node: &CommandNode) {
+    if !node.positionals.is_empty() {
+        let _ = writeln!(out, "  {}", style.heading("Arguments:"));
+        for pos in &node.positionals {
+            let label = format!("{} {}", pos.name, pos.type_name);
+            let _ = write!(out, "    {} ", style.name_padded(&label, fw));
+            style.write_desc(out, &pos.desc, 5 + fw.max(label.len()));
+        }
+    }
+    let flags: Vec<&FlagInfo> = node.flags.iter().filter(|f| f.deprecated.is_none()).collect();
+    if !flags.is_empty() {
+        let _ = writeln!(out, "  {}", style.heading("Options:"));
+        for flag in flags {
+            let label = flag_label(flag);
+            let _ = write!(out, "    {} ", style.name_padded(&label, fw));
+            style.write_desc(out, &flag.desc, 5 + fw.max(label.len()));
+        }
+    }
+}
+
+/// Full command line for a node: `<command>` for a parent, its positionals
+/// for a leaf.
+pub(crate) fn synopsis(cmdname: &str, node: &CommandNode) -> String {
+    let mut line = style::example_line(cmdname, &node.full_path.join(" "));
+    if !node.children.is_empty() {
+        line.push_str(" <command>");
+    }
+    for pos in &node.positionals {
+        line.push(' ');
+        line.push_str(&positional_param(pos));
+    }
+    line
+}
+
+pub(crate) fn positional_param(pos: &PositionalInfo) -> String {
+    match (pos.is_variadic, pos.required) {
+        (true, true) => format!("<{}>...", pos.name),
+        (true, false) => format!("...{}", pos.name),
+        (false, true) => format!("<{}>", pos.name),
+        (false, false) => format!("[{}]", pos.name),
+    }
+}
+
+/// `-s, --name type` as shown in the options list.
+fn flag_label(flag: &FlagInfo) -> String {
+    let mut label = field::flag_names(flag.short.as_deref(), &flag.name, flag.default_on);
+    if !flag.is_boolean {
+        label.push(' ');
+        label.push_str(&flag.value_label());
+    }
+    label
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(path: &[&str], children: Vec<CommandNode>) -> CommandNode {
+        CommandNode {
+            name: path.last().unwrap().to_string(),
+            desc: String::new(),
+            full_path: path.iter().map(|s| s.to_string()).collect(),
+            flags: Vec::new(),
+            positionals: Vec::new(),
+            children,
+            hidden: false,
+            annotations: Vec::new(),
+            func: None,
+        }
+    }
+
+    #[test]
+    fn test_find_node() {
+        let tree = vec![node(&["remote"], vec![node(&["remote", "add"], Vec::new())])];
+        let path = vec!["remote".to_string(), "add".to_string()];
+        assert_eq!(find_node(&tree, &path).ok().unwrap().full_path, path);
+
+        let path = vec!["remote".to_string(), "ad".to_string()];
+        let (name, siblings) = find_node(&tree, &path).err().unwrap();
+        assert_eq!(name, "ad");
+        assert_eq!(siblings[0].name, "add");
+    }
+
+    #[test]
+    fn test_separator() {
+        let args = |words: &[&str]| words.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(separator(&args(&["--", "Title"])), Some(0));
+        assert_eq!(separator(&args(&["remote", "--", "Title", "add", "Add"])), Some(1));
+        assert_eq!(separator(&args(&["Title", "add", "Add"])), None);
+        // `--` as a description in plain help is not a separator
+        assert_eq!(separator(&args(&["Title", "add", "--"])), None);
+        assert_eq!(separator(&args(&["Title", "add", "--", "rm", "Remove"])), None);
+    }
+
+    #[test]
+    fn test_synopsis() {
+        let mut leaf = node(&["remote", "add"], Vec::new());
+        for (name, required, is_variadic) in [("name", true, false), ("urls", false, true)] {
+            leaf.positionals.push(PositionalInfo {
+                name: name.to_string(),
+                desc: String::new(),
+                type_name: "string".to_string(),
+                choices: Vec::new(),
+                required,
+                is_variadic,
+            });
+        }
+        assert_eq!(synopsis("git", &leaf), "git remote add <name> ...urls");
+        let parent = node(&["remote"], vec![leaf]);
+        assert_eq!(synopsis("git", &parent), "git remote <command>");
+    }
+}
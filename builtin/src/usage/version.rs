@@ -0,0 +1,181 @@
+//! :usage::version builtin -- `version` subcommand and `--version` flag.
+//!
+//! The version comes from `ARGSH_APP_VERSION` (or `VERSION`) set by the
+//! script; the commit from `ARGSH_APP_COMMIT`, else from git when the
+//! script sets no version and lives in a git checkout.
+
+use crate::{word_list_to_vec, BashBuiltin, SyncPtr, WordList, BUILTIN_ENABLED};
+use crate::shared;
+use crate::shell;
+use std::ffi::{c_char, c_int};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+// -- :usage::version builtin registration -------------------------------------
+
+static USAGE_VERSION_LONG_DOC: [SyncPtr; 2] = [
+    SyncPtr(c"Print the script version (text or JSON).".as_ptr()),
+    SyncPtr(std::ptr::null()),
+];
+
+/// Print the script version, its commit and the argsh version.
+#[export_name = ":usage::version_struct"]
+pub static mut USAGE_VERSION_STRUCT: BashBuiltin = BashBuiltin {
+    name: c":usage::version".as_ptr(),
+    function: usage_version_builtin_fn,
+    flags: BUILTIN_ENABLED,
+    short_doc: c":usage::version [--json] [-- title usage_pairs...]".as_ptr(),
+    long_doc: USAGE_VERSION_LONG_DOC.as_ptr().cast(),
+    handle: std::ptr::null(),
+};
+
+#[export_name = ":usage::version_builtin_load"]
+pub extern "C" fn usage_version_builtin_load(_name: *const c_char) -> c_int {
+    1 // success
+}
+
+#[export_name = ":usage::version_builtin_unload"]
+pub extern "C" fn usage_version_builtin_unload(_name: *const c_char) {} // coverage:off - bash internal callback
+
+extern "C" fn usage_version_builtin_fn(word_list: *const WordList) -> c_int {
+    let code = std::panic::catch_unwind(|| {
+        let args = word_list_to_vec(word_list);
+        usage_version_main(&args)
+    })
+    .unwrap_or(1); // coverage:off - catch_unwind: panics don't occur in practice
+
+    std::process::exit(if code == shared::HELP_EXIT || code == 0 { 0 } else { code }) // coverage:off
+}
+
+// -- :usage::version implementation -------------------------------------------
+
+/// Main entry point for :usage::version builtin.
+/// Called via "${usage[@]}" for `version` and `--version`.
+/// Args: [--json] [-- title original_usage_pairs...]
+pub fn usage_version_main(args: &[String]) -> i32 {
+    let sep = args.iter().position(|s| s == "--").unwrap_or(args.len());
+    let commandname = shell::get_commandname();
+    let cmd_name = commandname.first().cloned().unwrap_or_else(shell::get_script_name);
+
+    let mut json = false;
+    for arg in &args[..sep] {
+        match arg.as_str() {
+            "--json" => json = true,
+            "-h" | "--help" => {
+                println!("Print version information.\n");
+                println!("Usage: {} version [--json]\n", cmd_name);
+                println!("Options:");
+                println!("  --json  Print a JSON object");
+                return shared::HELP_EXIT;
+            }
+            _ => {
+                return shared::error_kind(
+                    shared::ErrorKind::UnknownFlag,
+                    "",
+                    arg,
+                    &format!("unknown flag: {}", arg),
+                );
+            }
+        }
+    }
+
+    let info = VersionInfo::detect(&cmd_name);
+    if json {
+        println!("{}", info.to_json());
+    } else {
+        print!("{}", info.to_text());
+    }
+    0
+}
+
+/// Version data shared by `version` and the MCP `script:///version` resource.
+#[derive(serde::Serialize)]
+pub struct VersionInfo {
+    pub name: String,
+    pub version: Option<String>,
+    pub commit: Option<String>,
+    pub argsh: Option<String>,
+}
+
+impl VersionInfo {
+    /// Read the version variables of the running script. git is only asked
+    /// for the commit of a script that sets no version of its own.
+    pub fn detect(name: &str) -> Self {
+        let var = |name: &str| shell::get_scalar(name).filter(|v| !v.is_empty() && v != "unknown");
+        let version = var("ARGSH_APP_VERSION").or_else(|| var("VERSION"));
+        let commit = var("ARGSH_APP_COMMIT").or_else(|| {
+            let script = shell::get_scalar("ARGSH_SOURCE")
+                .filter(|s| !s.is_empty())
+                .unwrap_or_else(shell::get_script_path);
+            version.is_none().then(|| git_commit(&script)).flatten()
+        });
+        VersionInfo {
+            name: name.to_string(),
+            version,
+            commit,
+            argsh: var("ARGSH_VERSION"),
+        }
+    }
+
+    /// `name version (commit)` followed by the argsh version.
+    pub fn to_text(&self) -> String {
+        let mut text = format!("{} {}", self.name, self.version.as_deref().unwrap_or("unknown"));
+        if let Some(ref commit) = self.commit {
+            text.push_str(&format!(" ({})", commit));
+        }
+        text.push('\n');
+        if let Some(ref argsh) = self.argsh {
+            text.push_str(&format!("argsh {}\n", argsh));
+        }
+        text
+    }
+
+    /// `{"name":…,"version":…,"commit":…,"argsh":…}`; unknown values are null.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// Short commit hash of the git checkout containing `script`, if any.
+/// A bare script name (found on `PATH`) has no directory to look in, so
+/// no commit is reported rather than guessing from the working directory.
+fn git_commit(script: &str) -> Option<String> {
+    let dir = Path::new(script).parent().filter(|d| !d.as_os_str().is_empty())?;
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["rev-parse", "--short", "HEAD"])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    let commit = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !commit.is_empty()).then_some(commit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_info_output() {
+        let info = VersionInfo {
+            name: "mytool".to_string(),
+            version: Some("1.2.0".to_string()),
+            commit: Some("abc1234".to_string()),
+            argsh: Some("0.6.0".to_string()),
+        };
+        assert_eq!(info.to_text(), "mytool 1.2.0 (abc1234)\nargsh 0.6.0\n");
+        assert_eq!(
+            info.to_json(),
+            r#"{"name":"mytool","version":"1.2.0","commit":"abc1234","argsh":"0.6.0"}"#
+        );
+
+        let bare = VersionInfo { name: "mytool".to_string(), version: None, commit: None, argsh: None };
+        assert_eq!(bare.to_text(), "mytool unknown\n");
+        assert_eq!(bare.to_json(), r#"{"name":"mytool","version":null,"commit":null,"argsh":null}"#);
+
+        let quoted = VersionInfo { name: "my \"tool\"".to_string(), version: None, commit: None, argsh: None };
+        assert_eq!(quoted.to_json(), r#"{"name":"my \"tool\"","version":null,"commit":null,"argsh":null}"#);
+    }
+}
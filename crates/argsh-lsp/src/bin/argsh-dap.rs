@@ -461,7 +461,7 @@ impl DapSession {
                     Ok(f) => {
                         let mut t = String::new();
                         if is_flag { t.push_str("flag"); } else { t.push_str("positional"); }
-                        if f.is_boolean { t.push_str(" :+"); }
+                        if f.is_counter { t.push_str(" :++"); } else if f.is_boolean { t.push_str(" :+"); }
                         if f.required { t.push_str(" :!"); }
                         if !f.type_name.is_empty() { t.push_str(&format!(" :~{}", f.type_name)); }
                         t
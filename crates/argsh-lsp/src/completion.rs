@@ -238,6 +238,13 @@ fn complete_args_modifiers() -> Vec<CompletionItem> {
             insert_text: Some("+".to_string()),
             ..Default::default()
         },
+        CompletionItem {
+            label: ":++".to_string(),
+            kind: Some(CompletionItemKind::KEYWORD),
+            detail: Some("Counter flag (repeatable, counts occurrences)".to_string()),
+            insert_text: Some("++".to_string()),
+            ..Default::default()
+        },
         CompletionItem {
             label: ":~int".to_string(),
             kind: Some(CompletionItemKind::TYPE_PARAMETER),
@@ -280,6 +287,14 @@ fn complete_args_modifiers() -> Vec<CompletionItem> {
             insert_text: Some("~stdin".to_string()),
             ..Default::default()
         },
+        CompletionItem {
+            label: ":~enum".to_string(),
+            kind: Some(CompletionItemKind::TYPE_PARAMETER),
+            detail: Some("One of a fixed set of choices".to_string()),
+            insert_text: Some("~enum(${1:choices})".to_string()),
+            insert_text_format: Some(InsertTextFormat::SNIPPET),
+            ..Default::default()
+        },
         CompletionItem {
             label: ":!".to_string(),
             kind: Some(CompletionItemKind::KEYWORD),
@@ -287,6 +302,13 @@ fn complete_args_modifiers() -> Vec<CompletionItem> {
             insert_text: Some("!".to_string()),
             ..Default::default()
         },
+        CompletionItem {
+            label: ":?".to_string(),
+            kind: Some(CompletionItemKind::KEYWORD),
+            detail: Some("Optional positional".to_string()),
+            insert_text: Some("?".to_string()),
+            ..Default::default()
+        },
         CompletionItem {
             label: ":#".to_string(),
             kind: Some(CompletionItemKind::KEYWORD),
@@ -301,6 +323,38 @@ fn complete_args_modifiers() -> Vec<CompletionItem> {
             insert_text: Some("^".to_string()),
             ..Default::default()
         },
+        CompletionItem {
+            label: ":$".to_string(),
+            kind: Some(CompletionItemKind::KEYWORD),
+            detail: Some("Fall back to an environment variable when the flag is absent".to_string()),
+            insert_text: Some("\\$${1:VAR}".to_string()),
+            insert_text_format: Some(InsertTextFormat::SNIPPET),
+            ..Default::default()
+        },
+        CompletionItem {
+            label: ":%".to_string(),
+            kind: Some(CompletionItemKind::KEYWORD),
+            detail: Some("Mutually exclusive flag group".to_string()),
+            insert_text: Some("%${1:group}".to_string()),
+            insert_text_format: Some(InsertTextFormat::SNIPPET),
+            ..Default::default()
+        },
+        CompletionItem {
+            label: ":&".to_string(),
+            kind: Some(CompletionItemKind::KEYWORD),
+            detail: Some("Requires another flag".to_string()),
+            insert_text: Some("&${1:flag}".to_string()),
+            insert_text_format: Some(InsertTextFormat::SNIPPET),
+            ..Default::default()
+        },
+        CompletionItem {
+            label: ":@deprecated".to_string(),
+            kind: Some(CompletionItemKind::KEYWORD),
+            detail: Some("Deprecated flag (still accepted, warns, hidden from help)".to_string()),
+            insert_text: Some("@deprecated=${1:new-name}".to_string()),
+            insert_text_format: Some(InsertTextFormat::SNIPPET),
+            ..Default::default()
+        },
     ]
 }
 
@@ -312,6 +366,21 @@ fn complete_args_types(analysis: &DocumentAnalysis, content: &str) -> Vec<Comple
         make_type_item("boolean", "Boolean type"),
         make_type_item("string", "String type"),
         make_type_item("stdin", "Read from stdin"),
+        make_type_item("duration", "Duration (90s, 5m, 1h30m) converted to seconds"),
+        make_type_item("size", "Size (10KB, 10MiB) converted to bytes"),
+        make_type_item("url", "URL with scheme and host"),
+        make_type_item("dir", "Existing directory"),
+        make_type_item("path", "Normalized path; path(new) must not exist"),
+        make_type_item("json", "Well-formed JSON"),
+        make_type_item("config", "Config file that supplies defaults for the other flags"),
+        CompletionItem {
+            label: "enum".to_string(),
+            kind: Some(CompletionItemKind::TYPE_PARAMETER),
+            detail: Some("One of a fixed set of choices: enum(a,b,c)".to_string()),
+            insert_text: Some("enum(${1:choices})".to_string()),
+            insert_text_format: Some(InsertTextFormat::SNIPPET),
+            ..Default::default()
+        },
     ];
 
     // Add custom `to::` function names found in the file as custom type validators.
@@ -24,6 +24,9 @@ pub mod codes {
     pub const AG013: &str = "AG013"; // import could not be resolved
     pub const AG014: &str = "AG014"; // :^ field without ${var:-...} default pattern
     pub const AG015: &str = "AG015"; // # argsh source= path does not exist or is not a directory
+    pub const AG016: &str = "AG016"; // local default violates the field's type or constraints
+    pub const AG017: &str = "AG017"; // :&flag references a flag not in the args array
+    pub const AG018: &str = "AG018"; // call uses a @deprecated command or flag
 }
 
 /// Generate LSP diagnostics from a document analysis.
@@ -52,6 +55,9 @@ pub fn generate_diagnostics(
         check_bare_function_resolution(func, analysis, imports, &mut diags);
         check_scope_shadow(func, analysis, content, &mut diags);
         check_inherited_without_default(func, &mut diags);
+        check_invalid_defaults(func, &mut diags);
+        check_unknown_required_flags(func, &mut diags);
+        check_deprecated_calls(func, content, &mut diags);
     }
 
     // Check unresolved imports (only when resolution actually ran — skip if resolveDepth=0)
@@ -645,6 +651,122 @@ fn check_inherited_without_default(func: &FunctionInfo, diags: &mut Vec<Diagnost
     }
 }
 
+/// Warn when a literal `local` default would be rejected by the field's type,
+/// choices, range or pattern. Defaults bypass validation at runtime, so a bad
+/// one only shows up as a wrong value. Expansions and empty strings are skipped.
+fn check_invalid_defaults(func: &FunctionInfo, diags: &mut Vec<Diagnostic>) {
+    for entry in &func.args_entries {
+        if entry.spec == "-" { continue; }
+        let Ok(ref field) = entry.parsed else { continue };
+        if field.is_boolean && !field.is_counter { continue; }
+        let Some(local_var) = func.local_vars.iter().find(|v| v.name == field.name) else { continue };
+        if local_var.is_array { continue; }
+        let Some(ref raw) = local_var.default_value else { continue };
+        let value = unquote(raw.trim());
+        if value.is_empty() || value.contains(['$', '`']) { continue; }
+        if let Err(err) = field.check_value(value) {
+            diags.push(make_diag(
+                line_range(local_var.line),
+                DiagnosticSeverity::WARNING,
+                codes::AG016,
+                format!("default for '{}' is invalid: {}", field.display_name, err),
+            ));
+        }
+    }
+}
+
+/// Warn when a `:&flag` modifier names a flag that is not declared in the
+/// same args array; the builtin rejects the command line at runtime.
+fn check_unknown_required_flags(func: &FunctionInfo, diags: &mut Vec<Diagnostic>) {
+    let flag_names: HashSet<&str> = func.args_entries.iter()
+        .filter_map(|e| e.parsed.as_ref().ok())
+        .filter(|f| !f.is_positional)
+        .map(|f| f.name.as_str())
+        .collect();
+    for entry in &func.args_entries {
+        let Ok(ref field) = entry.parsed else { continue };
+        for other in &field.requires {
+            if !flag_names.contains(argsh_syntax::field::field_name(other, true).as_str()) {
+                diags.push(make_diag(
+                    line_range(entry.line),
+                    DiagnosticSeverity::WARNING,
+                    codes::AG017,
+                    format!("'{}' requires unknown flag '{}'", field.display_name, other),
+                ));
+            }
+        }
+    }
+}
+
+/// Flag calls like `func old-cmd` or `func --old-flag` that use a
+/// `@deprecated` usage entry or args field of `func`; they still work but
+/// print a warning at runtime. Matching is per line and word-based.
+fn check_deprecated_calls(func: &FunctionInfo, content: &str, diags: &mut Vec<Diagnostic>) {
+    let commands: Vec<(&str, String)> = func.usage_entries.iter()
+        .filter(|e| !e.is_group_separator)
+        .filter_map(|e| e.deprecated().map(|r| (e, r)))
+        .flat_map(|(e, r)| e.aliases.iter().map(move |a| (a.as_str(), r.clone())))
+        .collect();
+    let flags: Vec<(String, String)> = func.args_entries.iter()
+        .filter_map(|e| e.parsed.as_ref().ok())
+        .filter_map(|f| {
+            let replacement = f.deprecated.as_ref()?;
+            let mut names = vec![format!("--{}", f.display_name)];
+            names.extend(f.short.iter().map(|s| format!("-{}", s)));
+            Some(names.into_iter().map(move |n| (n, replacement.clone())))
+        })
+        .flatten()
+        .collect();
+    if commands.is_empty() && flags.is_empty() {
+        return;
+    }
+
+    for (i, line) in content.lines().enumerate() {
+        if line.trim_start().starts_with('#') {
+            continue;
+        }
+        let words: Vec<(usize, &str)> = line
+            .split_whitespace()
+            .map(|w| (w.as_ptr() as usize - line.as_ptr() as usize, w))
+            .collect();
+        let Some(at) = words.iter().position(|&(_, w)| w == func.name) else { continue };
+        for (pos, (col, word)) in words.iter().enumerate().skip(at + 1) {
+            let name = word.split('=').next().unwrap_or(word);
+            let hit = if pos == at + 1 && !word.starts_with('-') {
+                commands.iter().find(|(c, _)| c == word).map(|(c, r)| (c.to_string(), r, ""))
+            } else {
+                flags.iter().find(|(f, _)| f == name).map(|(f, r)| (f.clone(), r, "--"))
+            };
+            let Some((used, replacement, prefix)) = hit else { continue };
+            let message = if replacement.is_empty() {
+                format!("'{}' is deprecated", used)
+            } else {
+                format!("'{}' is deprecated, use '{}{}' instead", used, prefix, replacement)
+            };
+            diags.push(make_diag_tagged(
+                Range {
+                    start: Position { line: i as u32, character: *col as u32 },
+                    end: Position { line: i as u32, character: (*col + name.len()) as u32 },
+                },
+                DiagnosticSeverity::WARNING,
+                codes::AG018,
+                message,
+                vec![DiagnosticTag::DEPRECATED],
+            ));
+        }
+    }
+}
+
+/// Strip one pair of matching surrounding quotes.
+fn unquote(s: &str) -> &str {
+    for q in ['"', '\''] {
+        if let Some(inner) = s.strip_prefix(q).and_then(|r| r.strip_suffix(q)) {
+            return inner;
+        }
+    }
+    s
+}
+
 fn line_range(line: usize) -> Range {
     Range {
         start: Position { line: line as u32, character: 0 },
@@ -817,4 +939,84 @@ f() {
         assert!(ag013_a.is_empty(),
             "With document_path=file_a, AG013 should not fire because helpers was resolved from file_a");
     }
+
+    fn ag016(content: &str) -> Vec<Diagnostic> {
+        let analysis = analyze(content);
+        generate_diagnostics(&analysis, &empty_imports(), content, None)
+            .into_iter()
+            .filter(|d| d.code == Some(NumberOrString::String("AG016".to_string())))
+            .collect()
+    }
+
+    #[test]
+    fn test_ag016_fires_for_out_of_range_default() {
+        let content = "#!/usr/bin/env bash\nsource argsh\nf() {\n  local port=70000\n  local -a args=(\n    'port|p:~int[1..65535]' \"Port\"\n  )\n  :args \"Test\" \"${@}\"\n}\n";
+        let diags = ag016(content);
+        assert_eq!(diags.len(), 1, "Expected AG016 for out-of-range default, got: {:?}", diags);
+        assert_eq!(diags[0].range.start.line, 3);
+        assert!(diags[0].message.contains("out of range (1..65535): 70000"));
+    }
+
+    #[test]
+    fn test_ag016_fires_for_pattern_mismatch() {
+        let content = "#!/usr/bin/env bash\nsource argsh\nf() {\n  local tag=\"1.0\"\n  local -a args=(\n    'tag|t:~string/^v[0-9]/' \"Tag\"\n  )\n  :args \"Test\" \"${@}\"\n}\n";
+        let diags = ag016(content);
+        assert_eq!(diags.len(), 1, "Expected AG016 for pattern mismatch, got: {:?}", diags);
+        assert!(diags[0].message.contains("does not match pattern"));
+    }
+
+    #[test]
+    fn test_ag016_not_fired_for_valid_or_dynamic_defaults() {
+        let content = "#!/usr/bin/env bash\nsource argsh\nf() {\n  local port=8080 env=\"${ENV:-x}\" tag=''\n  local -a args=(\n    'port|p:~int[1..65535]' \"Port\"\n    'env|e:~enum(dev,prod)' \"Env\"\n    'tag|t:~string/^v/' \"Tag\"\n  )\n  :args \"Test\" \"${@}\"\n}\n";
+        let diags = ag016(content);
+        assert!(diags.is_empty(), "Expected no AG016, got: {:?}", diags);
+    }
+
+    #[test]
+    fn test_ag016_fires_for_non_numeric_counter_default() {
+        let content = "#!/usr/bin/env bash\nsource argsh\nf() {\n  local verbose=yes quiet=0\n  local -a args=(\n    'verbose|v:++' \"Verbosity\"\n    'quiet|q:++' \"Quietness\"\n  )\n  :args \"Test\" \"${@}\"\n}\n";
+        let diags = ag016(content);
+        assert_eq!(diags.len(), 1, "Expected AG016 for counter default, got: {:?}", diags);
+        assert!(diags[0].message.contains("invalid count: yes"));
+    }
+
+    #[test]
+    fn test_ag017_fires_for_unknown_required_flag() {
+        let content = "#!/usr/bin/env bash\nsource argsh\nf() {\n  local tls_cert tls_key\n  local -a args=(\n    'tls-cert|:&tls-ky' \"Cert\"\n    'tls-key|' \"Key\"\n  )\n  :args \"Test\" \"${@}\"\n}\n";
+        let analysis = analyze(content);
+        let diags: Vec<_> = generate_diagnostics(&analysis, &empty_imports(), content, None)
+            .into_iter()
+            .filter(|d| d.code == Some(NumberOrString::String("AG017".to_string())))
+            .collect();
+        assert_eq!(diags.len(), 1, "Expected AG017, got: {:?}", diags);
+        assert_eq!(diags[0].range.start.line, 5);
+        assert!(diags[0].message.contains("unknown flag 'tls-ky'"));
+    }
+
+    #[test]
+    fn test_ag017_not_fired_for_known_flag() {
+        let content = "#!/usr/bin/env bash\nsource argsh\nf() {\n  local tls_cert tls_key\n  local -a args=(\n    'tls-cert|:&tls-key' \"Cert\"\n    'tls-key|:&tls_cert' \"Key\"\n  )\n  :args \"Test\" \"${@}\"\n}\n";
+        let analysis = analyze(content);
+        let diags: Vec<_> = generate_diagnostics(&analysis, &empty_imports(), content, None)
+            .into_iter()
+            .filter(|d| d.code == Some(NumberOrString::String("AG017".to_string())))
+            .collect();
+        assert!(diags.is_empty(), "Expected no AG017, got: {:?}", diags);
+    }
+
+    #[test]
+    fn test_ag018_fires_for_deprecated_command_and_flag() {
+        let content = "#!/usr/bin/env bash\nsource argsh\nmain() {\n  local dry_run\n  local -a args=(\n    'dry-run|n:+' \"Dry run\"\n    'dry|:+@deprecated=dry-run' \"Old name\"\n  )\n  local -a usage=(\n    'release' \"Release\"\n    'deploy@deprecated=release:-main::release' \"Deploy\"\n  )\n  :usage \"Test\" \"${@}\"\n}\nmain deploy --dry\nmain release --dry-run\n";
+        let analysis = analyze(content);
+        let diags: Vec<_> = generate_diagnostics(&analysis, &empty_imports(), content, None)
+            .into_iter()
+            .filter(|d| d.code == Some(NumberOrString::String("AG018".to_string())))
+            .collect();
+        assert_eq!(diags.len(), 2, "Expected AG018 for deploy and --dry, got: {:?}", diags);
+        assert_eq!(diags[0].range.start, Position { line: 14, character: 5 });
+        assert!(diags[0].message.contains("'deploy' is deprecated, use 'release' instead"));
+        assert_eq!(diags[1].range.start, Position { line: 14, character: 12 });
+        assert!(diags[1].message.contains("'--dry' is deprecated, use '--dry-run' instead"));
+        assert_eq!(diags[1].tags, Some(vec![DiagnosticTag::DEPRECATED]));
+    }
 }
@@ -8,11 +8,15 @@ use argsh_lsp::resolver::ResolvedImports;
 use crate::util::extract_word_at;
 
 /// Format a type string, appending `[]` when the field backs an array variable.
+/// Enum fields show their choices (`{a|b|c}`) and ranged numbers their
+/// bounds (`int[1..10]`) like the runtime help does. Counters hold a number.
 fn format_type(field: &FieldDef, is_array: bool) -> String {
-    let base = if field.is_boolean {
+    let base = if field.is_counter {
+        "int".to_string()
+    } else if field.is_boolean {
         "boolean".to_string()
     } else {
-        field.type_name.clone()
+        field.type_label()
     };
     if is_array {
         format!("{}[]", base)
@@ -155,14 +159,23 @@ fn hover_modifier(
         None
     };
 
+    let next = line.as_bytes().get(col + 1).copied().map(|b| b as char);
+    let is_counter = ch == '+' && (prev == Some('+') || next == Some('+'));
+
     let doc = match (prev, ch) {
+        (_, '+') if is_counter && is_after_colon_in_spec(line, col) => {
+            Some(("`:++` Counter flag", "Flag takes no value and may be repeated. Variable holds the number of occurrences (`-vvv` sets it to `3`); `--no-flag` resets it to `0`."))
+        }
         (Some(':'), '+') | (_, '+') if is_after_colon_in_spec(line, col) => {
             Some(("`:+` Boolean flag", "Flag takes no value. Variable is set to `true` when the flag is present, empty otherwise."))
         }
         (Some(':'), '~') => {
             // Extract the type name after :~
             let type_name = extract_type_after_tilde(line, col);
-            let builtin_types = ["int", "float", "file", "boolean", "string", "stdin"];
+            let builtin_types = [
+                "int", "float", "file", "boolean", "string", "stdin", "enum",
+                "duration", "size", "url", "dir", "path", "json", "config",
+            ];
             if let Some(ref tname) = type_name {
                 if builtin_types.contains(&tname.as_str()) {
                     let desc = match tname.as_str() {
@@ -172,6 +185,14 @@ fn hover_modifier(
                         "boolean" => "Converts to boolean (0 or 1)",
                         "string" => "Identity conversion (any string accepted)",
                         "stdin" => "Reads value from stdin if not provided",
+                        "enum" => "Validates that the value is one of the listed choices, e.g. `enum(dev,staging,prod)`",
+                        "duration" => "Converts a duration (`90s`, `5m`, `1h30m`) to seconds",
+                        "size" => "Converts a size (`10KB`, `10MiB`) to bytes",
+                        "url" => "Validates a `scheme://host[:port]` URL",
+                        "dir" => "Validates that the directory exists",
+                        "path" => "Normalizes the path; `path(new)` also requires that it does not exist",
+                        "json" => "Validates that the value is well-formed JSON",
+                        "config" => "Reads flag defaults from this file (`key = value` or JSON); command-line flags and `:$VAR` take precedence",
                         _ => "",
                     };
                     return Some(Hover {
@@ -199,14 +220,26 @@ fn hover_modifier(
                     });
                 }
             }
-            Some(("`:~type` Typed parameter", "Specifies a type validator. Built-in types: `int`, `float`, `file`, `boolean`, `string`, `stdin`. Custom types use `to::name` functions."))
+            Some(("`:~type` Typed parameter", "Specifies a type validator. Built-in types: `int`, `float`, `file`, `boolean`, `string`, `stdin`, `enum(a,b,...)`, `duration`, `size`, `url`, `dir`, `path`, `json`. Custom types use `to::name` functions."))
         }
         (Some(':'), '!') | (_, '!') if is_after_colon_in_spec(line, col) => {
             Some(("`:!` Required field", "The argument must be provided. An error is raised if it is missing."))
         }
+        (Some(':'), '?') | (_, '?') if is_after_colon_in_spec(line, col) => {
+            Some(("`:?` Optional positional", "The positional may be omitted even without a default value. Not allowed on flags or together with `:!`."))
+        }
         (Some(':'), '#') | (_, '#') if is_after_colon_in_spec(line, col) => {
             Some(("`:#` Hidden field", "The field is hidden from help text output but still functional."))
         }
+        (Some(':'), '$') | (_, '$') if is_after_colon_in_spec(line, col) => {
+            Some(("`:$VAR` Environment fallback", "When the flag is not given on the command line, its value is read from the environment variable `VAR` (validated like a command-line value). A set variable satisfies `:!`."))
+        }
+        (Some(':'), '%') | (_, '%') if is_after_colon_in_spec(line, col) => {
+            Some(("`:%group` Exclusive group", "At most one flag of the group may be given. If any member is also `:!`, exactly one is required."))
+        }
+        (Some(':'), '&') | (_, '&') if is_after_colon_in_spec(line, col) => {
+            Some(("`:&flag` Requires flag", "This flag may only be given together with `--flag`. Repeat the modifier to require several flags."))
+        }
         (Some(':'), '^') | (_, '^') if is_after_colon_in_spec(line, col) => {
             Some(("`:^` Inherited field", "This field yields to non-`:^` duplicates with the same name. When combined with `\"${args[@]:-}\"` expansion, the last non-`:^` entry wins."))
         }
@@ -292,6 +325,10 @@ fn hover_annotation(line: &str, col: usize) -> Option<Hover> {
             "@idempotent",
             "MCP idempotentHint: Repeated calls with the same arguments have the same effect as a single call.",
         )),
+        "deprecated" => Some((
+            "@deprecated",
+            "Deprecated command or flag: still works but prints a warning on stderr, naming the replacement given as `@deprecated=new-name`. Hidden from help, completion and tool schemas.",
+        )),
         "openworld" => Some((
             "@openworld",
             "MCP openWorldHint: This command interacts with external entities (network, third-party APIs).",
@@ -349,7 +386,12 @@ fn render_help_preview(func: &FunctionInfo, _analysis: &DocumentAnalysis) -> Str
             }
             if let Ok(ref field) = entry.parsed {
                 if field.is_positional {
-                    if field.required {
+                    // Variadic positionals render like the runtime help
+                    if entry.is_array && field.required {
+                        synopsis.push_str(&format!(" <{}>...", field.display_name));
+                    } else if entry.is_array {
+                        synopsis.push_str(&format!(" ...{}", field.display_name));
+                    } else if field.required {
                         synopsis.push_str(&format!(" <{}>", field.display_name));
                     } else {
                         synopsis.push_str(&format!(" [{}]", field.display_name));
@@ -406,7 +448,7 @@ fn render_help_preview(func: &FunctionInfo, _analysis: &DocumentAnalysis) -> Str
             md.push_str("\n**Options:**\n\n");
             for entry in &flags {
                 if let Ok(ref field) = entry.parsed {
-                    let type_str = if field.is_boolean && !entry.is_array {
+                    let type_str = if field.is_boolean && !field.is_counter && !entry.is_array {
                         String::new()
                     } else {
                         format!(" {}", format_type(field, entry.is_array))
@@ -499,6 +541,18 @@ fn hover_array_overview(
                     if field.is_inherited {
                         desc.push_str(" *(inherited)*");
                     }
+                    if let Some(ref var) = field.env_var {
+                        desc.push_str(&format!(" *(env: `{}`)*", var));
+                    }
+                    if let Some(ref group) = field.group {
+                        desc.push_str(&format!(" *(group: `{}`)*", group));
+                    }
+                    for other in &field.requires {
+                        desc.push_str(&format!(" *(requires: `--{}`)*", other));
+                    }
+                    if field.deprecated.is_some() {
+                        desc.push_str(" *(deprecated)*");
+                    }
 
                     md.push_str(&format!("| {} | {} | {} |\n", flag_str, type_str, desc));
                 } else {
@@ -597,11 +651,11 @@ fn hover_args_entry(
         .iter()
         .find(|e| e.spec == word || e.spec.starts_with(&format!("{}|", word)) || e.spec.starts_with(&format!("{}:", word)))?;
 
-    Some(render_args_entry_detail(entry))
+    Some(render_args_entry_detail(entry, func))
 }
 
-/// Render hover markdown for a single args entry.
-fn render_args_entry_detail(entry: &ArgsArrayEntry) -> Hover {
+/// Render hover markdown for a single args entry of `func`.
+fn render_args_entry_detail(entry: &ArgsArrayEntry, func: &FunctionInfo) -> Hover {
     let field = match entry.parsed.as_ref() {
         Ok(f) => f,
         Err(e) => {
@@ -634,12 +688,39 @@ fn render_args_entry_detail(entry: &ArgsArrayEntry) -> Hover {
         "\n*Required: {}*",
         if field.required { "yes" } else { "no" }
     ));
+    // Like the help text, only a boolean that defaults to on shows `--no-`
+    let default_on = func.local_vars.iter().any(|v| {
+        v.name == field.name
+            && !v.is_array
+            && v.default_value.as_deref().is_some_and(|d| !matches!(d.trim_matches(['\'', '"']), "" | "0"))
+    });
+    if field.is_boolean && !field.is_positional && default_on {
+        md.push_str(&format!("\n*Negated form: `--no-{}`*", field.display_name));
+    }
+    if field.is_counter {
+        md.push_str("\n*Repeatable: yes (counts occurrences)*");
+    }
     if field.hidden {
         md.push_str("\n*Hidden: yes*");
     }
     if field.is_inherited {
         md.push_str("\n*Inherited: yields to non-`:^` duplicates*");
     }
+    if let Some(ref var) = field.env_var {
+        md.push_str(&format!("\n*Environment: `${}`*", var));
+    }
+    if let Some(ref group) = field.group {
+        md.push_str(&format!("\n*Exclusive group: `{}`*", group));
+    }
+    if !field.requires.is_empty() {
+        let others: Vec<String> = field.requires.iter().map(|r| format!("`--{}`", r)).collect();
+        md.push_str(&format!("\n*Requires: {}*", others.join(", ")));
+    }
+    match field.deprecated.as_deref() {
+        Some("") => md.push_str("\n*Deprecated*"),
+        Some(replacement) => md.push_str(&format!("\n*Deprecated: use `--{}`*", replacement)),
+        None => {}
+    }
 
     Hover {
         contents: HoverContents::Markup(MarkupContent {
@@ -768,7 +849,7 @@ fn hover_inside_array(
             if entry.parsed.is_err() {
                 continue;
             }
-            return Some(render_args_entry_detail(entry));
+            return Some(render_args_entry_detail(entry, func));
         }
     }
 
@@ -3,11 +3,13 @@
 use std::collections::HashMap;
 
 use argsh_syntax::document::DocumentAnalysis;
-use argsh_syntax::field::FieldDef;
+use argsh_syntax::field::{field_name, FieldDef};
 
 /// Format a type string, appending `[]` when the field backs an array variable.
 fn format_type(field: &FieldDef, is_array: bool) -> String {
-    let base = if field.is_boolean {
+    let base = if field.is_counter {
+        "int".to_string()
+    } else if field.is_boolean {
         "boolean".to_string()
     } else {
         field.type_name.clone()
@@ -435,13 +437,16 @@ fn build_mcp_tools(analysis: &DocumentAnalysis, script_name: &str) -> String {
 
         let mut properties = serde_json::Map::new();
         let mut required_list = Vec::new();
+        let mut fields: Vec<&FieldDef> = Vec::new();
 
         for entry in &func.args_entries {
             if entry.spec == "-" {
                 continue;
             }
             if let Ok(ref field) = entry.parsed {
-                let json_type = if field.is_boolean {
+                let json_type = if field.is_counter {
+                    "integer"
+                } else if field.is_boolean {
                     "boolean"
                 } else {
                     match field.type_name.as_str() {
@@ -452,11 +457,57 @@ fn build_mcp_tools(analysis: &DocumentAnalysis, script_name: &str) -> String {
                 };
                 let mut prop = serde_json::Map::new();
                 prop.insert("type".to_string(), serde_json::Value::String(json_type.to_string()));
-                prop.insert("description".to_string(), serde_json::Value::String(entry.description.clone()));
+                let description = match field.env_var {
+                    Some(ref var) => format!("{} (env: {})", entry.description, var),
+                    None => entry.description.clone(),
+                };
+                prop.insert("description".to_string(), serde_json::Value::String(description));
+                if !field.choices.is_empty() {
+                    prop.insert("enum".to_string(), serde_json::json!(field.choices));
+                }
+                if field.is_counter {
+                    prop.insert("minimum".to_string(), serde_json::json!(0));
+                }
+                for (key, bound) in [("minimum", &field.minimum), ("maximum", &field.maximum)] {
+                    if let Some(n) = bound.as_deref().and_then(|b| b.parse::<serde_json::Number>().ok()) {
+                        prop.insert(key.to_string(), serde_json::Value::Number(n));
+                    }
+                }
+                if let Some(ref pattern) = field.pattern {
+                    prop.insert("pattern".to_string(), serde_json::Value::String(pattern.clone()));
+                }
+                if field.is_positional && entry.is_array {
+                    // Variadic positional: the value schema describes each item
+                    let description = prop.remove("description").unwrap_or_default();
+                    let mut array = serde_json::Map::new();
+                    array.insert("type".to_string(), serde_json::Value::String("array".to_string()));
+                    array.insert("items".to_string(), serde_json::Value::Object(prop));
+                    array.insert("description".to_string(), description);
+                    prop = array;
+                } else if entry.is_map && !field.is_positional {
+                    // Map flag (`local -A`): the value schema describes each entry
+                    let description = prop.remove("description").unwrap_or_default();
+                    let mut object = serde_json::Map::new();
+                    object.insert("type".to_string(), serde_json::Value::String("object".to_string()));
+                    object.insert("additionalProperties".to_string(), serde_json::Value::Object(prop));
+                    object.insert("description".to_string(), description);
+                    prop = object;
+                }
                 properties.insert(field.name.clone(), serde_json::Value::Object(prop));
-                if field.required {
+                let required = if field.is_positional && !entry.is_array {
+                    let has_default = func
+                        .local_vars
+                        .iter()
+                        .any(|v| v.name == field.name && v.default_value.is_some());
+                    !field.is_optional && !has_default
+                } else {
+                    field.required
+                };
+                // Required members of a :%group are alternatives (see oneOf below)
+                if required && field.group.is_none() {
                     required_list.push(serde_json::Value::String(field.name.clone()));
                 }
+                fields.push(field);
             }
         }
 
@@ -466,6 +517,7 @@ fn build_mcp_tools(analysis: &DocumentAnalysis, script_name: &str) -> String {
         if !required_list.is_empty() {
             schema.insert("required".to_string(), serde_json::Value::Array(required_list));
         }
+        insert_group_constraints(&mut schema, &fields);
         schema.insert("additionalProperties".to_string(), serde_json::Value::Bool(false));
 
         let mut tool = serde_json::Map::new();
@@ -534,6 +586,18 @@ fn build_docgen_yaml(analysis: &DocumentAnalysis, _content: &str) -> String {
                     yaml.push_str(&format!("    {}:\n", field.name));
                     let type_str = format_type(field, entry.is_array);
                     yaml.push_str(&format!("      type: {}\n", type_str));
+                    if !field.choices.is_empty() {
+                        yaml.push_str(&format!("      choices: [{}]\n", field.choices.join(", ")));
+                    }
+                    if let Some(ref min) = field.minimum {
+                        yaml.push_str(&format!("      minimum: {}\n", min));
+                    }
+                    if let Some(ref max) = field.maximum {
+                        yaml.push_str(&format!("      maximum: {}\n", max));
+                    }
+                    if let Some(ref pattern) = field.pattern {
+                        yaml.push_str(&format!("      pattern: \"{}\"\n", yaml_escape(pattern)));
+                    }
                     yaml.push_str(&format!("      description: \"{}\"\n", yaml_escape(&entry.description)));
                     if field.required {
                         yaml.push_str("      required: true\n");
@@ -544,6 +608,15 @@ fn build_docgen_yaml(analysis: &DocumentAnalysis, _content: &str) -> String {
                     if field.is_inherited {
                         yaml.push_str("      inherited: true\n");
                     }
+                    if let Some(ref var) = field.env_var {
+                        yaml.push_str(&format!("      env: {}\n", var));
+                    }
+                    if let Some(ref group) = field.group {
+                        yaml.push_str(&format!("      group: {}\n", group));
+                    }
+                    if !field.requires.is_empty() {
+                        yaml.push_str(&format!("      requires: [{}]\n", field.requires.join(", ")));
+                    }
                     if let Some(ref short) = field.short {
                         yaml.push_str(&format!("      short: {}\n", short));
                     }
@@ -578,6 +651,40 @@ fn build_docgen_yaml(analysis: &DocumentAnalysis, _content: &str) -> String {
     yaml
 }
 
+/// Add `allOf`/`oneOf` for `:%group` and `dependentRequired` for `:&flag`,
+/// matching the runtime MCP schema.
+fn insert_group_constraints(schema: &mut serde_json::Map<String, serde_json::Value>, fields: &[&FieldDef]) {
+    let present = |name: &str| serde_json::json!({ "required": [name] });
+    let mut groups: Vec<serde_json::Value> = Vec::new();
+    let mut seen: Vec<&str> = Vec::new();
+    for field in fields {
+        let Some(ref group) = field.group else { continue };
+        if seen.contains(&group.as_str()) {
+            continue;
+        }
+        seen.push(group);
+        let members: Vec<&&FieldDef> = fields.iter().filter(|f| f.group.as_ref() == Some(group)).collect();
+        let mut alternatives: Vec<serde_json::Value> = members.iter().map(|f| present(&f.name)).collect();
+        if !members.iter().any(|f| f.required) {
+            // "none of them" is also allowed
+            alternatives.push(serde_json::json!({ "not": { "anyOf": alternatives.clone() } }));
+        }
+        groups.push(serde_json::json!({ "oneOf": alternatives }));
+    }
+    if !groups.is_empty() {
+        schema.insert("allOf".to_string(), serde_json::Value::Array(groups));
+    }
+
+    let mut dependent = serde_json::Map::new();
+    for field in fields.iter().filter(|f| !f.requires.is_empty()) {
+        let others: Vec<String> = field.requires.iter().map(|r| field_name(r, true)).collect();
+        dependent.insert(field.name.clone(), serde_json::json!(others));
+    }
+    if !dependent.is_empty() {
+        schema.insert("dependentRequired".to_string(), serde_json::Value::Object(dependent));
+    }
+}
+
 /// Export MCP tool schema as pretty-printed JSON.
 pub fn export_mcp_json(analysis: &DocumentAnalysis, script_name: &str) -> String {
     build_mcp_tools(analysis, script_name)
@@ -609,6 +716,14 @@ pub fn export_docgen_json(analysis: &DocumentAnalysis) -> String {
                         format_type(field, e.is_array)
                     ));
                     m.insert("description".to_string(), serde_json::Value::String(e.description.clone()));
+                    if !field.choices.is_empty() { m.insert("choices".to_string(), serde_json::json!(field.choices)); }
+                    for (key, bound) in [("minimum", &field.minimum), ("maximum", &field.maximum)] {
+                        if let Some(n) = bound.as_deref().and_then(|b| b.parse::<serde_json::Number>().ok()) { m.insert(key.to_string(), serde_json::Value::Number(n)); }
+                    }
+                    if let Some(ref p) = field.pattern { m.insert("pattern".to_string(), serde_json::Value::String(p.clone())); }
+                    if let Some(ref var) = field.env_var { m.insert("env".to_string(), serde_json::Value::String(var.clone())); }
+                    if let Some(ref group) = field.group { m.insert("group".to_string(), serde_json::Value::String(group.clone())); }
+                    if !field.requires.is_empty() { m.insert("requires".to_string(), serde_json::json!(field.requires)); }
                     if field.required { m.insert("required".to_string(), serde_json::Value::Bool(true)); }
                     if let Some(ref s) = field.short { m.insert("short".to_string(), serde_json::Value::String(s.clone())); }
                     Some(serde_json::Value::Object(m))
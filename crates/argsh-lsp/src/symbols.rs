@@ -33,7 +33,9 @@ fn build_nested_symbols(analysis: &DocumentAnalysis) -> Vec<DocumentSymbol> {
                     continue;
                 }
                 let detail = if let Ok(ref field) = entry.parsed {
-                    let type_str = if field.is_boolean {
+                    let type_str = if field.is_counter {
+                        "int"
+                    } else if field.is_boolean {
                         "boolean"
                     } else {
                         &field.type_name
@@ -48,7 +50,9 @@ fn build_nested_symbols(analysis: &DocumentAnalysis) -> Vec<DocumentSymbol> {
                     name: entry.spec.clone(),
                     detail,
                     kind: SymbolKind::PROPERTY,
-                    tags: None,
+                    tags: deprecated_tag(
+                        entry.parsed.as_ref().is_ok_and(|f| f.deprecated.is_some()),
+                    ),
                     deprecated: None,
                     range: line_range(entry.line),
                     selection_range: line_range(entry.line),
@@ -70,7 +74,7 @@ fn build_nested_symbols(analysis: &DocumentAnalysis) -> Vec<DocumentSymbol> {
                     name: entry.name.clone(),
                     detail,
                     kind: SymbolKind::ENUM,
-                    tags: None,
+                    tags: deprecated_tag(entry.deprecated().is_some()),
                     deprecated: None,
                     range: line_range(entry.line),
                     selection_range: line_range(entry.line),
@@ -171,3 +175,8 @@ fn line_range(line: usize) -> Range {
         },
     }
 }
+
+/// `@deprecated` args and usage entries are tagged so editors strike them through.
+fn deprecated_tag(deprecated: bool) -> Option<Vec<SymbolTag>> {
+    deprecated.then(|| vec![SymbolTag::DEPRECATED])
+}
@@ -580,6 +580,10 @@ myfunc() {
     };
     // Should suggest type names like int, float, file, etc.
     assert!(!items.is_empty(), "Expected type completion items");
+    let labels: Vec<&str> = items.iter().filter_map(|i| i["label"].as_str()).collect();
+    for expected in ["duration", "size", "url", "dir", "path", "json", "config"] {
+        assert!(labels.contains(&expected), "Missing type '{}' in {:?}", expected, labels);
+    }
 
     client.shutdown();
 }
@@ -1002,6 +1006,51 @@ fn test_hover_on_args_entry_shows_field_details() {
     client.shutdown();
 }
 
+#[test]
+fn test_hover_on_boolean_flag_shows_negated_form() {
+    let mut client = LspTestClient::new();
+    client.initialize();
+
+    let content = "#!/usr/bin/env bash\nsource argsh\nserve() {\n  local color=1\n  local -a args=(\n    'color|c:+' \"Colored output\"\n  )\n  :args \"Start server\" \"${@}\"\n}\n";
+    client.open_document("file:///test_negated.sh", content);
+    let resp = client.hover("file:///test_negated.sh", 5, 8);
+    assert!(resp.get("error").is_none(), "Error: {:?}", resp["error"]);
+    let content_str = format!("{}", resp["result"]);
+    assert!(
+        content_str.contains("--no-color"),
+        "Hover should show the negated form: {}",
+        content_str
+    );
+
+    // A boolean that defaults to off has no negated form to show
+    let content = content.replace("local color=1", "local color");
+    client.open_document("file:///test_negated_off.sh", &content);
+    let resp = client.hover("file:///test_negated_off.sh", 5, 8);
+    let content_str = format!("{}", resp["result"]);
+    assert!(!content_str.contains("--no-color"), "Unexpected negated form: {}", content_str);
+
+    client.shutdown();
+}
+
+#[test]
+fn test_hover_on_counter_flag_shows_int_type() {
+    let mut client = LspTestClient::new();
+    client.initialize();
+
+    let content = "#!/usr/bin/env bash\nsource argsh\nserve() {\n  local verbose=0\n  local -a args=(\n    'verbose|v:++' \"Verbosity\"\n  )\n  :args \"Start server\" \"${@}\"\n}\n";
+    client.open_document("file:///test_counter.sh", content);
+    let resp = client.hover("file:///test_counter.sh", 5, 8);
+    assert!(resp.get("error").is_none(), "Error: {:?}", resp["error"]);
+    let content_str = format!("{}", resp["result"]);
+    assert!(
+        content_str.contains("`int`") && content_str.contains("Repeatable: yes"),
+        "Hover should show the counter as a repeatable int: {}",
+        content_str
+    );
+
+    client.shutdown();
+}
+
 #[test]
 fn test_hover_on_usage_entry_shows_command_info() {
     let mut client = LspTestClient::new();
@@ -1555,6 +1604,171 @@ fn test_export_mcp_json() {
     client.shutdown();
 }
 
+#[test]
+fn test_export_mcp_json_enum_choices() {
+    let mut client = LspTestClient::new();
+    client.initialize();
+
+    let content = "#!/usr/bin/env bash\nsource argsh\ndeploy() {\n  local env\n  local -a args=(\n    'env|e:~enum(dev,staging,prod)' \"Target environment\"\n  )\n  :args \"Deploy\" \"${@}\"\n}\n";
+    let uri = "file:///test_mcp_enum.sh";
+    client.open_document(uri, content);
+
+    let resp = client.send_request(
+        "workspace/executeCommand",
+        json!({
+            "command": "argsh.exportMcpJson",
+            "arguments": [uri]
+        }),
+    );
+    assert!(resp.get("error").is_none(), "Error: {:?}", resp["error"]);
+    let result_str = resp["result"].as_str().unwrap_or("");
+    let parsed: Value = serde_json::from_str(result_str).expect("MCP export should be valid JSON");
+    let env = &parsed["tools"][0]["inputSchema"]["properties"]["env"];
+    assert_eq!(env["type"], "string");
+    assert_eq!(env["enum"], json!(["dev", "staging", "prod"]));
+
+    client.shutdown();
+}
+
+#[test]
+fn test_export_mcp_json_env_fallback() {
+    let mut client = LspTestClient::new();
+    client.initialize();
+
+    let content = "#!/usr/bin/env bash\nsource argsh\nserve() {\n  local port\n  local -a args=(\n    'port|p:~int:$APP_PORT' \"Port number\"\n  )\n  :args \"Start server\" \"${@}\"\n}\n";
+    let uri = "file:///test_mcp_env.sh";
+    client.open_document(uri, content);
+
+    let resp = client.send_request(
+        "workspace/executeCommand",
+        json!({
+            "command": "argsh.exportMcpJson",
+            "arguments": [uri]
+        }),
+    );
+    assert!(resp.get("error").is_none(), "Error: {:?}", resp["error"]);
+    let result_str = resp["result"].as_str().unwrap_or("");
+    let parsed: Value = serde_json::from_str(result_str).expect("MCP export should be valid JSON");
+    let port = &parsed["tools"][0]["inputSchema"]["properties"]["port"];
+    assert_eq!(port["type"], "integer");
+    assert_eq!(port["description"], "Port number (env: APP_PORT)");
+
+    client.shutdown();
+}
+
+#[test]
+fn test_export_mcp_json_range_and_pattern() {
+    let mut client = LspTestClient::new();
+    client.initialize();
+
+    let content = "#!/usr/bin/env bash\nsource argsh\nserve() {\n  local port tag\n  local -a args=(\n    'port|p:~int[1..65535]' \"Port number\"\n    'tag|t:~string/^v[0-9]+/' \"Release tag\"\n  )\n  :args \"Start server\" \"${@}\"\n}\n";
+    let uri = "file:///test_mcp_constraints.sh";
+    client.open_document(uri, content);
+
+    let resp = client.send_request(
+        "workspace/executeCommand",
+        json!({
+            "command": "argsh.exportMcpJson",
+            "arguments": [uri]
+        }),
+    );
+    assert!(resp.get("error").is_none(), "Error: {:?}", resp["error"]);
+    let result_str = resp["result"].as_str().unwrap_or("");
+    let parsed: Value = serde_json::from_str(result_str).expect("MCP export should be valid JSON");
+    let props = &parsed["tools"][0]["inputSchema"]["properties"];
+    assert_eq!(props["port"]["minimum"], 1);
+    assert_eq!(props["port"]["maximum"], 65535);
+    assert_eq!(props["tag"]["pattern"], "^v[0-9]+");
+
+    client.shutdown();
+}
+
+#[test]
+fn test_export_mcp_json_flag_groups() {
+    let mut client = LspTestClient::new();
+    client.initialize();
+
+    let content = "#!/usr/bin/env bash\nsource argsh\nfetch() {\n  local file url tls_cert tls_key\n  local -a args=(\n    'file|f:%input:!' \"Read from file\"\n    'url|u:%input:!' \"Read from URL\"\n    'tls-cert|:&tls-key' \"Client certificate\"\n    'tls-key|' \"Client key\"\n  )\n  :args \"Fetch data\" \"${@}\"\n}\n";
+    let uri = "file:///test_mcp_groups.sh";
+    client.open_document(uri, content);
+
+    let resp = client.send_request(
+        "workspace/executeCommand",
+        json!({
+            "command": "argsh.exportMcpJson",
+            "arguments": [uri]
+        }),
+    );
+    assert!(resp.get("error").is_none(), "Error: {:?}", resp["error"]);
+    let result_str = resp["result"].as_str().unwrap_or("");
+    let parsed: Value = serde_json::from_str(result_str).expect("MCP export should be valid JSON");
+    let schema = &parsed["tools"][0]["inputSchema"];
+    assert!(schema.get("required").is_none(), "group members must not be individually required");
+    assert_eq!(
+        schema["allOf"][0]["oneOf"],
+        json!([{ "required": ["file"] }, { "required": ["url"] }])
+    );
+    assert_eq!(schema["dependentRequired"], json!({ "tls_cert": ["tls_key"] }));
+
+    client.shutdown();
+}
+
+#[test]
+fn test_export_mcp_json_variadic_positionals() {
+    let mut client = LspTestClient::new();
+    client.initialize();
+
+    let content = "#!/usr/bin/env bash\nsource argsh\ncopy() {\n  local -a src\n  local dest mode\n  local -a args=(\n    'src:~file:!' \"Sources\"\n    'dest' \"Destination\"\n    'mode:?' \"Copy mode\"\n  )\n  :args \"Copy files\" \"${@}\"\n}\n";
+    let uri = "file:///test_mcp_variadic.sh";
+    client.open_document(uri, content);
+
+    let resp = client.send_request(
+        "workspace/executeCommand",
+        json!({
+            "command": "argsh.exportMcpJson",
+            "arguments": [uri]
+        }),
+    );
+    assert!(resp.get("error").is_none(), "Error: {:?}", resp["error"]);
+    let result_str = resp["result"].as_str().unwrap_or("");
+    let parsed: Value = serde_json::from_str(result_str).expect("MCP export should be valid JSON");
+    let schema = &parsed["tools"][0]["inputSchema"];
+    assert_eq!(schema["properties"]["src"]["type"], "array");
+    assert_eq!(schema["properties"]["src"]["items"]["type"], "string");
+    assert_eq!(schema["properties"]["src"]["description"], "Sources");
+    assert_eq!(schema["properties"]["dest"]["type"], "string");
+    assert_eq!(schema["required"], json!(["src", "dest"]));
+
+    client.shutdown();
+}
+
+#[test]
+fn test_export_mcp_json_map_flag() {
+    let mut client = LspTestClient::new();
+    client.initialize();
+
+    let content = "#!/usr/bin/env bash\nsource argsh\ndeploy() {\n  local -A label\n  local -a args=(\n    'label|l:~int' \"Labels\"\n  )\n  :args \"Deploy\" \"${@}\"\n}\n";
+    let uri = "file:///test_mcp_map.sh";
+    client.open_document(uri, content);
+
+    let resp = client.send_request(
+        "workspace/executeCommand",
+        json!({
+            "command": "argsh.exportMcpJson",
+            "arguments": [uri]
+        }),
+    );
+    assert!(resp.get("error").is_none(), "Error: {:?}", resp["error"]);
+    let result_str = resp["result"].as_str().unwrap_or("");
+    let parsed: Value = serde_json::from_str(result_str).expect("MCP export should be valid JSON");
+    let label = &parsed["tools"][0]["inputSchema"]["properties"]["label"];
+    assert_eq!(label["type"], "object");
+    assert_eq!(label["additionalProperties"]["type"], "integer");
+    assert_eq!(label["description"], "Labels");
+
+    client.shutdown();
+}
+
 #[test]
 fn test_export_yaml() {
     let mut client = LspTestClient::new();
@@ -41,6 +41,8 @@ pub struct LocalVar {
     pub line: usize,
     /// Declared with `local -a`.
     pub is_array: bool,
+    /// Declared with `local -A` (associative array).
+    pub is_map: bool,
     /// Default value if assigned inline.
     pub default_value: Option<String>,
 }
@@ -59,6 +61,9 @@ pub struct ArgsArrayEntry {
     /// Whether the corresponding variable is declared as `local -a` (array).
     /// When true, the flag accepts multiple values (e.g. `--files a --files b`).
     pub is_array: bool,
+    /// Whether the corresponding variable is declared as `local -A`; the flag
+    /// then takes repeated `key=value` pairs (e.g. `--label team=core`).
+    pub is_map: bool,
 }
 
 /// Result of analysing a source file.
@@ -181,6 +186,7 @@ fn find_functions(lines: &[&str]) -> Vec<FunctionInfo> {
         for entry in &mut args_entries {
             if let Ok(ref field) = entry.parsed {
                 entry.is_array = local_vars.iter().any(|v| v.name == field.name && v.is_array);
+                entry.is_map = local_vars.iter().any(|v| v.name == field.name && v.is_map);
             }
         }
 
@@ -405,6 +411,7 @@ fn add_paired_entries(entries: &mut Vec<ArgsArrayEntry>, tokens: &[String], base
             parsed,
             line: base_line,
             is_array: false, // enriched later from local_vars
+            is_map: false,
         });
         i += 2;
     }
@@ -419,11 +426,11 @@ fn extract_locals(body: &[&str], body_start: usize) -> Vec<LocalVar> {
         if let Some(cap) = re_local.captures(line) {
             let decl = cap.get(1).unwrap().as_str();
             let is_array = decl.starts_with("-a ");
-            let decl = if is_array {
-                decl.strip_prefix("-a ").unwrap_or(decl)
-            } else {
-                decl
-            };
+            let is_map = decl.starts_with("-A ");
+            let decl = decl
+                .strip_prefix("-a ")
+                .or_else(|| decl.strip_prefix("-A "))
+                .unwrap_or(decl);
 
             // Split on whitespace to handle `local a b c` or `local -a arr=(...)`.
             // Each segment may have `=value`.
@@ -446,6 +453,7 @@ fn extract_locals(body: &[&str], body_start: usize) -> Vec<LocalVar> {
                         name,
                         line: body_start + i,
                         is_array,
+                        is_map,
                         default_value: Some(value.to_string()),
                     });
                 } else {
@@ -461,6 +469,7 @@ fn extract_locals(body: &[&str], body_start: usize) -> Vec<LocalVar> {
                         name,
                         line: body_start + i,
                         is_array,
+                        is_map,
                         default_value: None,
                     });
                 }
@@ -1049,6 +1058,24 @@ f() {
             "output should not be array"
         );
     }
+
+    #[test]
+    fn test_args_entry_is_map_from_local() {
+        let src = r#"
+f() {
+  local -A labels
+  local -a args=(
+    'labels|l' "Labels"
+  )
+  :args "T" "${@}"
+}
+"#;
+        let doc = analyze(src);
+        let func = &doc.functions[0];
+        // 'labels' is local -A -> is_map, but not an indexed array
+        assert!(func.args_entries[0].is_map, "labels should be detected as map");
+        assert!(!func.args_entries[0].is_array);
+    }
 }
 
 #[cfg(test)]
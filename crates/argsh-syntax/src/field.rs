@@ -1,10 +1,13 @@
 //! Parse argsh field definitions.
 //!
-//! Mirrors the parsing logic in `builtin/src/field.rs` but without any
-//! shell FFI calls — pure text-only parsing suitable for static analysis.
+//! Pure text-only parsing, without any shell FFI calls. The builtin
+//! (`builtin/src/field.rs`) uses this parser and adds the state of the
+//! field's shell variable; the LSP uses it for static analysis.
 
 use std::fmt;
 
+use regex::Regex;
+
 /// Error returned when a field spec is invalid.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FieldError {
@@ -30,20 +33,118 @@ pub struct FieldDef {
     pub short: Option<String>,
     /// `:+` modifier — flag that takes no value.
     pub is_boolean: bool,
-    /// Type after `:~` (int, float, file, boolean, string, or custom).
+    /// `:++` modifier — boolean flag that counts its repetitions.
+    pub is_counter: bool,
+    /// Type after `:~` (int, float, file, boolean, string, enum, or custom).
     pub type_name: String,
+    /// Allowed values for `:~enum(a,b,c)`; empty for every other type.
+    pub choices: Vec<String>,
+    /// Lower bound from `:~int[min..max]` / `:~float[min..max]`, normalized.
+    pub minimum: Option<String>,
+    /// Upper bound from `:~int[min..max]` / `:~float[min..max]`, normalized.
+    pub maximum: Option<String>,
+    /// Regular expression from `:~string/re/`, or the anchored equivalent of
+    /// a `:~string(glob)`. Matched with the `regex` crate everywhere.
+    pub pattern: Option<String>,
+    /// Glob from `:~string(glob)`, matched against the whole value.
+    pub glob: Option<String>,
+    /// `:~path(new)` — the path must not exist yet.
+    pub must_not_exist: bool,
     /// `:!` modifier — field is required.
     pub required: bool,
+    /// `:?` modifier — positional may be omitted even without a default.
+    pub is_optional: bool,
     /// `#` prefix on name — field is hidden from help text.
     pub hidden: bool,
     /// No `|` separator in definition — positional parameter.
     pub is_positional: bool,
     /// `:^` modifier — field yields to non-`:^` duplicates (inherited).
     pub is_inherited: bool,
+    /// `:$VAR` modifier — environment variable consulted when the flag is absent.
+    pub env_var: Option<String>,
+    /// `:%group` modifier — at most one flag of the group may be given
+    /// (exactly one when any member is `:!`).
+    pub group: Option<String>,
+    /// `:&flag` modifiers — flags that must be given together with this one.
+    pub requires: Vec<String>,
+    /// `:@deprecated[=new-name]` modifier — replacement name, empty if none.
+    pub deprecated: Option<String>,
     /// Raw spec string, preserved for diagnostics.
     pub raw: String,
 }
 
+impl FieldDef {
+    /// Type as shown in help text: `{a|b|c}` for enums, `path(new)`,
+    /// `int[1..10]` for ranged numbers, the type name otherwise.
+    pub fn type_label(&self) -> String {
+        if !self.choices.is_empty() {
+            return format!("{{{}}}", self.choices.join("|"));
+        }
+        if self.must_not_exist {
+            return format!("{}(new)", self.type_name);
+        }
+        match self.range_label() {
+            Some(range) => format!("{}[{}]", self.type_name, range),
+            None => self.type_name.clone(),
+        }
+    }
+
+    /// Bounds as written in help text (`1..10`, `1..`, `..10`), if any.
+    pub fn range_label(&self) -> Option<String> {
+        if self.minimum.is_none() && self.maximum.is_none() {
+            return None;
+        }
+        Some(format!(
+            "{}..{}",
+            self.minimum.as_deref().unwrap_or(""),
+            self.maximum.as_deref().unwrap_or("")
+        ))
+    }
+
+    /// Check a literal value against the field's type and constraints, the
+    /// way the builtin would at runtime. Types that need the shell (file,
+    /// custom `to::*` converters) are not checked.
+    pub fn check_value(&self, value: &str) -> Result<(), FieldError> {
+        let fail = |message: String| Err(FieldError { message });
+        if self.is_counter {
+            if value.parse::<u64>().is_err() {
+                return fail(format!("invalid count: {}", value));
+            }
+            return Ok(());
+        }
+        if !self.choices.is_empty() {
+            if !self.choices.iter().any(|c| c == value) {
+                return fail(format!("invalid choice ({}): {}", self.choices.join("|"), value));
+            }
+            return Ok(());
+        }
+        if !is_number(&self.type_name, value) {
+            return fail(format!("invalid type ({}): {}", self.type_name, value));
+        }
+        if let Some(range) = self.range_label() {
+            let cmp = |bound: &str| compare_numbers(&self.type_name, value, bound);
+            let below = self.minimum.as_deref().is_some_and(|m| cmp(m).is_lt());
+            let above = self.maximum.as_deref().is_some_and(|m| cmp(m).is_gt());
+            if below || above {
+                return fail(format!("out of range ({}): {}", range, value));
+            }
+        }
+        if self.pattern.as_deref().is_some_and(|p| !pattern_matches(p, value)) {
+            return fail(self.pattern_error(value));
+        }
+        Ok(())
+    }
+
+    /// Error message for a value that does not match the field's pattern.
+    pub fn pattern_error(&self, value: &str) -> String {
+        match (&self.glob, &self.pattern) {
+            (Some(glob), _) => format!("does not match glob ({}): {}", glob, value),
+            (None, Some(pattern)) => format!("does not match pattern ({}): {}", pattern, value),
+            (None, None) => String::new(),
+        }
+    }
+}
+
 /// Extract the variable name from a field definition string.
 ///
 /// `'flag|f:~int!'` -> `"flag"`
@@ -71,8 +172,16 @@ pub fn field_name(field: &str, asref: bool) -> String {
 /// - `name` (no `|`) — positional parameter
 /// - `:+` — boolean flag
 /// - `:~type` — typed parameter (int, float, file, boolean, string, or custom)
+/// - `:~enum(a,b,c)` — value restricted to a fixed set of choices
+/// - `:~int[1..10]` / `:~float[0..1]` — numeric bounds (either may be omitted)
+/// - `:~string/re/` — value must match a regular expression
+/// - `:~string(glob)` — value must match a glob
 /// - `:!` — required
 /// - `:#` — hidden (also `#` prefix on name)
+/// - `:$VAR` — fall back to environment variable `VAR` (flags only)
+/// - `:%group` — mutually exclusive flag group (flags only)
+/// - `:&flag` — requires another flag (flags only, repeatable)
+/// - `:@deprecated[=new-name]` — still accepted, but warns (flags only)
 /// - Error on conflicting modifiers (`:+` with `:~type`)
 /// - Error on unknown modifiers
 pub fn parse_field(spec: &str) -> Result<FieldDef, FieldError> {
@@ -80,7 +189,7 @@ pub fn parse_field(spec: &str) -> Result<FieldDef, FieldError> {
     let name = field_name(spec, true);
     let display_name = field_name(spec, false);
     let hidden = spec.starts_with('#');
-    let is_positional = !spec.contains('|') && spec != "-";
+    let is_positional = !is_flag_spec(spec) && spec != "-";
 
     // Parse short name
     let short = if !is_positional {
@@ -97,10 +206,17 @@ pub fn parse_field(spec: &str) -> Result<FieldDef, FieldError> {
 
     // Parse modifiers after ':'
     let mut is_boolean = false;
+    let mut is_counter = false;
     let mut type_name = String::new();
     let mut required = false;
+    let mut is_optional = false;
     let mut saw_hidden_mod = false;
     let mut is_inherited = false;
+    let mut type_spec = TypeSpec::default();
+    let mut env_var = None;
+    let mut group = None;
+    let mut requires = Vec::new();
+    let mut deprecated = None;
 
     if let Some(colon_pos) = spec.find(':') {
         let mods = &spec[colon_pos + 1..];
@@ -122,6 +238,10 @@ pub fn parse_field(spec: &str) -> Result<FieldDef, FieldError> {
                     }
                     is_boolean = true;
                     chars.next();
+                    if chars.peek() == Some(&'+') {
+                        is_counter = true;
+                        chars.next();
+                    }
                 }
                 '~' => {
                     if is_boolean {
@@ -130,16 +250,33 @@ pub fn parse_field(spec: &str) -> Result<FieldDef, FieldError> {
                         });
                     }
                     chars.next();
-                    // Collect type name until next modifier or separator
+                    // Collect type name until next modifier or separator; enum
+                    // choices `(...)`, a range `[...]` and a pattern `/.../` are
+                    // taken verbatim
                     let mut tname = String::new();
                     while let Some(&tc) = chars.peek() {
-                        if tc == '+' || tc == '~' || tc == '!' || tc == '#' || tc == '^' || tc == ':' {
+                        if tc == '+' || tc == '~' || tc == '!' || tc == '#' || tc == '^' || tc == ':' || tc == '$' || tc == '%' || tc == '&' || tc == '?' || tc == '@' {
                             break;
                         }
                         tname.push(tc);
                         chars.next();
+                        let close = match tc {
+                            '(' => ')',
+                            '[' => ']',
+                            '/' => '/',
+                            _ => continue,
+                        };
+                        let mut escaped = false;
+                        for ac in chars.by_ref() {
+                            tname.push(ac);
+                            if ac == close && !escaped {
+                                break;
+                            }
+                            escaped = close == '/' && ac == '\\' && !escaped;
+                        }
                     }
-                    type_name = tname;
+                    type_spec = parse_type_spec(&tname)?;
+                    type_name = type_spec.name.clone();
                 }
                 '!' => {
                     if required {
@@ -150,6 +287,10 @@ pub fn parse_field(spec: &str) -> Result<FieldDef, FieldError> {
                     required = true;
                     chars.next();
                 }
+                '?' => {
+                    is_optional = true;
+                    chars.next();
+                }
                 '#' => {
                     saw_hidden_mod = true;
                     chars.next();
@@ -158,6 +299,57 @@ pub fn parse_field(spec: &str) -> Result<FieldDef, FieldError> {
                     is_inherited = true;
                     chars.next();
                 }
+                '$' => {
+                    if env_var.is_some() {
+                        return Err(FieldError {
+                            message: "field already has an environment variable".to_string(),
+                        });
+                    }
+                    chars.next();
+                    let mut var = String::new();
+                    while let Some(&vc) = chars.peek() {
+                        if !vc.is_ascii_alphanumeric() && vc != '_' {
+                            break;
+                        }
+                        var.push(vc);
+                        chars.next();
+                    }
+                    if var.is_empty() {
+                        return Err(FieldError {
+                            message: "missing environment variable name after $".to_string(),
+                        });
+                    }
+                    env_var = Some(var);
+                }
+                '%' => {
+                    if group.is_some() {
+                        return Err(FieldError {
+                            message: "field already belongs to a group".to_string(),
+                        });
+                    }
+                    chars.next();
+                    group = Some(take_name(&mut chars, "group name after %")?);
+                }
+                '&' => {
+                    chars.next();
+                    requires.push(take_name(&mut chars, "flag name after &")?);
+                }
+                '@' => {
+                    chars.next();
+                    let annotation = take_name(&mut chars, "annotation after @")?;
+                    if annotation != "deprecated" {
+                        return Err(FieldError {
+                            message: format!("unknown annotation: @{}", annotation),
+                        });
+                    }
+                    let replacement = if chars.peek() == Some(&'=') {
+                        chars.next();
+                        take_name(&mut chars, "replacement after @deprecated=")?
+                    } else {
+                        String::new()
+                    };
+                    deprecated = Some(replacement);
+                }
                 _ => {
                     return Err(FieldError {
                         message: format!("unknown modifier: {}", c),
@@ -167,6 +359,42 @@ pub fn parse_field(spec: &str) -> Result<FieldDef, FieldError> {
         }
     }
 
+    if is_positional && is_counter {
+        return Err(FieldError {
+            message: "counters are only supported for flags".to_string(),
+        });
+    }
+    if is_positional && env_var.is_some() {
+        return Err(FieldError {
+            message: "environment variable fallback is only supported for flags".to_string(),
+        });
+    }
+    if is_positional && (group.is_some() || !requires.is_empty()) {
+        return Err(FieldError {
+            message: "flag groups are only supported for flags".to_string(),
+        });
+    }
+    if is_positional && deprecated.is_some() {
+        return Err(FieldError {
+            message: "deprecation is only supported for flags".to_string(),
+        });
+    }
+    if is_positional && type_name == "config" {
+        return Err(FieldError {
+            message: "config files are only supported for flags".to_string(),
+        });
+    }
+    if !is_positional && is_optional {
+        return Err(FieldError {
+            message: "optional marker is only supported for positionals".to_string(),
+        });
+    }
+    if is_optional && required {
+        return Err(FieldError {
+            message: "field cannot be both required and optional".to_string(),
+        });
+    }
+
     // Default type
     if type_name.is_empty() && !is_boolean {
         type_name = "string".to_string();
@@ -177,15 +405,314 @@ pub fn parse_field(spec: &str) -> Result<FieldDef, FieldError> {
         display_name,
         short,
         is_boolean,
+        is_counter,
         type_name,
+        choices: type_spec.choices,
+        minimum: type_spec.minimum,
+        maximum: type_spec.maximum,
+        pattern: type_spec.pattern,
+        glob: type_spec.glob,
+        must_not_exist: type_spec.must_not_exist,
         required,
+        is_optional,
         hidden: hidden || saw_hidden_mod,
         is_positional,
         is_inherited,
+        env_var,
+        group,
+        requires,
+        deprecated,
         raw,
     })
 }
 
+/// Collect a group or flag name (`[A-Za-z0-9_-]`) following a modifier.
+fn take_name(
+    chars: &mut std::iter::Peekable<std::str::Chars<'_>>,
+    what: &str,
+) -> Result<String, FieldError> {
+    let mut name = String::new();
+    while let Some(&c) = chars.peek() {
+        if !c.is_ascii_alphanumeric() && c != '_' && c != '-' {
+            break;
+        }
+        name.push(c);
+        chars.next();
+    }
+    if name.is_empty() {
+        return Err(FieldError {
+            message: format!("missing {}", what),
+        });
+    }
+    Ok(name)
+}
+
+/// Whether an args entry is a flag: its name part (before modifiers) has a `|`.
+pub fn is_flag_spec(spec: &str) -> bool {
+    spec.split(':').next().unwrap_or(spec).contains('|')
+}
+
+/// A `:~` type split into its base name and optional constraints.
+#[derive(Debug, Default)]
+struct TypeSpec {
+    name: String,
+    choices: Vec<String>,
+    minimum: Option<String>,
+    maximum: Option<String>,
+    pattern: Option<String>,
+    glob: Option<String>,
+    must_not_exist: bool,
+}
+
+/// Split a type spec into its base name and constraints.
+///
+/// `int` -> int, `enum(dev,prod)` -> enum with choices, `path(new)` -> path
+/// that must not exist yet, `string(*.tar.gz)` -> string with glob,
+/// `int[1..65535]` -> int with bounds, `string/^v[0-9]+/` -> string with pattern
+fn parse_type_spec(tname: &str) -> Result<TypeSpec, FieldError> {
+    let err = |message: String| FieldError { message };
+    let base_end = tname.find(['(', '[', '/']).unwrap_or(tname.len());
+    let mut spec = TypeSpec {
+        name: tname[..base_end].to_string(),
+        ..TypeSpec::default()
+    };
+    let mut rest = &tname[base_end..];
+
+    if let Some(after) = rest.strip_prefix('(') {
+        let close = after
+            .find(')')
+            .ok_or_else(|| err(format!("missing closing parenthesis in type: {}", tname)))?;
+        let args = &after[..close];
+        match spec.name.as_str() {
+            "enum" => {
+                spec.choices = args
+                    .split(',')
+                    .map(|c| c.trim().to_string())
+                    .filter(|c| !c.is_empty())
+                    .collect();
+                if spec.choices.is_empty() {
+                    return Err(err("enum requires at least one choice".to_string()));
+                }
+            }
+            "path" if args.trim() == "new" => spec.must_not_exist = true,
+            "string" if !args.is_empty() => {
+                spec.pattern = Some(glob_to_regex(args));
+                spec.glob = Some(args.to_string());
+            }
+            _ => return Err(err(format!("type does not take arguments: {}", spec.name))),
+        }
+        rest = &after[close + 1..];
+    }
+
+    if let Some(after) = rest.strip_prefix('[') {
+        let close = after
+            .find(']')
+            .ok_or_else(|| err(format!("missing closing bracket in type: {}", tname)))?;
+        (spec.minimum, spec.maximum) = parse_range(&spec.name, &after[..close]).map_err(err)?;
+        rest = &after[close + 1..];
+    }
+
+    if let Some(after) = rest.strip_prefix('/') {
+        let close = closing_slash(after)
+            .ok_or_else(|| err(format!("missing closing slash in pattern: {}", tname)))?;
+        if spec.name != "string" {
+            return Err(err(format!("type does not take a pattern: {}", spec.name)));
+        }
+        if spec.glob.is_some() {
+            return Err(err(format!("type cannot have both a glob and a pattern: {}", tname)));
+        }
+        let pattern = after[..close].replace("\\/", "/");
+        if Regex::new(&pattern).is_err() {
+            return Err(err(format!("invalid pattern: {}", pattern)));
+        }
+        spec.pattern = Some(pattern);
+        rest = &after[close + 1..];
+    }
+
+    if !rest.is_empty() {
+        return Err(err(format!("unexpected characters in type: {}", tname)));
+    }
+    Ok(spec)
+}
+
+/// Parse the inside of `[min..max]`; either bound may be omitted.
+fn parse_range(type_name: &str, range: &str) -> Result<(Option<String>, Option<String>), String> {
+    if type_name != "int" && type_name != "float" {
+        return Err(format!("type does not take a range: {}", type_name));
+    }
+    let (lo, hi) = range
+        .split_once("..")
+        .ok_or_else(|| format!("invalid range: [{}]", range))?;
+    let bound = |b: &str| -> Result<Option<String>, String> {
+        let b = b.trim();
+        if b.is_empty() {
+            return Ok(None);
+        }
+        let invalid = || format!("invalid range bound ({}): {}", type_name, b);
+        if !is_number(type_name, b) {
+            return Err(invalid());
+        }
+        // Normalize (e.g. `007` -> `7`) so bounds are valid JSON numbers
+        let normalized = if type_name == "int" {
+            b.parse::<i64>().ok().map(|n| n.to_string())
+        } else {
+            b.parse::<f64>().ok().map(|n| n.to_string())
+        };
+        normalized.map(Some).ok_or_else(invalid)
+    };
+    let (minimum, maximum) = (bound(lo)?, bound(hi)?);
+    match (&minimum, &maximum) {
+        (None, None) => return Err(format!("invalid range: [{}]", range)),
+        (Some(lo), Some(hi)) if compare_numbers(type_name, lo, hi).is_gt() => {
+            return Err(format!("invalid range: [{}]", range));
+        }
+        _ => {}
+    }
+    Ok((minimum, maximum))
+}
+
+/// Translate a bash glob (`*`, `?`, `[...]`, `\x`) into an anchored regular
+/// expression, so globs and patterns share one matcher and JSON Schema can
+/// carry either as `pattern`.
+pub fn glob_to_regex(glob: &str) -> String {
+    let mut re = String::from("^");
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => re.push_str(".*"),
+            '?' => re.push('.'),
+            '\\' => match chars.next() {
+                Some(next) => re.push_str(&regex::escape(&next.to_string())),
+                None => re.push_str("\\\\"),
+            },
+            '[' => match bracket_class(chars.clone()) {
+                Some((class, len)) => {
+                    re.push_str(&class);
+                    for _ in 0..len {
+                        chars.next();
+                    }
+                }
+                None => re.push_str("\\["),
+            },
+            _ => re.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    re.push('$');
+    re
+}
+
+/// Translate the rest of a glob bracket expression (after `[`) into a regex
+/// class. Returns the class and the number of chars consumed, or None if the
+/// bracket is never closed (bash then matches a literal `[`).
+fn bracket_class(chars: impl Iterator<Item = char>) -> Option<(String, usize)> {
+    let mut class = String::from("[");
+    let mut len = 0;
+    let mut chars = chars.peekable();
+    if matches!(chars.peek(), Some('!' | '^')) {
+        class.push('^');
+        chars.next();
+        len += 1;
+    }
+    let mut first = true;
+    while let Some(c) = chars.next() {
+        len += 1;
+        match c {
+            ']' if !first => {
+                class.push(']');
+                return Some((class, len));
+            }
+            '[' if chars.peek() == Some(&':') => {
+                // POSIX class such as [:digit:], copied through verbatim
+                class.push('[');
+                for pc in chars.by_ref() {
+                    len += 1;
+                    class.push(pc);
+                    if pc == ']' {
+                        break;
+                    }
+                }
+            }
+            '\\' => {
+                let next = chars.next()?;
+                len += 1;
+                class.push('\\');
+                class.push(next);
+            }
+            '[' | ']' | '&' | '~' | '^' => {
+                class.push('\\');
+                class.push(c);
+            }
+            _ => class.push(c),
+        }
+        first = false;
+    }
+    None
+}
+
+/// Byte offset of the first `/` not escaped by a backslash.
+fn closing_slash(s: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in s.char_indices() {
+        match c {
+            '/' if !escaped => return Some(i),
+            '\\' => escaped = !escaped,
+            _ => escaped = false,
+        }
+    }
+    None
+}
+
+/// Whether `value` is accepted by the builtin's `int` / `float` converters.
+/// Always true for other types.
+fn is_number(type_name: &str, value: &str) -> bool {
+    match type_name {
+        "int" => value.parse::<i64>().is_ok(),
+        "float" => {
+            // Matches the builtin: ^-?[0-9]+(\.[0-9]+)?$
+            let digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+            let unsigned = value.strip_prefix('-').unwrap_or(value);
+            match unsigned.split_once('.') {
+                Some((int, frac)) => digits(int) && digits(frac),
+                None => digits(unsigned),
+            }
+        }
+        _ => true,
+    }
+}
+
+thread_local! {
+    /// Compiled `/pattern/` and glob constraints, so a pattern is compiled
+    /// once rather than for every value checked against it.
+    static PATTERNS: std::cell::RefCell<std::collections::HashMap<String, Regex>> =
+        std::cell::RefCell::default();
+}
+
+/// Whether `value` matches `pattern`; a pattern that does not compile
+/// matches nothing.
+fn pattern_matches(pattern: &str, value: &str) -> bool {
+    PATTERNS.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if !cache.contains_key(pattern) {
+            let Ok(re) = Regex::new(pattern) else {
+                return false;
+            };
+            cache.insert(pattern.to_string(), re);
+        }
+        cache[pattern].is_match(value)
+    })
+}
+
+/// Compare two already-validated int or float values.
+fn compare_numbers(type_name: &str, a: &str, b: &str) -> std::cmp::Ordering {
+    if type_name == "int" {
+        if let (Ok(a), Ok(b)) = (a.parse::<i64>(), b.parse::<i64>()) {
+            return a.cmp(&b);
+        }
+    }
+    let (a, b) = (a.parse::<f64>().unwrap_or(0.0), b.parse::<f64>().unwrap_or(0.0));
+    a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -495,4 +1022,287 @@ mod tests {
         let def = parse_field("normal|n").unwrap();
         assert!(!def.is_inherited);
     }
+
+    #[test]
+    fn test_enum_type() {
+        let def = parse_field("env|e:~enum(dev,staging,prod)").unwrap();
+        assert_eq!(def.type_name, "enum");
+        assert_eq!(def.choices, vec!["dev", "staging", "prod"]);
+        assert_eq!(def.short, Some("e".to_string()));
+    }
+
+    #[test]
+    fn test_enum_with_modifiers() {
+        let def = parse_field("env|e:~enum(dev, prod):!").unwrap();
+        assert_eq!(def.choices, vec!["dev", "prod"]);
+        assert!(def.required);
+    }
+
+    #[test]
+    fn test_enum_choices_keep_modifier_chars() {
+        let def = parse_field("level:~enum(a+b,c!)").unwrap();
+        assert!(def.is_positional);
+        assert_eq!(def.choices, vec!["a+b", "c!"]);
+    }
+
+    #[test]
+    fn test_non_enum_has_no_choices() {
+        let def = parse_field("port|p:~int").unwrap();
+        assert!(def.choices.is_empty());
+    }
+
+    #[test]
+    fn test_error_enum_empty() {
+        let err = parse_field("env|e:~enum()").unwrap_err();
+        assert!(err.message.contains("at least one choice"));
+    }
+
+    #[test]
+    fn test_error_enum_unclosed() {
+        let err = parse_field("env|e:~enum(dev,prod").unwrap_err();
+        assert!(err.message.contains("missing closing parenthesis"));
+    }
+
+    #[test]
+    fn test_error_args_on_non_enum_type() {
+        let err = parse_field("port|p:~int(1)").unwrap_err();
+        assert!(err.message.contains("does not take arguments"));
+    }
+
+    #[test]
+    fn test_env_var_modifier() {
+        let def = parse_field("port|p:~int:$APP_PORT").unwrap();
+        assert_eq!(def.type_name, "int");
+        assert_eq!(def.env_var, Some("APP_PORT".to_string()));
+    }
+
+    #[test]
+    fn test_env_var_stops_type_name() {
+        let def = parse_field("port|p:~int$APP_PORT:!").unwrap();
+        assert_eq!(def.type_name, "int");
+        assert_eq!(def.env_var, Some("APP_PORT".to_string()));
+        assert!(def.required);
+    }
+
+    #[test]
+    fn test_env_var_on_boolean() {
+        let def = parse_field("debug|d:+:$DEBUG").unwrap();
+        assert!(def.is_boolean);
+        assert_eq!(def.env_var, Some("DEBUG".to_string()));
+    }
+
+    #[test]
+    fn test_no_env_var_by_default() {
+        let def = parse_field("port|p:~int").unwrap();
+        assert_eq!(def.env_var, None);
+    }
+
+    #[test]
+    fn test_error_env_var_missing_name() {
+        let err = parse_field("port|p:$").unwrap_err();
+        assert!(err.message.contains("missing environment variable name"));
+    }
+
+    #[test]
+    fn test_error_env_var_duplicate() {
+        let err = parse_field("port|p:$A:$B").unwrap_err();
+        assert!(err.message.contains("already has an environment variable"));
+    }
+
+    #[test]
+    fn test_error_env_var_on_positional() {
+        let err = parse_field("port:$APP_PORT").unwrap_err();
+        assert!(err.message.contains("only supported for flags"));
+    }
+
+    #[test]
+    fn test_int_range() {
+        let def = parse_field("port|p:~int[1..65535]:!").unwrap();
+        assert_eq!(def.type_name, "int");
+        assert_eq!(def.minimum, Some("1".to_string()));
+        assert_eq!(def.maximum, Some("65535".to_string()));
+        assert_eq!(def.range_label(), Some("1..65535".to_string()));
+        assert!(def.required);
+    }
+
+    #[test]
+    fn test_open_ended_ranges() {
+        let def = parse_field("retries|:~int[0..]").unwrap();
+        assert_eq!(def.minimum, Some("0".to_string()));
+        assert!(def.maximum.is_none());
+        let def = parse_field("ratio|:~float[..0.5]").unwrap();
+        assert!(def.minimum.is_none());
+        assert_eq!(def.maximum, Some("0.5".to_string()));
+    }
+
+    #[test]
+    fn test_range_bounds_normalized() {
+        let def = parse_field("n|:~int[007..010]").unwrap();
+        assert_eq!(def.range_label(), Some("7..10".to_string()));
+    }
+
+    #[test]
+    fn test_string_pattern() {
+        let def = parse_field("tag|t:~string/^v[0-9]+$/:$TAG").unwrap();
+        assert_eq!(def.type_name, "string");
+        assert_eq!(def.pattern, Some("^v[0-9]+$".to_string()));
+        assert_eq!(def.env_var, Some("TAG".to_string()));
+    }
+
+    #[test]
+    fn test_pattern_with_escaped_slash_and_pipe() {
+        let def = parse_field("path:~string/^(src|lib)\\/[a-z]+$/").unwrap();
+        assert!(def.is_positional);
+        assert_eq!(def.pattern, Some("^(src|lib)/[a-z]+$".to_string()));
+    }
+
+    #[test]
+    fn test_string_glob() {
+        let def = parse_field("archive|a:~string(*.tar.gz)").unwrap();
+        assert_eq!(def.type_name, "string");
+        assert_eq!(def.glob, Some("*.tar.gz".to_string()));
+        assert_eq!(def.pattern, Some("^.*\\.tar\\.gz$".to_string()));
+        assert!(parse_field("v|:~int(*)").unwrap_err().message.contains("does not take arguments"));
+        assert!(parse_field("v|:~string(*)/x/").unwrap_err().message.contains("both a glob and a pattern"));
+    }
+
+    #[test]
+    fn test_glob_to_regex() {
+        assert_eq!(glob_to_regex("v?.*"), "^v.\\..*$");
+        assert_eq!(glob_to_regex("[!a-c]x"), "^[^a-c]x$");
+        assert_eq!(glob_to_regex("[]x][[:digit:]]"), "^[\\]x][[:digit:]]$");
+        assert_eq!(glob_to_regex("a[b"), "^a\\[b$");
+        assert_eq!(glob_to_regex("\\*"), "^\\*$");
+    }
+
+    #[test]
+    fn test_error_range_on_string() {
+        let err = parse_field("name|:~string[1..3]").unwrap_err();
+        assert!(err.message.contains("does not take a range"));
+    }
+
+    #[test]
+    fn test_error_range_invalid() {
+        assert!(parse_field("n|:~int[a..3]").unwrap_err().message.contains("invalid range bound"));
+        assert!(parse_field("n|:~int[10..1]").unwrap_err().message.contains("invalid range"));
+        assert!(parse_field("n|:~int[..]").unwrap_err().message.contains("invalid range"));
+        assert!(parse_field("n|:~int[1-3]").unwrap_err().message.contains("invalid range"));
+        assert!(parse_field("n|:~int[1..3").unwrap_err().message.contains("missing closing bracket"));
+    }
+
+    #[test]
+    fn test_error_pattern_invalid() {
+        assert!(parse_field("v|:~int/^1/").unwrap_err().message.contains("does not take a pattern"));
+        assert!(parse_field("v|:~string/^v").unwrap_err().message.contains("missing closing slash"));
+        assert!(parse_field("v|:~string/(/").unwrap_err().message.contains("invalid pattern"));
+        assert!(parse_field("v|:~string/a/b").unwrap_err().message.contains("unexpected characters"));
+    }
+
+    #[test]
+    fn test_group_modifier() {
+        let def = parse_field("file|f:~file:%input:!").unwrap();
+        assert_eq!(def.type_name, "file");
+        assert_eq!(def.group, Some("input".to_string()));
+        assert!(def.required);
+        assert!(def.requires.is_empty());
+    }
+
+    #[test]
+    fn test_requires_modifier_repeatable() {
+        let def = parse_field("tls-cert|:~file:&tls-key:&tls-ca").unwrap();
+        assert_eq!(def.requires, vec!["tls-key".to_string(), "tls-ca".to_string()]);
+        assert!(def.group.is_none());
+    }
+
+    #[test]
+    fn test_deprecated_modifier() {
+        let def = parse_field("dry|d:+@deprecated=dry-run").unwrap();
+        assert!(def.is_boolean);
+        assert_eq!(def.deprecated, Some("dry-run".to_string()));
+        let def = parse_field("legacy|:~int@deprecated").unwrap();
+        assert_eq!(def.type_name, "int");
+        assert_eq!(def.deprecated, Some(String::new()));
+        assert!(parse_field("verbose|v:+").unwrap().deprecated.is_none());
+        assert!(parse_field("old|:@obsolete").unwrap_err().message.contains("unknown annotation: @obsolete"));
+        assert!(parse_field("file:@deprecated").unwrap_err().message.contains("only supported for flags"));
+    }
+
+    #[test]
+    fn test_error_group_modifiers() {
+        assert!(parse_field("a|:%").unwrap_err().message.contains("missing group name"));
+        assert!(parse_field("a|:&").unwrap_err().message.contains("missing flag name"));
+        assert!(parse_field("a|:%x:%y").unwrap_err().message.contains("already belongs to a group"));
+        assert!(parse_field("src:%input").unwrap_err().message.contains("only supported for flags"));
+    }
+
+    #[test]
+    fn test_optional_positional() {
+        let def = parse_field("dest:~file:?").unwrap();
+        assert!(def.is_optional);
+        assert_eq!(def.type_name, "file");
+        assert!(!parse_field("src").unwrap().is_optional);
+        assert!(parse_field("out|o:?").unwrap_err().message.contains("only supported for positionals"));
+        assert!(parse_field("dest:?:!").unwrap_err().message.contains("both required and optional"));
+    }
+
+    #[test]
+    fn test_path_new_type() {
+        let def = parse_field("out|o:~path(new)").unwrap();
+        assert_eq!(def.type_name, "path");
+        assert!(def.must_not_exist);
+        assert!(!parse_field("out|o:~path").unwrap().must_not_exist);
+        assert!(parse_field("out|o:~path(old)").unwrap_err().message.contains("does not take arguments"));
+        assert!(parse_field("out|o:~dir(new)").unwrap_err().message.contains("does not take arguments"));
+    }
+
+    #[test]
+    fn test_config_type() {
+        let def = parse_field("config|c:~config").unwrap();
+        assert_eq!(def.type_name, "config");
+        assert!(parse_field("config:~config").unwrap_err().message.contains("only supported for flags"));
+    }
+
+    #[test]
+    fn test_counter_flag() {
+        let def = parse_field("verbose|v:++").unwrap();
+        assert!(def.is_boolean);
+        assert!(def.is_counter);
+        assert!(!parse_field("verbose|v:+").unwrap().is_counter);
+        assert!(parse_field("level:++").unwrap_err().message.contains("only supported for flags"));
+        assert!(parse_field("verbose|v:++:~int").unwrap_err().message.contains("already flagged as boolean"));
+        assert!(def.check_value("3").is_ok());
+        assert_eq!(def.check_value("-1").unwrap_err().message, "invalid count: -1");
+    }
+
+    #[test]
+    fn test_pattern_matches() {
+        assert!(pattern_matches("^\\d+(?i)x$", "12X"));
+        assert!(pattern_matches("^v[0-9]+", "v1.2"));
+        assert!(!pattern_matches("^v[0-9]+", "1.2"));
+        assert!(!pattern_matches("(", "("));
+    }
+
+    #[test]
+    fn test_check_value_range() {
+        let def = parse_field("port|p:~int[1..65535]").unwrap();
+        assert!(def.check_value("8080").is_ok());
+        assert_eq!(def.check_value("0").unwrap_err().message, "out of range (1..65535): 0");
+        assert_eq!(def.check_value("x").unwrap_err().message, "invalid type (int): x");
+        let def = parse_field("ratio|:~float[0..1]").unwrap();
+        assert!(def.check_value("0.25").is_ok());
+        assert!(def.check_value("1.5").is_err());
+    }
+
+    #[test]
+    fn test_check_value_pattern_and_choices() {
+        let def = parse_field("tag|:~string/^v[0-9]+/").unwrap();
+        assert!(def.check_value("v12").is_ok());
+        assert_eq!(def.check_value("12").unwrap_err().message, "does not match pattern (^v[0-9]+): 12");
+        let def = parse_field("file|:~string(*.[ch])").unwrap();
+        assert!(def.check_value("src/main.c").is_ok());
+        assert_eq!(def.check_value("main.rs").unwrap_err().message, "does not match glob (*.[ch]): main.rs");
+        let def = parse_field("env|:~enum(dev,prod)").unwrap();
+        assert!(def.check_value("prod").is_ok());
+        assert!(def.check_value("qa").is_err());
+    }
 }
@@ -16,4 +16,4 @@ pub use document::{
 };
 pub use field::{parse_field, FieldDef, FieldError};
 pub use scope::{Scope, ScopeChain};
-pub use usage::{parse_annotations, parse_usage_entry, UsageEntry};
+pub use usage::{deprecation, parse_annotations, parse_usage_entry, UsageEntry};
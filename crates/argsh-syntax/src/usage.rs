@@ -1,7 +1,7 @@
 //! Parse argsh usage entries.
 //!
-//! Mirrors the usage-entry parsing logic from `builtin/src/usage/mod.rs`
-//! but without any shell FFI — pure text-only parsing.
+//! Pure text-only parsing, without any shell FFI. The builtin
+//! (`builtin/src/usage/mod.rs`) shares the annotation parsing.
 
 /// Parsed usage entry from a usage array.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -24,15 +24,41 @@ pub struct UsageEntry {
     pub line: usize,
 }
 
-/// Parse annotations from a usage entry name.
+impl UsageEntry {
+    /// Replacement named by `@deprecated=<name>` (see [`deprecation`]).
+    pub fn deprecated(&self) -> Option<String> {
+        deprecation(&self.annotations)
+    }
+}
+
+/// Parse `@` annotations from a usage entry.
 ///
 /// Annotations are `@word` suffixes on the entry name, e.g.
 /// `cmd@readonly@destructive` yields `("cmd", vec!["readonly", "destructive"])`.
-pub fn parse_annotations(name: &str) -> (String, Vec<String>) {
-    let parts: Vec<&str> = name.split('@').collect();
-    let clean_name = parts[0].to_string();
-    let annotations: Vec<String> = parts[1..].iter().filter(|s| !s.is_empty()).map(|s| s.to_string()).collect();
-    (clean_name, annotations)
+/// They end at a `:-func` mapping, which is kept:
+/// `old@deprecated=new:-main::new` yields `("old:-main::new", vec!["deprecated=new"])`.
+pub fn parse_annotations(entry: &str) -> (String, Vec<String>) {
+    let Some(at_pos) = entry.find('@') else {
+        return (entry.to_string(), Vec::new());
+    };
+    let end = entry[at_pos..].find(":-").map_or(entry.len(), |p| at_pos + p);
+    let name_part = format!("{}{}", &entry[..at_pos], &entry[end..]);
+    let annotations = entry[at_pos + 1..end]
+        .split('@')
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect();
+    (name_part, annotations)
+}
+
+/// Replacement named by a `@deprecated` / `@deprecated=<name>` annotation:
+/// `None` if not deprecated, an empty string if no replacement is named.
+pub fn deprecation(annotations: &[String]) -> Option<String> {
+    annotations.iter().find_map(|a| match a.split_once('=') {
+        Some(("deprecated", replacement)) => Some(replacement.to_string()),
+        None if a == "deprecated" => Some(String::new()),
+        _ => None,
+    })
 }
 
 /// Parse a usage entry spec like `'cmd|alias:-func@readonly'`.
@@ -57,22 +83,18 @@ pub fn parse_usage_entry(spec: &str) -> UsageEntry {
     let hidden = spec.starts_with('#');
     let work = if hidden { &spec[1..] } else { spec };
 
+    // Annotations may come before or after an explicit `:-func` mapping
+    let (work, annotations) = parse_annotations(work);
+
     // Split off explicit function mapping `:-func`
-    let (name_part, explicit_func) = if let Some(pos) = work.find(":-") {
-        let func_and_rest = &work[pos + 2..];
-        // The function name may itself have annotations
-        let (func_name, _) = parse_annotations(func_and_rest);
-        (&work[..pos], Some(func_name))
-    } else {
-        (work, None)
+    let (name_part, explicit_func) = match work.split_once(":-") {
+        Some((name, func)) => (name, Some(func.to_string())),
+        None => (work.as_str(), None),
     };
 
     // Remove colon-modifiers that are NOT `:-` (field modifiers on usage entries
     // are uncommon but let's strip them to get a clean name).
-    let name_part = name_part.split(':').next().unwrap_or(name_part);
-
-    // Extract annotations from the name part
-    let (clean_name, annotations) = parse_annotations(name_part);
+    let clean_name = name_part.split(':').next().unwrap_or(name_part);
 
     // Split aliases
     let alias_parts: Vec<&str> = clean_name.split('|').collect();
@@ -148,6 +170,30 @@ mod tests {
         assert!(annotations.is_empty());
     }
 
+    #[test]
+    fn test_annotations_with_alias() {
+        let (name, annotations) = parse_annotations("serve|s@readonly");
+        assert_eq!(name, "serve|s");
+        assert_eq!(annotations, vec!["readonly"]);
+    }
+
+    #[test]
+    fn test_annotations_with_explicit_mapping() {
+        let (name, annotations) = parse_annotations("serve:-my_serve@readonly");
+        assert_eq!(name, "serve:-my_serve");
+        assert_eq!(annotations, vec!["readonly"]);
+        let entry = parse_usage_entry("serve:-my_serve@readonly");
+        assert_eq!(entry.explicit_func, Some("my_serve".to_string()));
+        assert_eq!(entry.annotations, vec!["readonly"]);
+    }
+
+    #[test]
+    fn test_annotations_before_mapping() {
+        let (name, annotations) = parse_annotations("deploy@deprecated=release:-main::release");
+        assert_eq!(name, "deploy:-main::release");
+        assert_eq!(annotations, vec!["deprecated=release"]);
+    }
+
     #[test]
     fn test_annotations_in_entry() {
         let entry = parse_usage_entry("deploy@destructive");
@@ -155,6 +201,16 @@ mod tests {
         assert_eq!(entry.annotations, vec!["destructive"]);
     }
 
+    #[test]
+    fn test_deprecated_entry() {
+        let entry = parse_usage_entry("deploy@deprecated=release:-main::release");
+        assert_eq!(entry.name, "deploy");
+        assert_eq!(entry.explicit_func, Some("main::release".to_string()));
+        assert_eq!(entry.deprecated(), Some("release".to_string()));
+        assert_eq!(parse_usage_entry("old@readonly@deprecated").deprecated(), Some(String::new()));
+        assert_eq!(parse_usage_entry("deploy@destructive").deprecated(), None);
+    }
+
     #[test]
     fn test_alias_with_explicit_func() {
         let entry = parse_usage_entry("cmd1:-fmt::args1");
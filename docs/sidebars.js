@@ -847,6 +847,21 @@ module.exports = {
           id: "diagnostics/ag015",
           label: "AG015 — Source path not found",
         },
+        {
+          type: "doc",
+          id: "diagnostics/ag016",
+          label: "AG016 — Invalid default value",
+        },
+        {
+          type: "doc",
+          id: "diagnostics/ag017",
+          label: "AG017 — Required flag not found",
+        },
+        {
+          type: "doc",
+          id: "diagnostics/ag018",
+          label: "AG018 — Deprecated command or flag",
+        },
       ],
     },
     {
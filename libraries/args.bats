@@ -40,9 +40,10 @@ if [[ "${ARGSH_BUILTIN_TEST:-}" == "1" ]]; then
     fi
     # shellcheck disable=SC2229
     if ! enable -f "${_so}" \
-      :usage :usage::help :usage::completion :usage::docgen :usage::mcp :args \
+      :usage :usage::help :usage::complete :usage::completion :usage::docgen :usage::mcp :usage::version :args \
       is::array is::uninitialized is::set is::tty \
       args::field_name to::int to::float to::boolean to::file to::string \
+      to::duration to::size to::url to::dir to::path to::json \
       import import::clear; then
       echo "ERROR: builtin .so failed to load: ${_so}" >&2
       exit 1
@@ -534,6 +535,38 @@ source "${PATH_FIXTURES}/fmt.sh"
   assert "${status}" -eq 1
 }
 
+@test "to: duration converts to seconds" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  result="$(to::duration "1h30m")"
+  assert "${result}" = "5400"
+  result="$(to::duration "90")"
+  assert "${result}" = "90"
+  to::duration "5x" >"${stdout}" 2>"${stderr}" || status=$?
+  assert "${status}" -eq 1
+}
+
+@test "to: size converts to bytes" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  result="$(to::size "10MiB")"
+  assert "${result}" = "10485760"
+  result="$(to::size "10KB")"
+  assert "${result}" = "10000"
+}
+
+@test "to: url, dir, path and json" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  result="$(to::url "https://example.com:8443/x")"
+  assert "${result}" = "https://example.com:8443/x"
+  result="$(to::dir "${BATS_TEST_DIRNAME}")"
+  assert "${result}" = "${BATS_TEST_DIRNAME}"
+  result="$(to::path "/a/./b/../c//d")"
+  assert "${result}" = "/a/c/d"
+  result="$(to::json '{"a": 1}')"
+  assert "${result}" = '{"a": 1}'
+  to::json '{a: 1}' >"${stdout}" 2>"${stderr}" || status=$?
+  assert "${status}" -eq 1
+}
+
 # -----------------------------------------------------------------------------
 # Direct is:: introspection tests
 
@@ -694,6 +727,52 @@ source "${PATH_FIXTURES}/fmt.sh"
   contains "Did you mean 'alias'" stderr
 }
 
+@test "attrs: flag typo suggests closest flag" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local verbose output
+    local -a args=(
+      'verbose|v:+' "Verbose"
+      'output|o'    "Output file"
+    )
+    :args "Suggest test" --verbos
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains "unknown flag: --verbos. Did you mean '--verbose'\\?" stderr
+}
+
+@test "attrs: truncated flag suggests by prefix" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local environment
+    local -a args=(
+      'environment|e' "Environment"
+    )
+    :args "Suggest test" --env=prod
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains "Did you mean '--environment'" stderr
+}
+
+@test "attrs: flag suggestion in json error" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local output
+    local -a args=(
+      'output|o' "Output file"
+    )
+    ARGSH_ERROR_FORMAT=json :args "Suggest test" --outptu x
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 3
+  is_empty stdout
+  contains '"suggestion":"--output"' stderr
+}
+
 # -----------------------------------------------------------------------------
 # Internal type conversion via :args (exercises field::convert_type)
 
@@ -2226,302 +2305,2450 @@ source "${PATH_FIXTURES}/fmt.sh"
   contains "default:.*val1 val2" stdout
 }
 
-# ── completion/man/md/rst/yaml builtin tests ──────────────────────────
-# These are builtin-only features — skip in pure bash mode.
+# ── enum field type ───────────────────────────────────────────────────
 
-@test "usage: completion bash generates script" {
+@test "attrs: enum accepts listed choice" {
   if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
   (
-    :test::usage completion bash
+    local env
+    local -a args=(
+      'env|e:~enum(dev,staging,prod)' "Target environment"
+    )
+    :args "Enum test" --env staging
+    echo "env=${env}"
   ) >"${stdout}" 2>"${stderr}" || status=$?
 
   assert "${status}" -eq 0
   is_empty stderr
-  contains "complete -o default -F" stdout
-  contains "COMPREPLY" stdout
+  contains "env=staging" stdout
 }
 
-@test "usage: completion zsh generates script" {
+@test "attrs: enum rejects unknown choice with suggestion" {
   if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
   (
-    :test::usage completion zsh
+    local env
+    local -a args=(
+      'env|e:~enum(dev,staging,prod)' "Target environment"
+    )
+    :args "Enum test" -e stagin
   ) >"${stdout}" 2>"${stderr}" || status=$?
 
-  assert "${status}" -eq 0
-  is_empty stderr
-  contains "#compdef" stdout
-  contains "_arguments" stdout
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains "invalid choice \\(dev\\|staging\\|prod\\): stagin. Did you mean 'staging'\\?" stderr
 }
 
-@test "usage: completion fish generates script" {
+@test "attrs: enum positional rejects unknown choice" {
   if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
   (
-    :test::usage completion fish
+    local level
+    local -a args=(
+      'level:~enum(low,high)' "Level"
+    )
+    :args "Enum test" extreme
   ) >"${stdout}" 2>"${stderr}" || status=$?
 
-  assert "${status}" -eq 0
-  is_empty stderr
-  contains "complete -c" stdout
+  assert "${status}" -eq 2
+  contains "invalid choice \\(low\\|high\\): extreme" stderr
+  ! grep -q "Did you mean" "${stderr}"
 }
 
-@test "usage: completion --help shows shell list" {
+@test "attrs: enum choices shown in help" {
   if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
   (
-    :test::usage completion --help
+    local env="dev"
+    local -a args=(
+      'env|e:~enum(dev,staging,prod)' "Target environment"
+    )
+    :args "Enum test" --help
   ) >"${stdout}" 2>"${stderr}" || status=$?
 
   assert "${status}" -eq 0
   is_empty stderr
-  contains "bash.*Bash completion" stdout
-  contains "zsh.*Zsh completion" stdout
-  contains "fish.*Fish completion" stdout
+  contains "\\s-e, --env \\{dev\\|staging\\|prod\\} \\(default: dev\\)" stdout
 }
 
-@test "usage: completion invalid shell fails" {
+@test "usage: enum choices in completion scripts" {
   if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
-  (
-    :test::usage completion powershell
-  ) >"${stdout}" 2>"${stderr}" || status=$?
+  local shell
+  for shell in bash zsh fish; do
+    (
+      local env
+      local -a args=(
+        'env|e:~enum(dev,staging,prod)' "Target environment"
+      )
+      local -a usage=(
+        'deploy' "Deploy the app"
+      )
+      :usage "Enum completion test" completion "${shell}"
+      "${usage[@]}"
+    ) >"${stdout}" 2>"${stderr}" || status=$?
 
-  assert "${status}" -ne 0
+    assert "${status}" -eq 0
+    is_empty stderr
+    case "${shell}" in
+      bash) contains "\\s--env\\|-e\\)\\n\\s+COMPREPLY=\\(\\$\\(compgen -W 'dev staging prod'" stdout ;;
+      zsh)  contains ":enum:\\(dev staging prod\\)'" stdout ;;
+      fish) contains "complete -c \\S+ -l 'env' -s 'e' -r -f -a 'dev staging prod'" stdout ;;
+    esac
+  done
 }
 
-@test "usage: docgen man generates troff output" {
+@test "usage: enum choices in llm tool schema" {
   if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
   (
-    :test::usage docgen man
+    local env
+    local -a args=(
+      'env|e:~enum(dev,staging,prod)' "Target environment"
+    )
+    local -a usage=(
+      'deploy' "Deploy the app"
+    )
+    :usage "Enum schema test" docgen llm claude
+    "${usage[@]}"
   ) >"${stdout}" 2>"${stderr}" || status=$?
 
   assert "${status}" -eq 0
   is_empty stderr
-  contains '\.TH' stdout
-  contains '\.SH NAME' stdout
-  contains '\.SH COMMANDS' stdout
-  contains '\.SH OPTIONS' stdout
+  contains '"enum": \["dev", "staging", "prod"\]' stdout
 }
 
-@test "usage: docgen --help shows format list" {
+# ── environment variable fallback ─────────────────────────────────────
+
+@test "attrs: env fallback used when flag is absent" {
   if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
   (
-    :test::usage docgen --help
+    local port=8080
+    local -a args=(
+      'port|p:~int:$APP_PORT' "Port number"
+    )
+    APP_PORT=9090 :args "Env test"
+    echo "port=${port}"
   ) >"${stdout}" 2>"${stderr}" || status=$?
 
   assert "${status}" -eq 0
   is_empty stderr
-  contains "man.*Man page" stdout
-  contains "md.*Markdown" stdout
-  contains "rst.*reStructuredText" stdout
-  contains "yaml.*YAML" stdout
+  contains "port=9090" stdout
 }
 
-@test "usage: docgen md generates markdown output" {
+@test "attrs: command line flag wins over env fallback" {
   if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
   (
-    :test::usage docgen md
+    local port
+    local -a args=(
+      'port|p:~int:$APP_PORT' "Port number"
+    )
+    APP_PORT=9090 :args "Env test" --port 7070
+    echo "port=${port}"
   ) >"${stdout}" 2>"${stderr}" || status=$?
 
   assert "${status}" -eq 0
   is_empty stderr
-  contains '## Synopsis' stdout
-  contains '## Commands' stdout
-  contains '## Options' stdout
-  contains '\| Command \| Description \|' stdout
+  contains "port=7070" stdout
 }
 
-@test "usage: docgen rst generates restructuredtext output" {
+@test "attrs: env fallback satisfies required flag" {
   if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
   (
-    :test::usage docgen rst
+    local token
+    local -a args=(
+      'token|t:!:$APP_TOKEN' "API token"
+    )
+    APP_TOKEN=secret :args "Env test"
+    echo "token=${token}"
   ) >"${stdout}" 2>"${stderr}" || status=$?
 
   assert "${status}" -eq 0
   is_empty stderr
-  contains 'Synopsis' stdout
-  contains '\.\. code-block:: bash' stdout
-  contains 'Commands' stdout
-  contains 'Options' stdout
+  contains "token=secret" stdout
 }
 
-@test "usage: docgen yaml generates yaml output" {
+@test "attrs: env fallback for boolean flag" {
   if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
   (
-    :test::usage docgen yaml
+    local debug
+    local -a args=(
+      'debug|d:+:$APP_DEBUG' "Debug mode"
+    )
+    APP_DEBUG=true :args "Env test"
+    echo "debug=${debug}"
   ) >"${stdout}" 2>"${stderr}" || status=$?
 
   assert "${status}" -eq 0
   is_empty stderr
-  contains 'name:' stdout
-  contains 'description:' stdout
-  contains 'commands:' stdout
-  contains 'options:' stdout
+  contains "debug=1" stdout
 }
 
-@test "usage: docgen invalid format fails" {
+@test "attrs: env fallback for boolean flag takes switch spellings" {
   if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
   (
-    :test::usage docgen html
+    local debug
+    local -a args=(
+      'debug|d:+:$APP_DEBUG' "Debug mode"
+    )
+    APP_DEBUG=off :args "Env test"
+    echo "debug=${debug}"
+    APP_DEBUG=ture :args "Env test"
   ) >"${stdout}" 2>"${stderr}" || status=$?
 
-  assert "${status}" -ne 0
+  assert "${status}" -eq 2
+  contains "^debug=0\n$" stdout
+  contains "invalid type \\(boolean\\): ture \\(from \\\$APP_DEBUG\\)" stderr
 }
 
-@test "usage: completion includes subcommands from usage array" {
+@test "attrs: env fallback for counter flag takes a count" {
   if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
   (
-    :test::usage completion bash
+    local verbose=0
+    local -a args=(
+      'verbose|v:++:$APP_V' "Verbosity"
+    )
+    APP_V=3 :args "Env test"
+    echo "verbose=${verbose}"
+    APP_V=lots :args "Env test"
   ) >"${stdout}" 2>"${stderr}" || status=$?
 
-  assert "${status}" -eq 0
-  contains "cmd1" stdout
-  contains "cmd2" stdout
+  assert "${status}" -eq 2
+  contains "^verbose=3\n$" stdout
+  contains "invalid count: lots \\(from \\\$APP_V\\)" stderr
 }
 
-@test "usage: completion includes flags from args array" {
+@test "attrs: env fallback value is type checked" {
   if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
   (
-    :test::usage completion bash
+    local port
+    local -a args=(
+      'port|p:~int:$APP_PORT' "Port number"
+    )
+    APP_PORT=abc :args "Env test"
   ) >"${stdout}" 2>"${stderr}" || status=$?
 
-  assert "${status}" -eq 0
-  contains "\-\-verbose" stdout
-  contains "\-\-config" stdout
-  contains "\-\-help" stdout
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains "invalid type \\(int\\): abc \\(from \\\$APP_PORT\\)" stderr
 }
 
-# ── coverage: no visible subcommands + long-only flag ────────────────
-# These tests exercise branches unreachable from :test::usage (which has
-# subcommands and flags with short options):
-#   - Flag without short option (flag.short == None)
-#   - Empty subcommands list (cmds.is_empty() == true)
-#   - Multi-line title with blank line (man .PP path)
-
-@test "usage: completion bash with no subcommands and long-only flag" {
+@test "attrs: env fallback shown in help" {
   if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
   (
-    :test::nosub completion bash
+    local port=8080
+    local -a args=(
+      'port|p:~int:$APP_PORT' "Port number"
+    )
+    :args "Env test" --help
   ) >"${stdout}" 2>"${stderr}" || status=$?
 
   assert "${status}" -eq 0
   is_empty stderr
-  contains "complete -o default -F" stdout
-  contains "\-\-longonly" stdout
-  contains "\-\-help" stdout
+  contains "\\s-p, --port int \\(env: APP_PORT\\) \\(default: 8080\\)" stdout
 }
 
-@test "usage: completion zsh with no subcommands and long-only flag" {
+# ── config file defaults ──────────────────────────────────────────────
+
+@test "attrs: config file supplies flag defaults" {
   if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  local conf
+  conf="$(mktemp)"
+  printf '%s\n' '# presets' 'region = "eu-west-1"' 'retries = 3' 'dry-run = true' 'tag = [a, b]' >"${conf}"
   (
-    :test::nosub completion zsh
+    local config region retries=1 dry_run
+    local -a tag
+    local -a args=(
+      'config|c:~config' "Config file"
+      'region|r' "Region"
+      'retries|:~int' "Retries"
+      'dry-run|:+' "Dry run"
+      'tag|t' "Tags"
+    )
+    :args "Config test" --config "${conf}"
+    echo "region=${region} retries=${retries} dry_run=${dry_run} tag=${tag[*]}"
   ) >"${stdout}" 2>"${stderr}" || status=$?
+  rm -f "${conf}"
 
   assert "${status}" -eq 0
   is_empty stderr
-  contains "#compdef" stdout
-  contains "\-\-longonly" stdout
-  # No _describe/commands block when subcommands are empty
+  contains "region=eu-west-1 retries=3 dry_run=1 tag=a b" stdout
 }
 
-@test "usage: completion fish with no subcommands and long-only flag" {
+@test "attrs: command line and env win over config file" {
   if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  local conf
+  conf="$(mktemp)"
+  printf '%s\n' '{"region": "eu-west-1", "retries": 3}' >"${conf}"
   (
-    :test::nosub completion fish
+    local config region retries
+    local -a args=(
+      'config|c:~config' "Config file"
+      'region|r' "Region"
+      'retries|:~int:$APP_RETRIES' "Retries"
+    )
+    APP_RETRIES=5 :args "Config test" -c "${conf}" --region us-east-1
+    echo "region=${region} retries=${retries}"
   ) >"${stdout}" 2>"${stderr}" || status=$?
+  rm -f "${conf}"
 
   assert "${status}" -eq 0
   is_empty stderr
-  contains "complete -c" stdout
-  contains "longonly" stdout
+  contains "region=us-east-1 retries=5" stdout
 }
 
-@test "usage: docgen man with no subcommands and long-only flag" {
+@test "attrs: default config path is read only if present" {
   if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
   (
-    :test::nosub docgen man
+    local config="/nonexistent/.apprc" region=default
+    local -a args=(
+      'config|c:~config' "Config file"
+      'region|r' "Region"
+    )
+    :args "Config test"
+    echo "region=${region}"
   ) >"${stdout}" 2>"${stderr}" || status=$?
 
   assert "${status}" -eq 0
   is_empty stderr
-  contains '\.TH' stdout
-  contains '\.SH OPTIONS' stdout
-  # Long-only non-boolean flag (no short option)
-  contains '\\-\\-longonly.*string' stdout
-  # Multi-line title with blank line produces .PP
-  contains '\.PP' stdout
+  contains "region=default" stdout
 }
 
-@test "usage: docgen md with no subcommands and long-only flag" {
+@test "attrs: explicit config file must exist" {
   if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
   (
-    :test::nosub docgen md
+    local config
+    local -a args=(
+      'config|c:~config' "Config file"
+    )
+    :args "Config test" --config /nonexistent/app.toml
   ) >"${stdout}" 2>"${stderr}" || status=$?
 
-  assert "${status}" -eq 0
-  is_empty stderr
-  contains '## Synopsis' stdout
-  contains '## Options' stdout
-  # No ## Commands section
-  contains '\-\-longonly' stdout
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains "config file not found: /nonexistent/app.toml" stderr
 }
 
-@test "usage: docgen rst with no subcommands and long-only flag" {
+@test "attrs: json error for config file names the config flag" {
   if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  local conf
+  conf="$(mktemp)"
+  printf '%s\n' 'not a setting' >"${conf}"
   (
-    :test::nosub docgen rst
+    local config
+    local -a args=(
+      'config|c:~config' "Config file"
+    )
+    ARGSH_ERROR_FORMAT=json :args "Config test" --config "${conf}"
   ) >"${stdout}" 2>"${stderr}" || status=$?
+  rm -f "${conf}"
 
-  assert "${status}" -eq 0
-  is_empty stderr
-  contains 'Synopsis' stdout
-  contains 'Options' stdout
-  contains '\-\-longonly' stdout
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains '"field":"config\|c:~config"' stderr
 }
 
-@test "usage: docgen yaml with no subcommands and long-only flag" {
+@test "attrs: config value error names file and key" {
   if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  local conf
+  conf="$(mktemp)"
+  printf '%s\n' 'retries = many' >"${conf}"
   (
-    :test::nosub docgen yaml
+    local config="${conf}" retries
+    local -a args=(
+      'config|c:~config' "Config file"
+      'retries|:~int' "Retries"
+    )
+    :args "Config test"
   ) >"${stdout}" 2>"${stderr}" || status=$?
+  rm -f "${conf}"
 
-  assert "${status}" -eq 0
-  is_empty stderr
-  contains 'name:' stdout
-  contains 'options:' stdout
-  contains 'longonly' stdout
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains "invalid type \\(int\\): many \\(from ${conf}: retries\\)" stderr
 }
 
-@test "usage: docgen yaml with group separators in usage array" {
+# ── structured errors ─────────────────────────────────────────────────
+
+@test "attrs: json error for unknown flag" {
   if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
   (
-    :test::fmt1 docgen yaml
+    local -a args=()
+    ARGSH_ERROR_FORMAT=json :args "Error test" --bogus
   ) >"${stdout}" 2>"${stderr}" || status=$?
 
-  assert "${status}" -eq 0
-  is_empty stderr
-  contains 'commands:' stdout
-  # Group separators ('-') should be filtered, only real commands listed
-  contains 'cmd1' stdout
-  contains 'noop' stdout
+  assert "${status}" -eq 3
+  is_empty stdout
+  contains '"error":"unknown_flag"' stderr
+  contains '"value":"--bogus"' stderr
+  contains '"exit_code":3' stderr
 }
 
-# ── LLM tool schema tests ──────────────────────────────────────────
-
-@test "usage: docgen llm claude generates anthropic tool schema" {
+@test "attrs: json error for invalid value" {
   if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
   (
-    :test::usage docgen llm claude
+    local env
+    local -a args=(
+      'env|e:~enum(dev,prod)' "Environment"
+    )
+    ARGSH_ERROR_FORMAT=json :args "Error test" --env prd
   ) >"${stdout}" 2>"${stderr}" || status=$?
 
-  assert "${status}" -eq 0
-  is_empty stderr
-  contains '"input_schema":' stdout
-  contains '"properties":' stdout
-  contains '"verbose":' stdout
+  assert "${status}" -eq 5
+  is_empty stdout
+  contains '"error":"invalid_value"' stderr
+  contains '"field":"env\|e:~enum\(dev,prod\)"' stderr
+  contains '"suggestion":"prod"' stderr
 }
 
-@test "usage: docgen llm openai generates openai tool schema" {
+@test "attrs: json error for missing required flag" {
   if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
   (
-    :test::usage docgen llm openai
+    local token
+    local -a args=(
+      'token|t:!' "API token"
+    )
+    ARGSH_ERROR_FORMAT=json :args "Error test"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 6
+  is_empty stdout
+  contains '"error":"missing_required"' stderr
+}
+
+@test "usage: json error for unknown command" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    ARGSH_ERROR_FORMAT=json :test::usage cm1
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 7
+  is_empty stdout
+  contains '"error":"unknown_command"' stderr
+  contains '"suggestion":"cmd1"' stderr
+}
+
+# ── flag prefix matching ──────────────────────────────────────────────
+
+@test "attrs: unique flag prefix with ARGSH_FLAG_PREFIX" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local ARGSH_FLAG_PREFIX=1
+    local verbose output
+    local -a args=(
+      'verbose|v:+' "Verbose"
+      'output|o'    "Output file"
+    )
+    :args "Prefix test" --verb --out=result.txt
+    echo "verbose=${verbose} output=${output}"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "verbose=1 output=result.txt" stdout
+}
+
+@test "attrs: ambiguous flag prefix lists candidates" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local ARGSH_FLAG_PREFIX=1
+    local verbose version
+    local -a args=(
+      'verbose|v:+' "Verbose"
+      'version|:+'  "Version"
+    )
+    :args "Prefix test" --ver
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains "ambiguous flag: --ver \\(could be --verbose, --version\\)" stderr
+}
+
+@test "attrs: flag prefix is off by default" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local verbose
+    local -a args=(
+      'verbose|v:+' "Verbose"
+    )
+    :args "Prefix test" --verb
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains "unknown flag: --verb" stderr
+}
+
+@test "attrs: negated flag prefix with ARGSH_FLAG_PREFIX" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local ARGSH_FLAG_PREFIX=1
+    local color=1
+    local -a args=(
+      'color|:+' "Colored output"
+    )
+    :args "Prefix test" --no-col
+    echo "color=${color}"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "color=0" stdout
+}
+
+@test "attrs: flag prefix ignores an exported ARGSH_FLAG_PREFIX" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    export ARGSH_FLAG_PREFIX=1
+    local verbose
+    local -a args=(
+      'verbose|v:+' "Verbose"
+    )
+    :args "Prefix test" --verb
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains "unknown flag: --verb" stderr
+}
+
+# ── deprecated flags and commands ─────────────────────────────────────
+
+@test "attrs: deprecated flag still works and warns" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local dry_run dry
+    local -a args=(
+      'dry-run|n:+'               "Dry run"
+      'dry|:+@deprecated=dry-run' "Old name"
+    )
+    :args "Deprecated test" --dry
+    echo "dry=${dry}"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  contains "dry=1" stdout
+  contains "Warning: --dry is deprecated, use --dry-run instead" stderr
+}
+
+@test "attrs: deprecated flag hidden from help" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local dry_run dry
+    local -a args=(
+      'dry-run|n:+'       "Dry run"
+      'dry|:+@deprecated' "Old name"
+    )
+    :args "Deprecated test" --help
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  contains "\\-\\-dry-run" stdout
+  not_contains "Old name" stdout
+}
+
+@test "usage: deprecated command still dispatches and warns" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    release() { echo "released"; }
+    local -a usage=(
+      'release'                            "Publish a release"
+      'deploy@deprecated=release:-release' "Old name of release"
+    )
+    local -a args=()
+    :usage "Deprecated test" deploy
+    "${usage[@]}"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  contains "released" stdout
+  contains "Warning: deploy is deprecated, use release instead" stderr
+}
+
+@test "usage: deprecated command hidden from help" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local -a usage=(
+      'release'                   "Publish a release"
+      'deploy@deprecated=release' "Old name of release"
+    )
+    local -a args=()
+    :usage "Deprecated test" --help
+    "${usage[@]}"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  contains "Publish a release" stdout
+  not_contains "Old name of release" stdout
+}
+
+@test "usage: docgen md badges deprecated command" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    release() { :; }
+    deploy() { :; }
+    tool() {
+      local -a usage=(
+        'release'                   "Publish a release"
+        'deploy@deprecated=release' "Old name of release"
+      )
+      :usage "Deprecated test" "${@}"
+      "${usage[@]}"
+    }
+    tool docgen md
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains '\| `deploy` \| \[deprecated: use release\] Old name of release \|' stdout
+}
+
+# ── range and pattern constraints ─────────────────────────────────────
+
+@test "attrs: int range accepts value within bounds" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local port
+    local -a args=(
+      'port|p:~int[1..65535]' "Port number"
+    )
+    :args "Range test" --port 65535
+    echo "port=${port}"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "port=65535" stdout
+}
+
+@test "attrs: int range rejects value out of bounds" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local port
+    local -a args=(
+      'port|p:~int[1..65535]' "Port number"
+    )
+    :args "Range test" --port 0
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains "out of range \\(1..65535\\): 0" stderr
+}
+
+@test "attrs: float range with open upper bound" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local ratio
+    local -a args=(
+      'ratio:~float[0.5..]' "Sampling ratio"
+    )
+    :args "Range test" 0.25
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains "out of range \\(0.5..\\): 0.25" stderr
+}
+
+@test "attrs: string pattern accepts matching value" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local tag
+    local -a args=(
+      'tag|t:~string/^v[0-9]+(\.[0-9]+)*$/' "Release tag"
+    )
+    :args "Pattern test" --tag v1.2
+    echo "tag=${tag}"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "tag=v1.2" stdout
+}
+
+@test "attrs: string pattern rejects non-matching value" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local tag
+    local -a args=(
+      'tag|t:~string/^v[0-9]+(\.[0-9]+)*$/' "Release tag"
+    )
+    :args "Pattern test" --tag 1.2
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains "does not match pattern \\(\\^v\\[0-9\\]\\+" stderr
+}
+
+@test "attrs: string glob matches the whole value" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local archive
+    local -a args=(
+      'archive|a:~string(*.tar.gz)' "Release archive"
+    )
+    :args "Glob test" --archive build.tar.gz
+    echo "archive=${archive}"
+    :args "Glob test" --archive build.tar.gz.sig
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  contains "archive=build.tar.gz" stdout
+  contains "does not match glob \\(\\*.tar.gz\\): build.tar.gz.sig" stderr
+}
+
+@test "attrs: invalid range in field definition" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local port
+    local -a args=(
+      'port|p:~int[10..1]' "Port number"
+    )
+    :args "Range test" --port 5
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains "invalid range: \\[10..1\\]" stderr
+}
+
+@test "attrs: range shown in help" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local port=8080
+    local -a args=(
+      'port|p:~int[1..65535]' "Port number"
+    )
+    :args "Range test" --help
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "\\s-p, --port int\\[1..65535\\] \\(default: 8080\\)" stdout
+}
+
+@test "usage: range and pattern in llm tool schema" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local port tag
+    local -a args=(
+      'port|p:~int[1..65535]' "Port number"
+      'tag|t:~string/^v/' "Release tag"
+    )
+    local -a usage=(
+      'deploy' "Deploy the app"
+    )
+    :usage "Constraint schema test" docgen llm claude
+    "${usage[@]}"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains '"minimum": 1,\s+"maximum": 65535,' stdout
+  contains '"pattern": "\^v",' stdout
+}
+
+# ── flag groups ───────────────────────────────────────────────────────
+
+@test "attrs: exclusive group rejects more than one flag" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local json yaml
+    local -a args=(
+      'json|:+:%format' "Output JSON"
+      'yaml|:+:%format' "Output YAML"
+    )
+    :args "Group test" --json --yaml
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains "flags cannot be used together: --json, --yaml" stderr
+}
+
+@test "attrs: json error for exclusive group names the flag" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local json yaml
+    local -a args=(
+      'json|:+:%format' "Output JSON"
+      'yaml|:+:%format' "Output YAML"
+    )
+    ARGSH_ERROR_FORMAT=json :args "Group test" --json --yaml
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains '"field":"yaml\|:\+:%format"' stderr
+}
+
+@test "attrs: exclusive group allows a single flag" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local json yaml
+    local -a args=(
+      'json|:+:%format' "Output JSON"
+      'yaml|:+:%format' "Output YAML"
+    )
+    :args "Group test" --yaml
+    echo "json=${json} yaml=${yaml}"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "json=0 yaml=1" stdout
+}
+
+@test "attrs: env and config booleans switched off do not count in a group" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  local conf
+  conf="$(mktemp)"
+  printf '%s\n' 'json = false' >"${conf}"
+  (
+    local config json yaml
+    local -a args=(
+      'config|c:~config' "Config file"
+      'json|:+:%format:$APP_JSON' "Output JSON"
+      'yaml|:+:%format' "Output YAML"
+    )
+    ( APP_JSON=false :args "Group test" --yaml; echo "env: json=${json} yaml=${yaml}" )
+    ( :args "Group test" -c "${conf}" --yaml; echo "config: json=${json} yaml=${yaml}" )
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+  rm -f "${conf}"
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "env: json=0 yaml=1" stdout
+  contains "config: json=0 yaml=1" stdout
+}
+
+@test "attrs: required group needs exactly one member" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local file url
+    local -a args=(
+      'file|f:%input:!' "Read from file"
+      'url|u:%input:!' "Read from URL"
+    )
+    :args "Group test"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains "missing required flag: one of --file, --url" stderr
+}
+
+@test "attrs: required group satisfied by any member" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local file url
+    local -a args=(
+      'file|f:%input:!' "Read from file"
+      'url|u:%input:!' "Read from URL"
+    )
+    :args "Group test" --url https://example.com
+    echo "url=${url}"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "url=https://example.com" stdout
+}
+
+@test "attrs: flag requires another flag" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local tls_cert tls_key
+    local -a args=(
+      'tls-cert|:&tls-key' "Client certificate"
+      'tls-key|' "Client key"
+    )
+    :args "Group test" --tls-cert cert.pem
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains "\\s--tls-cert requires --tls-key" stderr
+}
+
+@test "attrs: flag groups shown in help" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local file url tls_cert tls_key
+    local -a args=(
+      'file|f:%input:!' "Read from file"
+      'url|u:%input:!' "Read from URL"
+      'tls-cert|:&tls-key' "Client certificate"
+      'tls-key|' "Client key"
+    )
+    :args "Group test" --help
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "Constraints:\\n  exactly one of --file, --url\\n  --tls-cert requires --tls-key" stdout
+}
+
+# ── variadic and optional positionals ─────────────────────────────────
+
+@test "attrs: variadic positional leaves room for trailing positional" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local dest
+    local -a src
+    local -a args=(
+      'src:!' "Source files"
+      'dest' "Destination"
+    )
+    :args "Copy" a b c
+    echo "src=${src[*]} dest=${dest}"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "src=a b dest=c" stdout
+}
+
+@test "attrs: variadic positional reports arity" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local dest
+    local -a src
+    local -a args=(
+      'src:!' "Source files"
+      'dest' "Destination"
+    )
+    :args "Copy" a
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains "expected at least 2 arguments, got 1" stderr
+}
+
+@test "attrs: optional variadic positional may be empty" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local cmd
+    local -a rest
+    local -a args=(
+      'cmd' "Command"
+      'rest' "Arguments"
+    )
+    :args "Run" ls
+    echo "cmd=${cmd} rest=${#rest[@]}"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "cmd=ls rest=0" stdout
+}
+
+@test "attrs: optional positional may be omitted" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local src dest
+    local -a args=(
+      'src' "Source"
+      'dest:?' "Destination"
+    )
+    :args "Copy" a
+    echo "src=${src} dest=${dest:-unset}"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "src=a dest=unset" stdout
+}
+
+@test "attrs: only one variadic positional is allowed" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local -a one two
+    local -a args=(
+      'one' "First"
+      'two' "Second"
+    )
+    :args "Variadic test" a
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains "only one variadic positional is allowed" stderr
+}
+
+@test "attrs: variadic positionals in help usage" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local dest
+    local -a src
+    local -a args=(
+      'src:!' "Source files"
+      'dest:?' "Destination"
+    )
+    :args "Copy" --help
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "<src>... \\[dest\\]" stdout
+}
+
+# ── flag terminator and parse mode ────────────────────────────────────
+
+@test "attrs: -- ends flag parsing" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local verbose
+    local -a files
+    local -a args=(
+      'files'       "Files"
+      'verbose|v:+' "Verbose"
+    )
+    :args "Terminator test" -v -- -v --help
+    echo "verbose=${verbose} files=${files[*]}"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "verbose=1 files=-v --help" stdout
+}
+
+@test "attrs: values after -- go to the rest field" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local verbose context
+    local -a rest
+    local -a args=(
+      'context:?'   "Context"
+      'rest'        "Command"
+      'verbose|v:+' "Verbose"
+    )
+    :args "Exec test" -v -- kubectl get pods -o yaml
+    echo "verbose=${verbose} context=${context:-unset} rest=${#rest[@]}: ${rest[*]}"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "verbose=1 context=unset rest=5: kubectl get pods -o yaml" stdout
+}
+
+@test "attrs: a rest array outside args does not take values after --" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local name
+    local -a rest
+    local -a args=(
+      'name' "Name"
+    )
+    :args "Exec test" -- a b
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains "too many arguments: b" stderr
+}
+
+@test "attrs: gnu mode accepts flags after positionals" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local verbose
+    local -a files
+    local -a args=(
+      'files'       "Files"
+      'verbose|v:+' "Verbose"
+    )
+    :args "Mode test" a -v b
+    echo "verbose=${verbose} files=${files[*]}"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "verbose=1 files=a b" stdout
+}
+
+@test "attrs: posix mode stops flag parsing at the first positional" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local verbose=0
+    local -a files
+    local -a args=(
+      'files'       "Files"
+      'verbose|v:+' "Verbose"
+    )
+    local ARGSH_PARSE_MODE=posix
+    :args "Mode test" a -v b
+    echo "verbose=${verbose} files=${files[*]}"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "verbose=0 files=a -v b" stdout
+}
+
+@test "attrs: invalid parse mode" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local ARGSH_PARSE_MODE=strict
+    local -a args=()
+    :args "Mode test"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains "invalid ARGSH_PARSE_MODE: strict \\(expected gnu or posix\\)" stderr
+}
+
+@test "attrs: exported ARGSH_PARSE_MODE is ignored" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    export ARGSH_PARSE_MODE=posix
+    local verbose=0
+    local -a files
+    local -a args=(
+      'files'       "Files"
+      'verbose|v:+' "Verbose"
+    )
+    :args "Mode test" a -v b
+    echo "verbose=${verbose} files=${files[*]}"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "verbose=1 files=a b" stdout
+}
+
+# ── help colors, wrapping and examples ────────────────────────────────
+
+@test "attrs: help shows examples block" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local name
+    local -a args=(
+      'name' "Name"
+    )
+    local -a examples=(
+      'world'  "Greet the world"
+      '--help' ""
+    )
+    COMMANDNAME=(greet)
+    :args "Greet someone" --help
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "Examples:\n  # Greet the world\n  greet world\n  greet --help\n" stdout
+}
+
+@test "usage: help shows examples block" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local -a usage=(
+      'deploy' "Deploy the app"
+    )
+    local -a examples=(
+      'deploy --env prod' "Deploy to production"
+    )
+    local -a args=()
+    COMMANDNAME=(app)
+    :usage "App" --help
+    "${usage[@]}"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "Examples:\n  # Deploy to production\n  app deploy --env prod\n\nUse" stdout
+}
+
+@test "usage: subcommand help does not show the parent examples" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    deploy() {
+      local env
+      local -a args=(
+        'env' "Environment"
+      )
+      :args "Deploy the app" "${@}"
+    }
+    app() {
+      local -a usage=(
+        'deploy' "Deploy the app"
+      )
+      local -a examples=(
+        'deploy prod' "Deploy to production"
+      )
+      :usage "App" "${@}"
+      "${usage[@]}"
+    }
+    COMMANDNAME=(app)
+    app deploy --help
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "Deploy the app" stdout
+  not_contains "Examples:" stdout
+}
+
+@test "attrs: help is plain when piped, colored with FORCE_COLOR" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local verbose
+    local -a args=(
+      'verbose|v:+' "Verbose"
+    )
+    ( :args "Color test" --help )
+    echo "---"
+    ( FORCE_COLOR=1 :args "Color test" --help )
+    echo "---"
+    ( FORCE_COLOR=1 NO_COLOR=1 :args "Color test" --help )
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains '^[^\x1b]*---' stdout
+  contains '\x1b\[1mOptions:\x1b\[0m\n\x1b\[36m   -v, --verbose' stdout
+  contains '\n---\n[^\x1b]*$' stdout
+}
+
+@test "attrs: help wraps descriptions to COLUMNS" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local verbose
+    local -a args=(
+      'verbose|v:+' "Print every step of the deployment as it happens"
+    )
+    COLUMNS=40 :args "Wrap test" --help
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "           Print every step of the\n           deployment as it happens\n" stdout
+}
+
+@test "usage: docgen md includes examples" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    deploy() { :; }
+    app() {
+      local -a usage=(
+        'deploy' "Deploy the app"
+      )
+      local -a examples=(
+        'deploy --env prod' "Deploy to production"
+      )
+      :usage "App" "${@}"
+      "${usage[@]}"
+    }
+    COMMANDNAME=(app)
+    app docgen md
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains '## Examples\n\nDeploy to production\n\n```bash\napp deploy --env prod\n```' stdout
+}
+
+# ── help command tree ─────────────────────────────────────────────────
+
+:test::tree() {
+  remote::add() {
+    local name url fetch=1
+    local -a args=(
+      'name'      "Remote name"
+      'url'       "Remote URL"
+      'fetch|f:+' "Fetch after adding"
+    )
+    :args "Add a remote" "${@}"
+  }
+  remote::remove() { :; }
+  remote() {
+    local -a usage=(
+      'add'    "Add a remote"
+      'remove' "Remove a remote"
+    )
+    :usage "Manage remotes" "${@}"
+    "${usage[@]}"
+  }
+  log() { :; }
+  prune() { :; }
+  tool() {
+    local verbose
+    local -a args=(
+      'verbose|v:+' "Verbose output"
+    )
+    local -a usage=(
+      'remote'                     "Manage remotes"
+      'log'                        "Show the log"
+      'prune'                      "Prune stale data"
+      '#debug'                     "Hidden debugging"
+      'gc@deprecated=prune:-prune' "Old name of prune"
+    )
+    :usage "Tool" "${@}"
+    "${usage[@]}"
+  }
+  COMMANDNAME=(tool)
+  tool "${@}"
+}
+
+@test "usage: --help-all renders the nested command tree" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::tree --help-all
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "Usage: tool <command> \[args\]\n\nOptions:\n   -v, --verbose" stdout
+  contains "Commands:\n\ntool remote <command>\n  Manage remotes\n\ntool remote add <name> <url>\n  Add a remote\n  Arguments:\n" stdout
+  contains "  Options:\n    -f, --\\[no-\\]fetch +Fetch after adding\n\ntool remote remove\n" stdout
+  contains "tool log\n  Show the log\n  Options:\n    -v, --verbose +Verbose output\n" stdout
+}
+
+@test "usage: --help-all leaves out hidden and deprecated commands" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::tree --help-all
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  not_contains "debug" stdout
+  not_contains "tool gc" stdout
+}
+
+@test "usage: help <command> shows that part of the tree" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::tree help remote add
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "^Add a remote\n\nUsage: tool remote add <name> <url>\n\n  Arguments:\n    name string +Remote name\n" stdout
+
+  (
+    :test::tree help remote
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  contains "^Manage remotes\n\nUsage: tool remote <command>\n\nCommands:\n\ntool remote add <name> <url>\n" stdout
+}
+
+@test "usage: help without a command shows the usual help" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::tree help
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "Available Commands:" stdout
+}
+
+@test "usage: help with an unknown command suggests the closest" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::tree help remote ad
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  contains "Invalid command: ad. Did you mean 'add'?" stderr
+}
+
+# ── version command ───────────────────────────────────────────────────
+
+:test::version() {
+  local -a usage=(
+    'deploy' "Deploy the app"
+  )
+  COMMANDNAME=(tool)
+  :usage "Version test" "${@}"
+  "${usage[@]}"
+}
+
+@test "usage: version and --version print the script version" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    ARGSH_APP_VERSION="1.2.0" ARGSH_APP_COMMIT="abc1234" ARGSH_VERSION="0.6.0"
+    ( :test::version version )
+    ( :test::version --version )
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "^tool 1.2.0 \(abc1234\)\nargsh 0.6.0\ntool 1.2.0 \(abc1234\)\nargsh 0.6.0\n$" stdout
+}
+
+@test "usage: version --json prints an object" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    VERSION="2.0.0" ARGSH_APP_COMMIT="abc1234" ARGSH_VERSION="unknown"
+    :test::version --version --json
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains '^\{"name":"tool","version":"2.0.0","commit":"abc1234","argsh":null\}\n$' stdout
+}
+
+@test "usage: version rejects unknown flags" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::version version --yaml
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  contains "unknown flag: --yaml" stderr
+}
+
+@test "usage: a declared version flag takes precedence over --version" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    deploy() { echo "deploy version=${version}"; }
+    local version
+    local -a args=(
+      'version|:+' "Own version flag"
+    )
+    ARGSH_APP_VERSION="1.2.0"
+    :test::version --version deploy
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "deploy version=1" stdout
+}
+
+# ── validation hooks ──────────────────────────────────────────────────
+
+:test::scale() {
+  local replicas max=10
+  local -a tags=()
+  local -a args=(
+    'replicas|r:~int' "Number of replicas"
+    'max|m:~int'      "Upper limit for replicas"
+    'tags|t'          "Tags"
+  )
+  :args "Scale" "${@}"
+  echo "replicas=${replicas:-} max=${max} tags=${#tags[@]}"
+}
+:test::scale::validate::replicas() {
+  (( ${1} > 0 )) || { echo "replicas must be positive" >&2; return 1; }
+}
+:test::scale::validate::tags() {
+  (( ${#} < 3 ))
+}
+:test::scale::validate() {
+  [[ -z "${replicas:-}" ]] || (( replicas <= max )) ||
+    { echo "--replicas (${replicas}) must not exceed --max (${max})" >&2; return 1; }
+}
+
+@test "validate: hooks accept valid values" {
+  (
+    :test::scale -r 20 --max 30 -t a -t b
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "replicas=20 max=30 tags=2" stdout
+}
+
+@test "validate: field hook stderr becomes the error" {
+  (
+    :test::scale -r 0
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains "Error: replicas must be positive" stderr
+  contains "for more information" stderr
+}
+
+@test "validate: silent field hook gets a default message" {
+  (
+    :test::scale -t a -t b -t c
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  contains "invalid value for tags: a b c" stderr
+}
+
+@test "validate: cross-field hook sees all parsed values" {
+  (
+    :test::scale -r 20
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains "Error: --replicas \(20\) must not exceed --max \(10\)" stderr
+}
+
+@test "validate: hook errors follow ARGSH_ERROR_FORMAT=json" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    ARGSH_ERROR_FORMAT=json
+    ( :test::scale -r 0 ) || echo "field=${?}" >&2
+    ( :test::scale -r 20 ) || echo "cross=${?}" >&2
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  contains '"error":"invalid_value"' stderr
+  contains '"field":"replicas\|r:~int"' stderr
+  contains '"message":"replicas must be positive"' stderr
+  contains "field=5" stderr
+  contains '"error":"usage"' stderr
+  contains '"message":"--replicas \(20\) must not exceed --max \(10\)"' stderr
+  contains "cross=2" stderr
+}
+
+@test "usage: docgen md --out writes a page per command" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  local dir page section='(?:(?!\n== )[\s\S])*'
+  dir="$(mktemp -d)"
+  (
+    :test::tree docgen md --out "${dir}" --frontmatter docusaurus --sidebar
+    for page in index.md remote/index.md remote/add.md remote/_category_.json sidebar.json; do
+      printf '== %s\n' "${page}"
+      cat "${dir}/${page}"
+    done
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+  rm -rf "${dir}"
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "^${dir}/index.md\n" stdout
+  not_contains "${dir}/debug.md" stdout
+  contains "\n== index.md\n${section}\| \[\`remote\`\]\(remote/index.md\) \| Manage remotes \|" stdout
+  contains "\n== index.md\n${section}\| \[\`log\`\]\(log.md\) \| Show the log \|" stdout
+  contains "\n== remote/index.md\n${section}\| \[\`remote add\`\]\(add.md\) \| Add a remote \|" stdout
+  contains "\n== remote/add.md\n---\ntitle: \"tool remote add\"\nsidebar_label: \"add\"\nsidebar_position: 1\n" stdout
+  contains "\n== remote/add.md\n${section}\`<name>\` \*string\* \| Remote name" stdout
+  contains "## See also\n\n- \[\`tool remote\`\]\(index.md\)\n== remote/_category_.json\n" stdout
+  contains "\n== remote/_category_.json\n${section}\"label\": \"remote\"" stdout
+  contains "\n== sidebar.json\n${section}\"path\": \"remote/add.md\"" stdout
+}
+
+# ── nested completion ─────────────────────────────────────────────────
+
+@test "completion: bash walks the nested command path" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::tree completion bash
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains '":remote"\) path="remote" ;;' stdout
+  contains '"remote:add"\) path="remote add" ;;' stdout
+  contains "compgen -W '\\-\\-fetch -f \\-\\-no-fetch \\-\\-help -h'" stdout
+  contains "compgen -W 'add remove'" stdout
+  not_contains "debug" stdout
+  not_contains '":gc"' stdout
+}
+
+@test "completion: zsh defines a function per command level" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::tree completion zsh
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  contains "_tool__remote\(\) \{" stdout
+  contains "add\) _tool__remote__add ;;" stdout
+  contains "_tool__remote__add\(\) \{" stdout
+}
+
+@test "completion: fish scopes completions to the command path" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::tree completion fish
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  contains "function __tool_at" stdout
+  contains "tool -f -a 'add' -d 'Add a remote' -n '__tool_at \"remote\"'" stdout
+  contains "tool -l 'fetch' -s 'f' .*-n '__tool_at \"remote add\"'" stdout
+}
+
+# ── dynamic completion ────────────────────────────────────────────────
+
+:test::dyn() {
+  to::branch() { echo "${1}"; }
+  complete::branch() { printf '%s\n' main develop feature/x; }
+  deploy::complete::target() { printf '%s\n' web worker; }
+  deploy() {
+    local target env branch config workdir replicas
+    local -a args=(
+      'target'                "Deploy target"
+      'env|e:~enum(dev,prod)' "Environment"
+      'branch|b:~branch'      "Branch"
+      'config|c:~file'        "Config file"
+      'workdir|w:~dir'        "Working directory"
+      'replicas|r:~int'       "Replicas"
+    )
+    :args "Deploy" "${@}"
+  }
+  tool() {
+    local verbose
+    local -a args=(
+      'verbose|v:+' "Verbose output"
+    )
+    local -a usage=(
+      'deploy' "Deploy the app"
+    )
+    :usage "Tool" "${@}"
+    "${usage[@]}"
+  }
+  COMMANDNAME=(tool)
+  tool "${@}"
+}
+
+@test "complete: commands and flags of the current level" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    ( :test::dyn __complete "" )
+    ( :test::dyn __complete --v )
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "^deploy\n:none\n\\-\\-verbose\n:none\n$" stdout
+}
+
+@test "complete: values from a field hook, enum choices and a type hook" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    ( :test::dyn __complete deploy wo )
+    ( :test::dyn __complete -v deploy --env "" )
+    ( :test::dyn __complete deploy -b f )
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "^worker\n:none\ndev\nprod\n:none\nfeature/x\n:none\n$" stdout
+}
+
+@test "complete: value after = in a --flag=value word" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    ( :test::dyn __complete deploy --env=p )
+    ( :test::dyn __complete deploy --branch = "" )
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "^\\-\\-env=prod\n:none\nmain\ndevelop\nfeature/x\n:none\n$" stdout
+}
+
+@test "complete: path types fall back to the shell" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    ( :test::dyn __complete deploy --config "" )
+    ( :test::dyn __complete deploy -w "" )
+    ( :test::dyn __complete deploy --replicas "" )
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "^:file\n:dir\n:none\n$" stdout
+}
+
+@test "complete: generated scripts call __complete" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  local shell
+  for shell in bash zsh fish; do
+    (
+      :test::dyn completion "${shell}"
+    ) >"${stdout}" 2>"${stderr}" || status=$?
+
+    assert "${status}" -eq 0
+    contains "__tool_complete" stdout
+    contains " __complete " stdout
+  done
+}
+
+# ── nushell and powershell completion ─────────────────────────────────
+
+@test "completion: nu script" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::dyn completion nu
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  snapshot stdout
+}
+
+@test "completion: powershell script" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::dyn completion powershell
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  snapshot stdout
+}
+
+# ── completion install ────────────────────────────────────────────────
+
+@test "completion: install follows SHELL and is idempotent" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  local home
+  home="$(mktemp -d)"
+  (
+    HOME="${home}" SHELL=/bin/bash
+    unset XDG_DATA_HOME XDG_CONFIG_HOME BASH_COMPLETION_USER_DIR
+    ( :test::dyn completion install )
+    ( :test::dyn completion install )
+    ( :test::dyn completion install fish )
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+  local script
+  script="$(head -n 1 "${home}/.local/share/bash-completion/completions/tool")"
+  rm -rf "${home}"
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "^Installed bash completion for tool: ${home}/.local/share/bash-completion/completions/tool\n" stdout
+  contains "\nAlready installed bash completion for tool: " stdout
+  contains "\nInstalled fish completion for tool: ${home}/.config/fish/completions/tool.fish\n$" stdout
+  assert "${script}" = "# bash completion for tool"
+}
+
+@test "completion: install --dry-run into --dir writes nothing" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  local dir
+  dir="$(mktemp -d)"
+  (
+    unset FPATH
+    :test::dyn completion install zsh --dir "${dir}/zfunc" --dry-run
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+  local written=0
+  [[ ! -e "${dir}/zfunc" ]] || written=1
+  rm -rf "${dir}"
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  assert "${written}" -eq 0
+  contains "^Would install zsh completion for tool: ${dir}/zfunc/_tool\n" stdout
+  contains "fpath=\(${dir}/zfunc \\\$fpath\)" stdout
+}
+
+@test "completion: install zsh defaults to the XDG site-functions" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  local home
+  home="$(mktemp -d)"
+  (
+    export HOME="${home}" FPATH="${home}/.zfunc"
+    unset XDG_DATA_HOME
+    :test::dyn completion install zsh --dry-run
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+  rm -rf "${home}"
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "^Would install zsh completion for tool: ${home}/.local/share/zsh/site-functions/_tool\n" stdout
+  contains "fpath=\(${home}/.local/share/zsh/site-functions \\\$fpath\)" stdout
+}
+
+@test "completion: install refuses a shell it has no directory for" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::dyn completion install nu
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  contains "cannot install completion for nu" stderr
+}
+
+# ── negatable boolean flags ───────────────────────────────────────────
+
+@test "attrs: --no-<flag> switches a default-on boolean off" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local color=1
+    local -a args=(
+      'color|c:+' "Colored output"
+    )
+    :args "Negation test" --no-color
+    echo "color=${color}"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "color=0" stdout
+}
+
+@test "attrs: --flag=false and --flag=0 switch a boolean off" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local color=1 verbose=1
+    local -a args=(
+      'color|c:+' "Colored output"
+      'verbose|v:+' "Verbose output"
+    )
+    :args "Negation test" --color=false --verbose=0
+    echo "color=${color} verbose=${verbose}"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "color=0 verbose=0" stdout
+}
+
+@test "attrs: --flag=true switches a boolean on" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local verbose
+    local -a args=(
+      'verbose|v:+' "Verbose output"
+    )
+    :args "Negation test" --verbose=true
+    echo "verbose=${verbose}"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "verbose=1" stdout
+}
+
+@test "attrs: --flag=<value> rejects a non-boolean value" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local verbose=1
+    local -a args=(
+      'verbose|v:+' "Verbose output"
+    )
+    :args "Negation test" --verbose=garbage
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains "invalid type \\(boolean\\): garbage" stderr
+
+  (
+    local verbose=1
+    local -a args=(
+      'verbose|v:+' "Verbose output"
+    )
+    :args "Negation test" --verbose=
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  contains "invalid type \\(boolean\\): $" stderr
+}
+
+@test "attrs: --no-<flag> does not count as given for groups and requires" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local json yaml tls_key tls_cert=1
+    local -a args=(
+      'json|:+:%format'      "Output JSON"
+      'yaml|:+:%format'      "Output YAML"
+      'tls-cert|:+:&tls-key' "Use a client certificate"
+      'tls-key|:+'           "Client key"
+    )
+    :args "Negation test" --no-json --yaml --no-tls-cert
+    echo "json=${json} yaml=${yaml} tls_cert=${tls_cert}"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "json=0 yaml=1 tls_cert=0" stdout
+}
+
+@test "attrs: --no-<flag> is rejected for value flags" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local output
+    local -a args=(
+      'output|o' "Output file"
+    )
+    :args "Negation test" --no-output
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains "unknown flag: --no-output" stderr
+}
+
+@test "attrs: negated form shown in help for default-on flags" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local color=1 verbose
+    local -a args=(
+      'color|c:+' "Colored output"
+      'verbose|v:+' "Verbose output"
+    )
+    :args "Negation test" --help
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "\\s-c, --\\[no-\\]color" stdout
+  contains "\\s-v, --verbose" stdout
+}
+
+@test "attrs: negated form in help follows the declared default" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local color=1 verbose
+    local -a args=(
+      'color|c:+' "Colored output"
+      'verbose|v:+' "Verbose output"
+    )
+    :args "Negation test" -v --no-color --help
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "\\s-c, --\\[no-\\]color" stdout
+  contains "\\s-v, --verbose" stdout
+}
+
+@test "usage: negated form in help follows the declared default" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local version_check
+    local -a usage=(
+      'run' "Run it"
+    )
+    local -a args=(
+      'version-check|:+' "Check for updates"
+    )
+    :usage "Negation test" --version-check --help
+    "${usage[@]}"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "\\s--version-check" stdout
+  not_contains "no-\\]version-check" stdout
+}
+
+# ── counter flags ─────────────────────────────────────────────────────
+
+@test "attrs: :++ counter counts repeated short flags" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local verbose=0
+    local -a args=(
+      'verbose|v:++' "Verbosity"
+    )
+    :args "Counter test" -vvv --verbose
+    echo "verbose=${verbose}"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "verbose=4" stdout
+}
+
+@test "usage: :++ counter counts repeated short flags" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local verbose
+    local -a usage=(
+      'cmd1' "A command"
+    )
+    cmd1() { echo "verbose=${verbose}"; }
+    local -a args=(
+      'verbose|v:++' "Verbosity"
+    )
+    :usage "Counter test" -vvv cmd1
+    "${usage[@]}"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "verbose=3" stdout
+}
+
+@test "attrs: :++ counter rejects a value" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local verbose=0
+    local -a args=(
+      'verbose|v:++' "Verbosity"
+    )
+    :args "Counter test" --verbose=2
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains "counter flag does not take a value: --verbose" stderr
+}
+
+@test "attrs: --no-<flag> resets a counter" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local verbose=2
+    local -a args=(
+      'verbose|v:++' "Verbosity"
+    )
+    :args "Counter test" --no-verbose -v
+    echo "verbose=${verbose}"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "verbose=1" stdout
+}
+
+@test "attrs: :++ counter shown as repeatable in help" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local verbose=0
+    local -a args=(
+      'verbose|v:++' "Verbosity"
+    )
+    :args "Counter test" --help
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "\\s-v, --verbose \\(repeatable\\)" stdout
+}
+
+# ── map flags ─────────────────────────────────────────────────────────
+
+@test "attrs: local -A flag collects key=value pairs" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local -A label
+    local -a args=(
+      'label|l' "Labels"
+    )
+    :args "Map test" --label team=core -l tier=1 --label=note=a=b
+    echo "team=${label[team]} tier=${label[tier]} note=${label[note]}"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "team=core tier=1 note=a=b" stdout
+}
+
+@test "attrs: map flag values are type checked" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local -A limit
+    local -a args=(
+      'limit|l:~int' "Limits"
+    )
+    :args "Map test" --limit cpu=two
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains "invalid type \\(int\\): two" stderr
+}
+
+@test "attrs: map flag rejects a malformed pair" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local -A label
+    local -a args=(
+      'label|l' "Labels"
+    )
+    :args "Map test" --label team
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains "invalid key=value pair: team" stderr
+}
+
+@test "attrs: map flag shown with key= in help" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local -A label
+    local -a args=(
+      'label|l' "Labels"
+    )
+    :args "Map test" --help
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "\\s-l, --label \\.\\.\\.key=string" stdout
+}
+
+# ── extended field types ──────────────────────────────────────────────
+
+@test "attrs: duration and size fields are converted" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local timeout limit
+    local -a args=(
+      'timeout|t:~duration' "Timeout"
+      'limit|l:~size' "Limit"
+    )
+    :args "Types test" --timeout 2m --limit 1KiB
+    echo "timeout=${timeout} limit=${limit}"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "timeout=120 limit=1024" stdout
+}
+
+@test "attrs: invalid url is rejected" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local endpoint
+    local -a args=(
+      'endpoint|e:~url' "Endpoint"
+    )
+    :args "Types test" --endpoint example.com
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains "invalid type \\(url\\): example.com" stderr
+}
+
+@test "attrs: dir must exist" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local workdir
+    local -a args=(
+      'workdir|w:~dir' "Working directory"
+    )
+    :args "Types test" --workdir /nonexistent/dir
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains "directory not found: /nonexistent/dir" stderr
+}
+
+@test "attrs: path(new) rejects an existing path" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local out
+    local -a args=(
+      'out|o:~path(new)' "Output"
+    )
+    :args "Types test" --out "${BATS_TEST_DIRNAME}/./args.bats"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 2
+  is_empty stdout
+  contains "path already exists: .*/args.bats" stderr
+}
+
+@test "attrs: path is normalized" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    local out
+    local -a args=(
+      'out|o:~path(new)' "Output"
+    )
+    :args "Types test" --out /tmp/a/../b/./new.txt
+    echo "out=${out}"
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "out=/tmp/b/new.txt" stdout
+}
+
+# ── completion/man/md/rst/yaml builtin tests ──────────────────────────
+# These are builtin-only features — skip in pure bash mode.
+
+@test "usage: completion bash generates script" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::usage completion bash
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "complete -o default -F" stdout
+  contains "COMPREPLY" stdout
+}
+
+@test "usage: completion zsh generates script" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::usage completion zsh
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "#compdef" stdout
+  contains "_arguments" stdout
+}
+
+@test "usage: completion fish generates script" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::usage completion fish
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "complete -c" stdout
+}
+
+@test "usage: completion --help shows shell list" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::usage completion --help
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "bash.*Bash completion" stdout
+  contains "zsh.*Zsh completion" stdout
+  contains "fish.*Fish completion" stdout
+  contains "nu.*Nushell completion" stdout
+  contains "powershell.*PowerShell completion" stdout
+  contains "completion install \[bash\|zsh\|fish\]" stdout
+}
+
+@test "usage: completion invalid shell fails" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::usage completion tcsh
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -ne 0
+}
+
+@test "usage: docgen man generates troff output" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::usage docgen man
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains '\.TH' stdout
+  contains '\.SH NAME' stdout
+  contains '\.SH COMMANDS' stdout
+  contains '\.SH OPTIONS' stdout
+}
+
+@test "usage: docgen --help shows format list" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::usage docgen --help
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "man.*Man page" stdout
+  contains "md.*Markdown" stdout
+  contains "rst.*reStructuredText" stdout
+  contains "yaml.*YAML" stdout
+}
+
+@test "usage: docgen md generates markdown output" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::usage docgen md
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains '## Synopsis' stdout
+  contains '## Commands' stdout
+  contains '## Options' stdout
+  contains '\| Command \| Description \|' stdout
+}
+
+@test "usage: docgen rst generates restructuredtext output" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::usage docgen rst
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains 'Synopsis' stdout
+  contains '\.\. code-block:: bash' stdout
+  contains 'Commands' stdout
+  contains 'Options' stdout
+}
+
+@test "usage: docgen yaml generates yaml output" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::usage docgen yaml
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains 'name:' stdout
+  contains 'description:' stdout
+  contains 'commands:' stdout
+  contains 'options:' stdout
+}
+
+@test "usage: docgen invalid format fails" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::usage docgen html
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -ne 0
+}
+
+@test "usage: completion includes subcommands from usage array" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::usage completion bash
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  contains "cmd1" stdout
+  contains "cmd2" stdout
+}
+
+@test "usage: completion includes flags from args array" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::usage completion bash
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  contains "\-\-verbose" stdout
+  contains "\-\-config" stdout
+  contains "\-\-help" stdout
+}
+
+# ── coverage: no visible subcommands + long-only flag ────────────────
+# These tests exercise branches unreachable from :test::usage (which has
+# subcommands and flags with short options):
+#   - Flag without short option (flag.short == None)
+#   - Empty subcommands list (cmds.is_empty() == true)
+#   - Multi-line title with blank line (man .PP path)
+
+@test "usage: completion bash with no subcommands and long-only flag" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::nosub completion bash
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "complete -o default -F" stdout
+  contains "\-\-longonly" stdout
+  contains "\-\-help" stdout
+}
+
+@test "usage: completion zsh with no subcommands and long-only flag" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::nosub completion zsh
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "#compdef" stdout
+  contains "\-\-longonly" stdout
+  # No _describe/commands block when subcommands are empty
+}
+
+@test "usage: completion fish with no subcommands and long-only flag" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::nosub completion fish
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains "complete -c" stdout
+  contains "longonly" stdout
+}
+
+@test "usage: docgen man with no subcommands and long-only flag" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::nosub docgen man
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains '\.TH' stdout
+  contains '\.SH OPTIONS' stdout
+  # Long-only non-boolean flag (no short option)
+  contains '\\-\\-longonly.*string' stdout
+  # Multi-line title with blank line produces .PP
+  contains '\.PP' stdout
+}
+
+@test "usage: docgen md with no subcommands and long-only flag" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::nosub docgen md
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains '## Synopsis' stdout
+  contains '## Options' stdout
+  # No ## Commands section
+  contains '\-\-longonly' stdout
+}
+
+@test "usage: docgen rst with no subcommands and long-only flag" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::nosub docgen rst
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains 'Synopsis' stdout
+  contains 'Options' stdout
+  contains '\-\-longonly' stdout
+}
+
+@test "usage: docgen yaml with no subcommands and long-only flag" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::nosub docgen yaml
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains 'name:' stdout
+  contains 'options:' stdout
+  contains 'longonly' stdout
+}
+
+@test "usage: docgen yaml with group separators in usage array" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::fmt1 docgen yaml
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains 'commands:' stdout
+  # Group separators ('-') should be filtered, only real commands listed
+  contains 'cmd1' stdout
+  contains 'noop' stdout
+}
+
+# ── LLM tool schema tests ──────────────────────────────────────────
+
+@test "usage: docgen llm claude generates anthropic tool schema" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::usage docgen llm claude
+  ) >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  is_empty stderr
+  contains '"input_schema":' stdout
+  contains '"properties":' stdout
+  contains '"verbose":' stdout
+}
+
+@test "usage: docgen llm openai generates openai tool schema" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  (
+    :test::usage docgen llm openai
   ) >"${stdout}" 2>"${stderr}" || status=$?
 
   assert "${status}" -eq 0
@@ -2846,6 +5073,20 @@ source "${PATH_FIXTURES}/fmt.sh"
   contains 'building' stdout
 }
 
+@test "usage: mcp tools/call passes positionals after --" {
+  if [[ "${ARGSH_BUILTIN_TEST:-}" != "1" ]]; then set +u; skip "builtin test"; fi
+  local fixture="${PATH_FIXTURES}/mcp_test.sh"
+  [[ -x "${fixture}" ]] || skip "mcp_test.sh fixture not found"
+  printf '%s\n' \
+    '{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}' \
+    '{"jsonrpc":"2.0","method":"notifications/initialized"}' \
+    '{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"args_sh_copy","arguments":{"src":["a.txt","-b.txt"],"dest":"out"}}}' \
+  | "${fixture}" mcp >"${stdout}" 2>"${stderr}" || status=$?
+
+  assert "${status}" -eq 0
+  contains 'copy a.txt -b.txt to out' stdout
+}
+
 # ── :^ inherited modifier ────────────────────────────────
 
 @test ":^ inherited: non-:^ wins over :^ for same field" {
@@ -19,9 +19,10 @@ import() { declare -A _i; (( ${_i[${1}]:-} )) || { _i[${1}]=1; . "${BASH_SOURCE[
 # Falls back to pure bash if unavailable.
 # Shared builtins list — also used by argsh::builtin::try() in main.sh.
 # obfus ignore variable
-declare -ga __ARGSH_BUILTINS=(:usage :usage::help :usage::completion :usage::docgen :usage::mcp :args
+declare -ga __ARGSH_BUILTINS=(:usage :usage::help :usage::complete :usage::completion :usage::docgen :usage::mcp :usage::version :args
   is::array is::uninitialized is::set is::tty
   args::field_name to::int to::float to::boolean to::file to::string
+  to::duration to::size to::url to::dir to::path to::json
   lib::pull lib::push
   # argsh disable=AG013
   import import::clear)
@@ -147,7 +148,8 @@ if ! (( ARGSH_BUILTIN )); then
 #     "${usage[@]}"
 #   }
 #   main::up() { echo "up"; }
-# Suggest closest command from usage array (Levenshtein distance ≤ 2).
+# Suggest closest command from usage array (Levenshtein distance ≤ 2, or a
+# truncated name of at least 3 characters; ties go to the prefix match).
 # @arg $1 string User input
 # @arg $2+ string Usage array entries (pairs: name desc name desc ...)
 # @stdout Suggested command name, or empty
@@ -155,9 +157,9 @@ if ! (( ARGSH_BUILTIN )); then
 :usage::suggest() {
   local input="${1}"; shift
   local -a entries=("${@}")
-  local best="" best_dist=999
+  local best="" best_dist=999 best_prefix=0
 
-  local i alias_str a dist
+  local i alias_str a dist prefix max_len
   local -a _sug_aliases=()
   for (( i=0; i < ${#entries[@]}; i+=2 )); do
     alias_str="${entries[i]/:*}"
@@ -165,22 +167,25 @@ if ! (( ARGSH_BUILTIN )); then
     alias_str="${alias_str#\#}"
     IFS='|' read -ra _sug_aliases <<< "${alias_str}"
     for a in "${_sug_aliases[@]}"; do
+      [[ "${a}" != "${input}" ]] || return 0
       dist="$(:usage::levenshtein "${input}" "${a}")"
-      if (( dist < best_dist )); then
+      prefix=0
+      if (( ${#input} >= 3 )) && [[ "${a}" == "${input}"* ]]; then
+        prefix=1
+      fi
+      # Threshold: distance ≤ 2 AND ≤ 40% of the longer string
+      max_len=${#input}
+      (( ${#a} > max_len )) && max_len=${#a}
+      (( prefix || (dist <= 2 && dist * 100 <= max_len * 40) )) || continue
+      if (( dist < best_dist || (dist == best_dist && prefix > best_prefix) )); then
         best_dist="${dist}"
         best="${a}"
+        best_prefix="${prefix}"
       fi
     done
   done
 
-  # Threshold: distance ≤ 2 AND ≤ 40% of the longer string
-  if (( best_dist > 0 && best_dist <= 2 )); then
-    local max_len=${#input}
-    (( ${#best} > max_len )) && max_len=${#best}
-    if (( best_dist * 100 <= max_len * 40 )); then
-      echo "${best}"
-    fi
-  fi
+  [[ -z "${best}" ]] || echo "${best}"
 }
 
 # Levenshtein edit distance between two strings.
@@ -519,6 +524,7 @@ if ! (( ARGSH_BUILTIN )); then
   :args::check_required_flags
   [[ ${#cli[@]} -eq 0 ]] ||
     :args::error_usage "too many arguments: ${cli[*]}"
+  :args::validate "${FUNCNAME[1]:-}"
 
   # Process trace: record variable state after parsing.
   declare -F __argsh_trace_args &>/dev/null && __argsh_trace_args "${title}"
@@ -674,6 +680,36 @@ if ! (( ARGSH_BUILTIN )); then
   done
 }
 
+# @description
+#   Call the validation hooks of a function once every field is set:
+#   `<func>::validate::<name>` for each set field, with its value(s) as
+#   arguments, then the cross-field `<func>::validate`. A hook rejects by
+#   returning non-zero; its stderr becomes the error message.
+# @arg $1 string The function that called :args
+# @set args array [get] The arguments to parse
+# @internal
+:args::validate() {
+  local _fn="${1}" _name _msg _status _i
+  [[ -n "${_fn}" ]] || return 0
+
+  for (( _i=0; _i < ${#args[@]}; _i+=2 )); do
+    [[ "${args[_i]}" != "-" ]] || continue
+    _name="$(args::field_name "${args[_i]}")"
+    declare -F "${_fn}::validate::${_name}" &>/dev/null || continue
+    ! is::uninitialized "${_name}" || is::array "${_name}" || continue
+    local -n _ref="${_name}"
+    _status=0
+    { _msg="$("${_fn}::validate::${_name}" "${_ref[@]}" 2>&1 >&3 3>&-)"; } 3>&1 || _status="${?}"
+    (( _status == 0 )) ||
+      :args::error_usage "${_msg:-"invalid value for $(args::field_name "${args[_i]}" 0): ${_ref[*]}"}"
+  done
+
+  declare -F "${_fn}::validate" &>/dev/null || return 0
+  _status=0
+  { _msg="$("${_fn}::validate" 2>&1 >&3 3>&-)"; } 3>&1 || _status="${?}"
+  (( _status == 0 )) || :args::error_usage "${_msg:-validation failed}"
+}
+
 # @description
 #   Set the flag value
 # @arg $1 string The field to set
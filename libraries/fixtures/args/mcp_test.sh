@@ -23,6 +23,7 @@ main() {
     'build@destructive'  "Build the project"
     'cluster'            "Cluster management"
     'status@json'        "Get status as JSON"
+    'copy'               "Copy files"
   )
   :usage "My test application" "${@}"
   "${usage[@]}"
@@ -53,6 +54,16 @@ status() {
   echo '{"status":"ok","uptime":42}'
 }
 
+copy() {
+  local dest
+  local -a src args=(
+    'src:!' "Files to copy"
+    'dest'  "Destination"
+  )
+  :args "Copy files" "${@}"
+  echo "copy ${src[*]} to ${dest}"
+}
+
 cluster() {
   local -a usage=(
     'up'   "Start cluster"
@@ -90,6 +90,16 @@ contains() {
   }
 }
 
+not_contains() {
+  local check="${1}"
+  local -n file="${2}"
+  ! $grep -qzP "${check}" "${file}" || {
+    echo "■■ ${file} contains ${check}"
+    cat "${file}"
+    return 1
+  }
+}
+
 is::uninitialized() {
   local var
   for var in "${@}"; do